@@ -0,0 +1,952 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use owo_colors::{AnsiColors, OwoColorize};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::cli::PollActionsArgs;
+use crate::output::{OutputFormat, Record, Report, render};
+use crate::scope::{PollScope, ScopeNames, resolve_channel_name, resolve_server};
+use crate::utils::{color_stage, format_channel, format_number, format_timestamp, pct};
+use crate::window::TimeWindow;
+
+pub async fn run_poll_actions(
+    pool: &PgPool,
+    window: TimeWindow,
+    args: &PollActionsArgs,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut names = ScopeNames::default();
+    let mut server_id = None;
+    if let Some(id_or_slug) = &args.server_id {
+        let (id, name) = resolve_server(pool, id_or_slug).await?;
+        server_id = Some(id);
+        names.server_name = Some(name);
+    }
+    if let Some(channel_id) = args.channel_id {
+        names.channel_name = resolve_channel_name(pool, channel_id).await?;
+    }
+
+    let scope = PollScope {
+        server_id,
+        channel_id: args.channel_id,
+        ..PollScope::new(&window)
+    };
+    let report = collect_actions(pool, window, scope, names).await?;
+    render(&report, format)
+}
+
+async fn collect_actions(
+    pool: &PgPool,
+    window: TimeWindow,
+    scope: PollScope,
+    names: ScopeNames,
+) -> Result<PollActionsReport> {
+    let breakdown_query = sqlx::query_as::<_, ActionTypeStage>(
+        r#"
+        SELECT COALESCE(pa."actionType"::text, 'none') AS action_type,
+               p.stage::text AS stage,
+               COUNT(*)::bigint AS proposals
+        FROM poll p
+        JOIN channel c ON c.id = p."channelId"
+        LEFT JOIN poll_action pa ON pa."pollId" = p.id
+        WHERE p."pollType" = 'proposal'
+          AND p."createdAt" >= $1 AND p."createdAt" < $5
+          AND ($2::uuid IS NULL OR p."channelId" = $2)
+          AND ($3::uuid IS NULL OR p.id = $3)
+          AND ($4::uuid IS NULL OR c."serverId" = $4)
+        GROUP BY 1, 2
+        ORDER BY 1, 2
+        "#,
+    )
+    .bind(scope.since)
+    .bind(scope.channel_id)
+    .bind(scope.poll_id)
+    .bind(scope.server_id)
+    .bind(scope.until)
+    .fetch_all(pool);
+
+    // `create-role` actions aren't linked to the role they create, so the
+    // target is the first role in the server with the proposed name created
+    // after the proposal. The decision time follows lifecycle metrics.
+    let ratified_query = sqlx::query_as::<_, RatifiedAction>(
+        r#"
+        SELECT p.id AS poll_id,
+               p."channelId" AS channel_id,
+               c.name AS channel_name,
+               s.name AS server_name,
+               pa."actionType"::text AS action_type,
+               LEAST(p."updatedAt", COALESCE(pc."closingAt", p."updatedAt")) AS decided_at,
+               r.id AS action_role_id,
+               r.name AS proposed_name,
+               r.color AS proposed_color,
+               target.id AS role_id,
+               target.name AS role_name,
+               target.color AS role_color,
+               target."updatedAt" AS role_updated_at
+        FROM poll p
+        JOIN poll_config pc ON pc."pollId" = p.id
+        JOIN channel c ON c.id = p."channelId"
+        JOIN server s ON s.id = c."serverId"
+        JOIN poll_action pa ON pa."pollId" = p.id
+        LEFT JOIN poll_action_role r ON r."pollActionId" = pa.id
+        LEFT JOIN LATERAL (
+            SELECT sr.id, sr.name, sr.color, sr."updatedAt"
+            FROM server_role sr
+            WHERE (pa."actionType" = 'change-role' AND sr.id = r."serverRoleId")
+               OR (pa."actionType" = 'create-role'
+                   AND sr."serverId" = c."serverId"
+                   AND sr.name = r.name
+                   AND sr."createdAt" >= p."createdAt")
+            ORDER BY sr."createdAt"
+            LIMIT 1
+        ) target ON TRUE
+        WHERE p."pollType" = 'proposal'
+          AND p.stage = 'ratified'
+          AND pa."actionType" IN ('change-role', 'create-role')
+          AND p."createdAt" >= $1 AND p."createdAt" < $5
+          AND ($2::uuid IS NULL OR p."channelId" = $2)
+          AND ($3::uuid IS NULL OR p.id = $3)
+          AND ($4::uuid IS NULL OR c."serverId" = $4)
+        ORDER BY decided_at DESC
+        "#,
+    )
+    .bind(scope.since)
+    .bind(scope.channel_id)
+    .bind(scope.poll_id)
+    .bind(scope.server_id)
+    .bind(scope.until)
+    .fetch_all(pool);
+
+    let (breakdown, ratified) = tokio::try_join!(breakdown_query, ratified_query)?;
+
+    let action_role_ids: Vec<Uuid> = ratified.iter().filter_map(|a| a.action_role_id).collect();
+    let role_ids: Vec<Uuid> = ratified.iter().filter_map(|a| a.role_id).collect();
+
+    let proposed_permissions_query = sqlx::query_as::<_, ProposedPermission>(
+        r#"
+        SELECT "pollActionRoleId" AS action_role_id,
+               action::text AS action,
+               subject::text AS subject,
+               "changeType"::text AS change_type
+        FROM poll_action_permission
+        WHERE "pollActionRoleId" = ANY($1)
+        ORDER BY subject, action
+        "#,
+    )
+    .bind(&action_role_ids)
+    .fetch_all(pool);
+
+    let proposed_members_query = sqlx::query_as::<_, ProposedMember>(
+        r#"
+        SELECT m."pollActionRoleId" AS action_role_id,
+               m."userId" AS user_id,
+               u.name AS user_name,
+               m."changeType"::text AS change_type
+        FROM poll_action_role_member m
+        JOIN "user" u ON u.id = m."userId"
+        WHERE m."pollActionRoleId" = ANY($1)
+        ORDER BY u.name
+        "#,
+    )
+    .bind(&action_role_ids)
+    .fetch_all(pool);
+
+    let role_permissions_query = sqlx::query_as::<_, RolePermission>(
+        r#"
+        SELECT "serverRoleId" AS role_id,
+               action::text AS action,
+               subject::text AS subject,
+               "createdAt" AS created_at
+        FROM server_role_permission
+        WHERE "serverRoleId" = ANY($1)
+        "#,
+    )
+    .bind(&role_ids)
+    .fetch_all(pool);
+
+    let role_members_query = sqlx::query_as::<_, RoleMember>(
+        r#"
+        SELECT "serverRoleId" AS role_id, "userId" AS user_id
+        FROM server_role_members_user
+        WHERE "serverRoleId" = ANY($1)
+        "#,
+    )
+    .bind(&role_ids)
+    .fetch_all(pool);
+
+    let (proposed_permissions, proposed_members, role_permissions, role_members) = tokio::try_join!(
+        proposed_permissions_query,
+        proposed_members_query,
+        role_permissions_query,
+        role_members_query,
+    )?;
+
+    let current = CurrentRoles::new(role_permissions, role_members);
+    let mut permissions_by_action: HashMap<Uuid, Vec<ProposedPermission>> = HashMap::new();
+    for permission in proposed_permissions {
+        permissions_by_action
+            .entry(permission.action_role_id)
+            .or_default()
+            .push(permission);
+    }
+    let mut members_by_action: HashMap<Uuid, Vec<ProposedMember>> = HashMap::new();
+    for member in proposed_members {
+        members_by_action
+            .entry(member.action_role_id)
+            .or_default()
+            .push(member);
+    }
+
+    let verifications: Vec<ActionVerification> = ratified
+        .into_iter()
+        .map(|action| {
+            let permissions = action
+                .action_role_id
+                .and_then(|id| permissions_by_action.remove(&id))
+                .unwrap_or_default();
+            let members = action
+                .action_role_id
+                .and_then(|id| members_by_action.remove(&id))
+                .unwrap_or_default();
+            verify(action, &permissions, &members, &current)
+        })
+        .collect();
+
+    let mut action_types: BTreeMap<String, ActionTypeCount> = BTreeMap::new();
+    let mut proposals = 0;
+    for row in breakdown {
+        proposals += row.proposals;
+        let entry = action_types
+            .entry(row.action_type.clone())
+            .or_insert_with(|| ActionTypeCount {
+                action_type: row.action_type.clone(),
+                proposals: 0,
+                by_stage: BTreeMap::new(),
+            });
+        entry.proposals += row.proposals;
+        entry.by_stage.insert(row.stage, row.proposals);
+    }
+    let mut action_types: Vec<ActionTypeCount> = action_types.into_values().collect();
+    action_types.sort_by_key(|count| std::cmp::Reverse(count.proposals));
+
+    Ok(PollActionsReport {
+        window_days: window.days(),
+        window,
+        server_id: scope.server_id,
+        channel_id: scope.channel_id,
+        names,
+        proposals,
+        action_types,
+        verifications,
+    })
+}
+
+/// What each target role looks like today.
+struct CurrentRoles {
+    /// Permission `(action, subject)` → when it was granted
+    permissions: HashMap<Uuid, HashMap<(String, String), NaiveDateTime>>,
+    members: HashMap<Uuid, HashSet<Uuid>>,
+}
+
+impl CurrentRoles {
+    fn new(permissions: Vec<RolePermission>, members: Vec<RoleMember>) -> Self {
+        let mut current = Self {
+            permissions: HashMap::new(),
+            members: HashMap::new(),
+        };
+        for permission in permissions {
+            current
+                .permissions
+                .entry(permission.role_id)
+                .or_default()
+                .insert(
+                    (permission.action, permission.subject),
+                    permission.created_at,
+                );
+        }
+        for member in members {
+            current
+                .members
+                .entry(member.role_id)
+                .or_default()
+                .insert(member.user_id);
+        }
+        current
+    }
+}
+
+/// Compares each change a ratified action describes with the role today.
+///
+/// Membership rows carry no timestamps and removed permissions leave no
+/// trace, so a missing change can't always be told apart from one that was
+/// applied and later reverted. Only a role renamed or recoloured since the
+/// decision, or a removed permission granted again after it, counts as
+/// evidence of a later change.
+fn verify(
+    action: RatifiedAction,
+    permissions: &[ProposedPermission],
+    members: &[ProposedMember],
+    current: &CurrentRoles,
+) -> ActionVerification {
+    let mut checks = Vec::new();
+    let mut unchecked = None;
+
+    match (action.action_role_id, action.role_id) {
+        // Deleting a server role cascades to the action's role, so a
+        // `change-role` action without one targeted a role that is gone
+        (None, _) if action.action_type == "change-role" => {
+            unchecked = Some((
+                ActionStatus::Undone,
+                "target role has since been deleted".to_string(),
+            ));
+        }
+        (None, _) => {
+            unchecked = Some((
+                ActionStatus::NotApplied,
+                "no role details recorded".to_string(),
+            ));
+        }
+        (Some(_), None) if action.action_type == "create-role" => {
+            unchecked = Some((
+                ActionStatus::NotApplied,
+                format!(
+                    "no role named '{}' was created in the server",
+                    action.proposed_name.as_deref().unwrap_or_default()
+                ),
+            ));
+        }
+        (Some(_), None) => {
+            unchecked = Some((
+                ActionStatus::NotApplied,
+                "target role not found".to_string(),
+            ));
+        }
+        (Some(_), Some(role_id)) => {
+            let changed_since = action
+                .role_updated_at
+                .is_some_and(|updated_at| updated_at > action.decided_at);
+            if let (Some(proposed), Some(role_name)) = (&action.proposed_name, &action.role_name) {
+                checks.push(RoleCheck::property(
+                    "name",
+                    proposed,
+                    role_name,
+                    changed_since,
+                ));
+            }
+            if let (Some(proposed), Some(role_color)) = (&action.proposed_color, &action.role_color)
+            {
+                checks.push(RoleCheck::property(
+                    "color",
+                    proposed,
+                    role_color,
+                    changed_since,
+                ));
+            }
+
+            let granted = current.permissions.get(&role_id);
+            for permission in permissions {
+                let key = (permission.action.clone(), permission.subject.clone());
+                let granted_at = granted.and_then(|granted| granted.get(&key));
+                let change = format!(
+                    "{}{} {}",
+                    sign(&permission.change_type),
+                    permission.action,
+                    permission.subject
+                );
+                checks.push(match (permission.change_type.as_str(), granted_at) {
+                    ("add", Some(_)) => RoleCheck::held("permission", change),
+                    ("add", None) => RoleCheck::missing("permission", change, "not granted", false),
+                    (_, None) => RoleCheck::held("permission", change),
+                    (_, Some(granted_at)) => {
+                        let later = *granted_at > action.decided_at;
+                        let detail = if later {
+                            "granted again after ratification"
+                        } else {
+                            "still granted"
+                        };
+                        RoleCheck::missing("permission", change, detail, later)
+                    }
+                });
+            }
+
+            let role_members = current.members.get(&role_id);
+            for member in members {
+                let is_member = role_members.is_some_and(|ids| ids.contains(&member.user_id));
+                let change = format!("{}{}", sign(&member.change_type), member.user_name);
+                let adding = member.change_type == "add";
+                checks.push(if is_member == adding {
+                    RoleCheck::held("member", change)
+                } else if adding {
+                    RoleCheck::missing("member", change, "not a member", false)
+                } else {
+                    RoleCheck::missing("member", change, "still a member", false)
+                });
+            }
+        }
+    }
+
+    let (status, note) = match unchecked {
+        Some((status, note)) => (status, Some(note)),
+        None => (status_of(&checks), None),
+    };
+
+    ActionVerification {
+        poll_id: action.poll_id,
+        channel_id: action.channel_id,
+        channel_name: action.channel_name,
+        server_name: action.server_name,
+        action_type: action.action_type,
+        decided_at: action.decided_at.and_utc(),
+        role_id: action.role_id,
+        role_name: action.role_name.or(action.proposed_name),
+        status,
+        note,
+        checks,
+    }
+}
+
+fn status_of(checks: &[RoleCheck]) -> ActionStatus {
+    let held = checks.iter().filter(|check| check.holds).count();
+    if held == checks.len() {
+        ActionStatus::Applied
+    } else if checks.iter().any(|check| check.changed_later) {
+        ActionStatus::Undone
+    } else if held == 0 {
+        ActionStatus::NotApplied
+    } else {
+        ActionStatus::Partial
+    }
+}
+
+fn sign(change_type: &str) -> &'static str {
+    if change_type == "add" { "+" } else { "-" }
+}
+
+// ---------------------------------------------------------------------------
+// Rows
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, FromRow)]
+struct ActionTypeStage {
+    action_type: String,
+    stage: String,
+    proposals: i64,
+}
+
+#[derive(Debug, FromRow)]
+struct RatifiedAction {
+    poll_id: Uuid,
+    channel_id: Uuid,
+    channel_name: String,
+    server_name: String,
+    action_type: String,
+    decided_at: NaiveDateTime,
+    action_role_id: Option<Uuid>,
+    proposed_name: Option<String>,
+    proposed_color: Option<String>,
+    role_id: Option<Uuid>,
+    role_name: Option<String>,
+    role_color: Option<String>,
+    role_updated_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, FromRow)]
+struct ProposedPermission {
+    action_role_id: Uuid,
+    action: String,
+    subject: String,
+    change_type: String,
+}
+
+#[derive(Debug, FromRow)]
+struct ProposedMember {
+    action_role_id: Uuid,
+    user_id: Uuid,
+    user_name: String,
+    change_type: String,
+}
+
+#[derive(Debug, FromRow)]
+struct RolePermission {
+    role_id: Uuid,
+    action: String,
+    subject: String,
+    created_at: NaiveDateTime,
+}
+
+#[derive(Debug, FromRow)]
+struct RoleMember {
+    role_id: Uuid,
+    user_id: Uuid,
+}
+
+// ---------------------------------------------------------------------------
+// Report
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PollActionsReport {
+    window_days: i64,
+    #[serde(flatten)]
+    window: TimeWindow,
+    server_id: Option<Uuid>,
+    channel_id: Option<Uuid>,
+    #[serde(flatten)]
+    names: ScopeNames,
+    proposals: i64,
+    /// Most common first; proposals without a `poll_action` count as `none`
+    action_types: Vec<ActionTypeCount>,
+    /// Ratified `change-role` and `create-role` actions, latest decision first
+    verifications: Vec<ActionVerification>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ActionTypeCount {
+    action_type: String,
+    proposals: i64,
+    /// Proposals per current stage
+    by_stage: BTreeMap<String, i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum ActionStatus {
+    /// Every described change holds today
+    Applied,
+    /// Some changes hold and none show signs of being reverted
+    Partial,
+    /// None of the changes hold
+    NotApplied,
+    /// At least one change was reverted after ratification
+    Undone,
+}
+
+impl ActionStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Applied => "applied",
+            Self::Partial => "partial",
+            Self::NotApplied => "notApplied",
+            Self::Undone => "undone",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Applied => "applied",
+            Self::Partial => "partially applied",
+            Self::NotApplied => "never applied",
+            Self::Undone => "later undone",
+        }
+    }
+
+    fn color(&self) -> AnsiColors {
+        match self {
+            Self::Applied => AnsiColors::Green,
+            Self::Partial => AnsiColors::Yellow,
+            Self::NotApplied => AnsiColors::Red,
+            Self::Undone => AnsiColors::Magenta,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ActionVerification {
+    poll_id: Uuid,
+    channel_id: Uuid,
+    channel_name: String,
+    server_name: String,
+    action_type: String,
+    /// When the proposal left `voting`
+    decided_at: DateTime<Utc>,
+    /// The server role the action applies to, `None` when it can't be found
+    role_id: Option<Uuid>,
+    role_name: Option<String>,
+    status: ActionStatus,
+    /// Why the changes couldn't be checked, if they couldn't
+    note: Option<String>,
+    checks: Vec<RoleCheck>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RoleCheck {
+    /// `name`, `color`, `permission` or `member`
+    target: &'static str,
+    change: String,
+    holds: bool,
+    /// What the role has instead, when the change doesn't hold
+    detail: Option<String>,
+    /// The role shows signs of being changed back after ratification
+    changed_later: bool,
+}
+
+impl RoleCheck {
+    fn held(target: &'static str, change: String) -> Self {
+        Self {
+            target,
+            change,
+            holds: true,
+            detail: None,
+            changed_later: false,
+        }
+    }
+
+    fn missing(target: &'static str, change: String, detail: &str, changed_later: bool) -> Self {
+        Self {
+            target,
+            change,
+            holds: false,
+            detail: Some(detail.to_string()),
+            changed_later,
+        }
+    }
+
+    /// A name or color check. The role's `updatedAt` only shows it was edited
+    /// after the decision, which is taken as the change being reverted.
+    fn property(target: &'static str, proposed: &str, current: &str, changed_since: bool) -> Self {
+        if proposed == current {
+            return Self::held(target, proposed.to_string());
+        }
+        let detail = if changed_since {
+            format!("now '{}', changed after ratification", current)
+        } else {
+            format!("still '{}'", current)
+        };
+        Self::missing(target, proposed.to_string(), &detail, changed_since)
+    }
+}
+
+impl Report for PollActionsReport {
+    const KIND: &'static str = "poll-actions";
+    const SCHEMA_VERSION: u32 = 1;
+
+    fn render_text(&self) {
+        let scope = self
+            .names
+            .describe()
+            .map(|scope| format!(", {}", scope))
+            .unwrap_or_default();
+        println!(
+            "\n{} {}",
+            "Poll Actions".bold().underline(),
+            format!("(proposals from {}{})", self.window.describe(), scope).dimmed()
+        );
+        println!(
+            "{} {} proposals, {} ratified role actions",
+            "•".cyan(),
+            format_number(self.proposals).bold(),
+            format_number(self.verifications.len() as i64).bold()
+        );
+
+        println!("\n{}", "Action Types".bold());
+        if self.action_types.is_empty() {
+            println!("{}", "  none".dimmed());
+        }
+        for count in &self.action_types {
+            let stages = count
+                .by_stage
+                .iter()
+                .map(|(stage, proposals)| format!("{} {}", color_stage(stage), proposals))
+                .collect::<Vec<_>>()
+                .join(", ");
+            println!(
+                "  {} {:<14} {:>6} ({:>5.1}%)  {}",
+                "→".dimmed(),
+                count.action_type,
+                count.proposals.to_string().bold(),
+                pct(count.proposals, self.proposals),
+                stages
+            );
+        }
+
+        if self.verifications.is_empty() {
+            return;
+        }
+
+        println!(
+            "\n{} {}",
+            "Ratified Role Actions".bold(),
+            "(compared with server roles today)".dimmed()
+        );
+        for status in [
+            ActionStatus::Applied,
+            ActionStatus::Partial,
+            ActionStatus::NotApplied,
+            ActionStatus::Undone,
+        ] {
+            let count = self
+                .verifications
+                .iter()
+                .filter(|verification| verification.status == status)
+                .count();
+            println!(
+                "  {} {} {:>6}",
+                "→".dimmed(),
+                format!("{:<17}", status.label())
+                    .color(status.color())
+                    .bold(),
+                count
+            );
+        }
+
+        let flagged: Vec<&ActionVerification> = self
+            .verifications
+            .iter()
+            .filter(|verification| verification.status != ActionStatus::Applied)
+            .collect();
+        if flagged.is_empty() {
+            return;
+        }
+        println!("\n{}", "Needs Attention".bold());
+        for verification in flagged {
+            println!(
+                "{} poll {} in {} — {} {}, ratified {}: {}",
+                "•".cyan(),
+                verification.poll_id,
+                format_channel(&verification.channel_name, &verification.server_name),
+                verification.action_type,
+                verification
+                    .role_name
+                    .as_deref()
+                    .unwrap_or("unknown role")
+                    .bold(),
+                format_timestamp(verification.decided_at, "%Y-%m-%d"),
+                verification
+                    .status
+                    .label()
+                    .color(verification.status.color())
+                    .bold()
+            );
+            if let Some(note) = &verification.note {
+                println!("  {} {}", "→".dimmed(), note);
+            }
+            for check in verification.checks.iter().filter(|check| !check.holds) {
+                println!(
+                    "  {} {} {} {}",
+                    "→".dimmed(),
+                    check.target,
+                    check.change,
+                    format!("({})", check.detail.as_deref().unwrap_or_default()).dimmed()
+                );
+            }
+        }
+    }
+
+    fn records(&self) -> Vec<Record> {
+        let mut records = vec![
+            Record::new("summary")
+                .field("windowDays", self.window_days)
+                .field("since", self.window.since.to_rfc3339())
+                .field("until", self.window.until.to_rfc3339())
+                .field("serverId", self.server_id.map(|id| id.to_string()))
+                .field("serverName", self.names.server_name.clone())
+                .field("channelId", self.channel_id.map(|id| id.to_string()))
+                .field("channelName", self.names.channel_name.clone())
+                .field("proposals", self.proposals)
+                .field("ratifiedRoleActions", self.verifications.len()),
+        ];
+
+        for count in &self.action_types {
+            for (stage, proposals) in &count.by_stage {
+                records.push(
+                    Record::new("actionTypes")
+                        .field("actionType", count.action_type.as_str())
+                        .field("stage", stage.as_str())
+                        .field("proposals", *proposals),
+                );
+            }
+        }
+        for verification in &self.verifications {
+            records.push(
+                Record::new("verifications")
+                    .field("pollId", verification.poll_id.to_string())
+                    .field("channelId", verification.channel_id.to_string())
+                    .field("channelName", verification.channel_name.as_str())
+                    .field("serverName", verification.server_name.as_str())
+                    .field("actionType", verification.action_type.as_str())
+                    .field("decidedAt", verification.decided_at.to_rfc3339())
+                    .field("roleId", verification.role_id.map(|id| id.to_string()))
+                    .field("roleName", verification.role_name.clone())
+                    .field("status", verification.status.as_str())
+                    .field("note", verification.note.clone()),
+            );
+        }
+        for verification in &self.verifications {
+            for check in &verification.checks {
+                records.push(
+                    Record::new("checks")
+                        .field("pollId", verification.poll_id.to_string())
+                        .field("target", check.target)
+                        .field("change", check.change.as_str())
+                        .field("holds", check.holds)
+                        .field("detail", check.detail.clone())
+                        .field("changedLater", check.changed_later),
+                );
+            }
+        }
+        records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+
+    use super::*;
+
+    const ROLE: Uuid = Uuid::from_u128(1);
+    const ACTION_ROLE: Uuid = Uuid::from_u128(2);
+
+    fn decided_at() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 3, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+    }
+
+    fn change_role() -> RatifiedAction {
+        RatifiedAction {
+            poll_id: Uuid::nil(),
+            channel_id: Uuid::nil(),
+            channel_name: "general".to_string(),
+            server_name: "praxis".to_string(),
+            action_type: "change-role".to_string(),
+            decided_at: decided_at(),
+            action_role_id: Some(ACTION_ROLE),
+            proposed_name: None,
+            proposed_color: None,
+            role_id: Some(ROLE),
+            role_name: Some("admins".to_string()),
+            role_color: Some("#f44336".to_string()),
+            role_updated_at: Some(decided_at() - Duration::days(1)),
+        }
+    }
+
+    fn member(user: u128, change_type: &str) -> ProposedMember {
+        ProposedMember {
+            action_role_id: ACTION_ROLE,
+            user_id: Uuid::from_u128(user),
+            user_name: format!("user{user}"),
+            change_type: change_type.to_string(),
+        }
+    }
+
+    fn permission(action: &str, change_type: &str) -> ProposedPermission {
+        ProposedPermission {
+            action_role_id: ACTION_ROLE,
+            action: action.to_string(),
+            subject: "Channel".to_string(),
+            change_type: change_type.to_string(),
+        }
+    }
+
+    fn granted(action: &str, hours_after_decision: i64) -> RolePermission {
+        RolePermission {
+            role_id: ROLE,
+            action: action.to_string(),
+            subject: "Channel".to_string(),
+            created_at: decided_at() + Duration::hours(hours_after_decision),
+        }
+    }
+
+    fn role_member(user: u128) -> RoleMember {
+        RoleMember {
+            role_id: ROLE,
+            user_id: Uuid::from_u128(user),
+        }
+    }
+
+    fn holds(verification: &ActionVerification) -> Vec<bool> {
+        verification
+            .checks
+            .iter()
+            .map(|check| check.holds)
+            .collect()
+    }
+
+    #[test]
+    fn member_changes_are_checked_against_current_members() {
+        let members = [member(10, "add"), member(11, "remove")];
+
+        let current = CurrentRoles::new(Vec::new(), vec![role_member(10)]);
+        let verification = verify(change_role(), &[], &members, &current);
+        assert_eq!(holds(&verification), vec![true, true]);
+        assert_eq!(verification.status, ActionStatus::Applied);
+
+        let current = CurrentRoles::new(Vec::new(), vec![role_member(11)]);
+        let verification = verify(change_role(), &[], &members, &current);
+        assert_eq!(holds(&verification), vec![false, false]);
+        assert_eq!(verification.status, ActionStatus::NotApplied);
+        assert_eq!(
+            verification.checks[0].detail.as_deref(),
+            Some("not a member")
+        );
+        assert_eq!(
+            verification.checks[1].detail.as_deref(),
+            Some("still a member")
+        );
+    }
+
+    #[test]
+    fn permission_changes_are_checked_against_current_grants() {
+        let permissions = [permission("create", "add"), permission("delete", "remove")];
+
+        let current = CurrentRoles::new(vec![granted("create", -1)], Vec::new());
+        let verification = verify(change_role(), &permissions, &[], &current);
+        assert_eq!(holds(&verification), vec![true, true]);
+        assert_eq!(verification.status, ActionStatus::Applied);
+
+        // A removed permission that was granted again after the decision
+        let current = CurrentRoles::new(
+            vec![granted("create", -1), granted("delete", 2)],
+            Vec::new(),
+        );
+        let verification = verify(change_role(), &permissions, &[], &current);
+        assert_eq!(holds(&verification), vec![true, false]);
+        assert!(verification.checks[1].changed_later);
+        assert_eq!(verification.status, ActionStatus::Undone);
+
+        // Still granted from before, so never removed
+        let current = CurrentRoles::new(
+            vec![granted("create", -1), granted("delete", -2)],
+            Vec::new(),
+        );
+        let verification = verify(change_role(), &permissions, &[], &current);
+        assert_eq!(verification.status, ActionStatus::Partial);
+    }
+
+    #[test]
+    fn deleted_target_role_counts_as_undone() {
+        let action = RatifiedAction {
+            action_role_id: None,
+            role_id: None,
+            ..change_role()
+        };
+        let current = CurrentRoles::new(Vec::new(), Vec::new());
+        let verification = verify(action, &[], &[member(10, "add")], &current);
+        assert_eq!(verification.status, ActionStatus::Undone);
+        assert!(verification.checks.is_empty());
+    }
+
+    #[test]
+    fn status_of_mixed_and_empty_checks() {
+        let held = || RoleCheck::held("member", "+ann".to_string());
+        let missing = || RoleCheck::missing("member", "+bo".to_string(), "not a member", false);
+        let reverted = || RoleCheck::missing("name", "mods".to_string(), "now 'admins'", true);
+
+        assert_eq!(status_of(&[]), ActionStatus::Applied);
+        assert_eq!(status_of(&[held(), held()]), ActionStatus::Applied);
+        assert_eq!(status_of(&[held(), missing()]), ActionStatus::Partial);
+        assert_eq!(status_of(&[missing(), missing()]), ActionStatus::NotApplied);
+        assert_eq!(status_of(&[held(), reverted()]), ActionStatus::Undone);
+        assert_eq!(status_of(&[missing(), reverted()]), ActionStatus::Undone);
+    }
+}
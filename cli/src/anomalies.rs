@@ -0,0 +1,697 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::Result;
+use chrono::{Duration, NaiveDateTime};
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::cli::VoteAnomaliesArgs;
+use crate::output::{OutputFormat, Record, Report, render};
+use crate::scope::{PollScope, ScopeNames, resolve_channel_name, resolve_server};
+use crate::utils::{
+    color_stage, format_channel, format_duration, format_number, format_timestamp, pct,
+};
+use crate::window::TimeWindow;
+
+/// Anonymous voters a proposal needs before their share is considered.
+const MIN_ANONYMOUS_VOTERS: usize = 2;
+/// Proposals fresh accounts must have voted on for their patterns to count
+/// as identical, so a single shared vote isn't a pattern.
+const MIN_PATTERN_PROPOSALS: usize = 2;
+
+pub async fn run_vote_anomalies(
+    pool: &PgPool,
+    window: TimeWindow,
+    args: &VoteAnomaliesArgs,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut names = ScopeNames::default();
+    let mut server_id = None;
+    if let Some(id_or_slug) = &args.server_id {
+        let (id, name) = resolve_server(pool, id_or_slug).await?;
+        server_id = Some(id);
+        names.server_name = Some(name);
+    }
+    if let Some(channel_id) = args.channel_id {
+        names.channel_name = resolve_channel_name(pool, channel_id).await?;
+    }
+
+    let scope = PollScope {
+        server_id,
+        channel_id: args.channel_id,
+        ..PollScope::new(&window)
+    };
+    let report = collect_anomalies(pool, window, scope, names, args).await?;
+    render(&report, format)
+}
+
+async fn collect_anomalies(
+    pool: &PgPool,
+    window: TimeWindow,
+    scope: PollScope,
+    names: ScopeNames,
+    args: &VoteAnomaliesArgs,
+) -> Result<VoteAnomaliesReport> {
+    let votes: Vec<VoteRow> = sqlx::query_as(
+        r#"
+        SELECT p.id AS poll_id,
+               p."channelId" AS channel_id,
+               c.name AS channel_name,
+               s.name AS server_name,
+               p.stage::text AS stage,
+               COALESCE(sc."anonymousUsersEnabled", FALSE) AS anonymous_enabled,
+               u.id AS user_id,
+               u.name AS user_name,
+               u.anonymous,
+               u."createdAt" AS account_created_at,
+               v."voteType"::text AS vote_type,
+               v."createdAt" AS voted_at
+        FROM vote v
+        JOIN poll p ON p.id = v."pollId"
+        JOIN channel c ON c.id = p."channelId"
+        JOIN server s ON s.id = c."serverId"
+        LEFT JOIN server_config sc ON sc."serverId" = s.id
+        JOIN "user" u ON u.id = v."userId"
+        WHERE p."pollType" = 'proposal'
+          AND p."createdAt" >= $1 AND p."createdAt" < $5
+          AND ($2::uuid IS NULL OR p."channelId" = $2)
+          AND ($3::uuid IS NULL OR p.id = $3)
+          AND ($4::uuid IS NULL OR c."serverId" = $4)
+        ORDER BY p."createdAt" DESC, v."createdAt"
+        "#,
+    )
+    .bind(scope.since)
+    .bind(scope.channel_id)
+    .bind(scope.poll_id)
+    .bind(scope.server_id)
+    .bind(scope.until)
+    .fetch_all(pool)
+    .await?;
+
+    let fresh_within = Duration::hours(args.fresh_hours as i64);
+    let mut proposals: Vec<ProposalVotes> = Vec::new();
+    let mut index: HashMap<Uuid, usize> = HashMap::new();
+    for vote in votes {
+        let i = *index.entry(vote.poll_id).or_insert_with(|| {
+            proposals.push(ProposalVotes {
+                poll_id: vote.poll_id,
+                channel_id: vote.channel_id,
+                channel_name: vote.channel_name.clone(),
+                server_name: vote.server_name.clone(),
+                stage: vote.stage.clone(),
+                anonymous_enabled: vote.anonymous_enabled,
+                votes: Vec::new(),
+            });
+            proposals.len() - 1
+        });
+        proposals[i].votes.push(vote);
+    }
+
+    let patterns = identical_patterns(&proposals, fresh_within, args.min_pattern_accounts);
+
+    let mut flagged = Vec::new();
+    for proposal in &proposals {
+        let flags: Vec<AnomalyFlag> = [
+            fresh_burst(proposal, fresh_within, args),
+            anonymous_share(proposal, args.max_anonymous_share),
+        ]
+        .into_iter()
+        .flatten()
+        .chain(
+            patterns
+                .iter()
+                .filter(|pattern| pattern.votes.contains_key(&proposal.poll_id))
+                .map(|pattern| pattern.flag(proposal.poll_id)),
+        )
+        .collect();
+        if flags.is_empty() {
+            continue;
+        }
+
+        let anonymous = proposal.votes.iter().filter(|vote| vote.anonymous).count();
+        flagged.push(FlaggedProposal {
+            poll_id: proposal.poll_id,
+            channel_id: proposal.channel_id,
+            channel_name: proposal.channel_name.clone(),
+            server_name: proposal.server_name.clone(),
+            stage: proposal.stage.clone(),
+            anonymous_enabled: proposal.anonymous_enabled,
+            votes: proposal.votes.len(),
+            anonymous_votes: anonymous,
+            flags,
+        });
+    }
+
+    Ok(VoteAnomaliesReport {
+        window_days: window.days(),
+        window,
+        server_id: scope.server_id,
+        channel_id: scope.channel_id,
+        names,
+        fresh_hours: args.fresh_hours,
+        burst_minutes: args.burst_minutes,
+        min_burst: args.min_burst,
+        max_anonymous_share: args.max_anonymous_share,
+        min_pattern_accounts: args.min_pattern_accounts,
+        proposals_checked: proposals.len(),
+        flagged,
+    })
+}
+
+/// The largest group of votes from fresh accounts cast within
+/// `--burst-minutes` of each other, flagged when it reaches `--min-burst`.
+fn fresh_burst(
+    proposal: &ProposalVotes,
+    fresh_within: Duration,
+    args: &VoteAnomaliesArgs,
+) -> Option<AnomalyFlag> {
+    let fresh: Vec<&VoteRow> = proposal
+        .votes
+        .iter()
+        .filter(|vote| vote.is_fresh(fresh_within))
+        .collect();
+    let burst_within = Duration::minutes(args.burst_minutes as i64);
+
+    // Votes are ordered by time, so the largest burst is the widest window
+    // of consecutive fresh votes spanning at most `burst_within`
+    let mut best = (0, 0);
+    let mut start = 0;
+    for end in 0..fresh.len() {
+        while fresh[end].voted_at - fresh[start].voted_at > burst_within {
+            start += 1;
+        }
+        if end + 1 - start > best.1 - best.0 {
+            best = (start, end + 1);
+        }
+    }
+    let burst = &fresh[best.0..best.1];
+    if burst.len() < args.min_burst as usize {
+        return None;
+    }
+
+    let span = burst[burst.len() - 1].voted_at - burst[0].voted_at;
+    Some(AnomalyFlag {
+        kind: AnomalyKind::FreshBurst,
+        summary: format!(
+            "{} votes within {} from accounts created less than {}h before voting",
+            burst.len(),
+            format_duration(span.num_seconds() as f64),
+            args.fresh_hours
+        ),
+        evidence: burst.iter().map(|vote| vote.describe()).collect(),
+        user_ids: burst.iter().map(|vote| vote.user_id).collect(),
+    })
+}
+
+/// Flags a proposal where anonymous voters make up more than
+/// `--max-anonymous-share` percent of its voters.
+fn anonymous_share(proposal: &ProposalVotes, max_share: u32) -> Option<AnomalyFlag> {
+    let anonymous: Vec<&VoteRow> = proposal
+        .votes
+        .iter()
+        .filter(|vote| vote.anonymous)
+        .collect();
+    let total = proposal.votes.len();
+    let share = pct(anonymous.len() as i64, total as i64);
+    if anonymous.len() < MIN_ANONYMOUS_VOTERS || share <= max_share as f64 {
+        return None;
+    }
+
+    let mut evidence = vec![format!(
+        "{} anonymous and {} registered voters, anonymous users {} in this server",
+        anonymous.len(),
+        total - anonymous.len(),
+        if proposal.anonymous_enabled {
+            "enabled"
+        } else {
+            "currently disabled"
+        }
+    )];
+    evidence.extend(anonymous.iter().map(|vote| vote.describe()));
+
+    Some(AnomalyFlag {
+        kind: AnomalyKind::AnonymousShare,
+        summary: format!("{:.1}% of voters are anonymous", share),
+        evidence,
+        user_ids: anonymous.iter().map(|vote| vote.user_id).collect(),
+    })
+}
+
+/// Groups fresh accounts that cast exactly the same votes on every proposal
+/// in scope they voted on. Accounts count as fresh when they voted within
+/// `--fresh-hours` of being created.
+fn identical_patterns(
+    proposals: &[ProposalVotes],
+    fresh_within: Duration,
+    min_accounts: u32,
+) -> Vec<VotePattern> {
+    let mut by_user: HashMap<Uuid, (&VoteRow, BTreeMap<Uuid, String>)> = HashMap::new();
+    let mut fresh_users = Vec::new();
+    for vote in proposals.iter().flat_map(|proposal| &proposal.votes) {
+        let (_, votes) = by_user
+            .entry(vote.user_id)
+            .or_insert_with(|| (vote, BTreeMap::new()));
+        votes.insert(vote.poll_id, vote.vote_type.clone());
+        if vote.is_fresh(fresh_within) {
+            fresh_users.push(vote.user_id);
+        }
+    }
+    fresh_users.sort();
+    fresh_users.dedup();
+
+    let mut groups: BTreeMap<&BTreeMap<Uuid, String>, Vec<&VoteRow>> = BTreeMap::new();
+    for user_id in &fresh_users {
+        let (vote, votes) = &by_user[user_id];
+        if votes.len() >= MIN_PATTERN_PROPOSALS {
+            groups.entry(votes).or_default().push(vote);
+        }
+    }
+
+    groups
+        .into_iter()
+        .filter(|(_, accounts)| accounts.len() >= min_accounts as usize)
+        .map(|(votes, accounts)| VotePattern {
+            votes: votes.clone(),
+            accounts: accounts
+                .iter()
+                .map(|vote| {
+                    (
+                        vote.user_id,
+                        vote.user_name.clone(),
+                        vote.account_created_at,
+                    )
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+/// Fresh accounts that voted identically across several proposals.
+struct VotePattern {
+    /// Proposal → vote type, shared by every account
+    votes: BTreeMap<Uuid, String>,
+    accounts: Vec<(Uuid, String, NaiveDateTime)>,
+}
+
+impl VotePattern {
+    fn flag(&self, poll_id: Uuid) -> AnomalyFlag {
+        let mut evidence: Vec<String> = self
+            .accounts
+            .iter()
+            .map(|(_, name, created_at)| {
+                format!(
+                    "{} (account created {})",
+                    name,
+                    format_timestamp(created_at.and_utc(), "%Y-%m-%d %H:%M")
+                )
+            })
+            .collect();
+        evidence.push(format!(
+            "same votes on {} proposals: {}",
+            self.votes.len(),
+            self.votes
+                .iter()
+                .map(|(id, vote_type)| format!("{} on {}", vote_type, short_id(*id)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+
+        AnomalyFlag {
+            kind: AnomalyKind::IdenticalPattern,
+            summary: format!(
+                "{} fresh accounts voted {} here and identically on all {} proposals they share",
+                self.accounts.len(),
+                self.votes[&poll_id],
+                self.votes.len()
+            ),
+            evidence,
+            user_ids: self.accounts.iter().map(|(id, _, _)| *id).collect(),
+        }
+    }
+}
+
+fn short_id(id: Uuid) -> String {
+    id.to_string()[..8].to_string()
+}
+
+// ---------------------------------------------------------------------------
+// Rows
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, FromRow)]
+struct VoteRow {
+    poll_id: Uuid,
+    channel_id: Uuid,
+    channel_name: String,
+    server_name: String,
+    stage: String,
+    anonymous_enabled: bool,
+    user_id: Uuid,
+    user_name: String,
+    anonymous: bool,
+    account_created_at: NaiveDateTime,
+    vote_type: String,
+    voted_at: NaiveDateTime,
+}
+
+impl VoteRow {
+    /// Created no more than `within` before casting this vote.
+    fn is_fresh(&self, within: Duration) -> bool {
+        self.voted_at - self.account_created_at <= within
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{}{} voted {} at {}, account {} old",
+            self.user_name,
+            if self.anonymous { " (anonymous)" } else { "" },
+            self.vote_type,
+            format_timestamp(self.voted_at.and_utc(), "%Y-%m-%d %H:%M"),
+            format_duration((self.voted_at - self.account_created_at).num_seconds() as f64)
+        )
+    }
+}
+
+struct ProposalVotes {
+    poll_id: Uuid,
+    channel_id: Uuid,
+    channel_name: String,
+    server_name: String,
+    stage: String,
+    anonymous_enabled: bool,
+    /// Oldest first
+    votes: Vec<VoteRow>,
+}
+
+// ---------------------------------------------------------------------------
+// Report
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VoteAnomaliesReport {
+    window_days: i64,
+    #[serde(flatten)]
+    window: TimeWindow,
+    server_id: Option<Uuid>,
+    channel_id: Option<Uuid>,
+    #[serde(flatten)]
+    names: ScopeNames,
+    fresh_hours: u32,
+    burst_minutes: u32,
+    min_burst: u32,
+    max_anonymous_share: u32,
+    min_pattern_accounts: u32,
+    /// Proposals in scope with at least one vote
+    proposals_checked: usize,
+    /// Newest proposal first
+    flagged: Vec<FlaggedProposal>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FlaggedProposal {
+    poll_id: Uuid,
+    channel_id: Uuid,
+    channel_name: String,
+    server_name: String,
+    stage: String,
+    /// The server's current `anonymousUsersEnabled` setting
+    anonymous_enabled: bool,
+    votes: usize,
+    anonymous_votes: usize,
+    flags: Vec<AnomalyFlag>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum AnomalyKind {
+    FreshBurst,
+    AnonymousShare,
+    IdenticalPattern,
+}
+
+impl AnomalyKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::FreshBurst => "freshBurst",
+            Self::AnonymousShare => "anonymousShare",
+            Self::IdenticalPattern => "identicalPattern",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::FreshBurst => "fresh account burst",
+            Self::AnonymousShare => "anonymous voters",
+            Self::IdenticalPattern => "identical voting pattern",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AnomalyFlag {
+    kind: AnomalyKind,
+    summary: String,
+    evidence: Vec<String>,
+    /// Accounts the flag is about
+    user_ids: Vec<Uuid>,
+}
+
+impl Report for VoteAnomaliesReport {
+    const KIND: &'static str = "vote-anomalies";
+    const SCHEMA_VERSION: u32 = 1;
+
+    fn render_text(&self) {
+        let scope = self
+            .names
+            .describe()
+            .map(|scope| format!(", {}", scope))
+            .unwrap_or_default();
+        println!(
+            "\n{} {}",
+            "Vote Anomalies".bold().underline(),
+            format!("(proposals from {}{})", self.window.describe(), scope).dimmed()
+        );
+        println!(
+            "{} {} of {} proposals flagged",
+            "•".cyan(),
+            format_number(self.flagged.len() as i64).bold(),
+            format_number(self.proposals_checked as i64).bold()
+        );
+        println!(
+            "{}",
+            format!(
+                "  fresh accounts: voted within {}h of being created; bursts: {}+ votes within {}m; \
+                 anonymous share above {}%; identical patterns: {}+ fresh accounts",
+                self.fresh_hours,
+                self.min_burst,
+                self.burst_minutes,
+                self.max_anonymous_share,
+                self.min_pattern_accounts
+            )
+            .dimmed()
+        );
+
+        for proposal in &self.flagged {
+            println!(
+                "\n{} poll {} in {} — {}, {} votes ({} anonymous)",
+                "•".cyan(),
+                proposal.poll_id,
+                format_channel(&proposal.channel_name, &proposal.server_name),
+                color_stage(&proposal.stage),
+                proposal.votes,
+                proposal.anonymous_votes
+            );
+            for flag in &proposal.flags {
+                println!(
+                    "  {} {}: {}",
+                    "!".red().bold(),
+                    flag.kind.label().bold(),
+                    flag.summary
+                );
+                for evidence in &flag.evidence {
+                    println!("    {} {}", "→".dimmed(), evidence);
+                }
+            }
+        }
+    }
+
+    fn records(&self) -> Vec<Record> {
+        let mut records = vec![
+            Record::new("summary")
+                .field("windowDays", self.window_days)
+                .field("since", self.window.since.to_rfc3339())
+                .field("until", self.window.until.to_rfc3339())
+                .field("serverId", self.server_id.map(|id| id.to_string()))
+                .field("serverName", self.names.server_name.clone())
+                .field("channelId", self.channel_id.map(|id| id.to_string()))
+                .field("channelName", self.names.channel_name.clone())
+                .field("freshHours", self.fresh_hours)
+                .field("burstMinutes", self.burst_minutes)
+                .field("minBurst", self.min_burst)
+                .field("maxAnonymousShare", self.max_anonymous_share)
+                .field("minPatternAccounts", self.min_pattern_accounts)
+                .field("proposalsChecked", self.proposals_checked)
+                .field("flagged", self.flagged.len()),
+        ];
+
+        for proposal in &self.flagged {
+            for flag in &proposal.flags {
+                records.push(
+                    Record::new("flags")
+                        .field("pollId", proposal.poll_id.to_string())
+                        .field("channelId", proposal.channel_id.to_string())
+                        .field("channelName", proposal.channel_name.as_str())
+                        .field("serverName", proposal.server_name.as_str())
+                        .field("stage", proposal.stage.as_str())
+                        .field("anonymousEnabled", proposal.anonymous_enabled)
+                        .field("votes", proposal.votes)
+                        .field("anonymousVotes", proposal.anonymous_votes)
+                        .field("kind", flag.kind.as_str())
+                        .field("summary", flag.summary.as_str())
+                        .field("evidence", flag.evidence.join("; ")),
+                );
+            }
+        }
+        records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn start() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 3, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+    }
+
+    /// A vote cast `minute` minutes after noon by user `user`, whose account
+    /// was created `age_hours` before the vote.
+    fn vote(poll: u128, user: u128, minute: i64, age_hours: i64, vote_type: &str) -> VoteRow {
+        let voted_at = start() + Duration::minutes(minute);
+        VoteRow {
+            poll_id: Uuid::from_u128(poll),
+            channel_id: Uuid::nil(),
+            channel_name: "general".to_string(),
+            server_name: "praxis".to_string(),
+            stage: "voting".to_string(),
+            anonymous_enabled: false,
+            user_id: Uuid::from_u128(user),
+            user_name: format!("user{user}"),
+            anonymous: false,
+            account_created_at: voted_at - Duration::hours(age_hours),
+            vote_type: vote_type.to_string(),
+            voted_at,
+        }
+    }
+
+    fn proposal(poll: u128, votes: Vec<VoteRow>) -> ProposalVotes {
+        ProposalVotes {
+            poll_id: Uuid::from_u128(poll),
+            channel_id: Uuid::nil(),
+            channel_name: "general".to_string(),
+            server_name: "praxis".to_string(),
+            stage: "voting".to_string(),
+            anonymous_enabled: false,
+            votes,
+        }
+    }
+
+    fn args() -> VoteAnomaliesArgs {
+        VoteAnomaliesArgs {
+            server_id: None,
+            channel_id: None,
+            fresh_hours: 24,
+            burst_minutes: 60,
+            min_burst: 3,
+            max_anonymous_share: 50,
+            min_pattern_accounts: 3,
+        }
+    }
+
+    #[test]
+    fn fresh_burst_counts_votes_inside_the_window() {
+        let fresh_within = Duration::hours(24);
+        let burst = |minutes: &[i64]| {
+            let votes = minutes
+                .iter()
+                .enumerate()
+                .map(|(i, &minute)| vote(1, i as u128 + 1, minute, 1, "agree"))
+                .collect();
+            fresh_burst(&proposal(1, votes), fresh_within, &args()).map(|flag| flag.user_ids.len())
+        };
+
+        // Exactly `--burst-minutes` apart still counts as one burst
+        assert_eq!(burst(&[0, 30, 60]), Some(3));
+        assert_eq!(burst(&[0, 30, 61]), None);
+        assert_eq!(burst(&[0, 30, 61, 90]), Some(3));
+        assert_eq!(burst(&[0, 5]), None);
+
+        // Accounts older than `--fresh-hours` don't count towards a burst
+        let votes = vec![
+            vote(1, 1, 0, 1, "agree"),
+            vote(1, 2, 10, 25, "agree"),
+            vote(1, 3, 20, 24, "agree"),
+        ];
+        assert!(fresh_burst(&proposal(1, votes), fresh_within, &args()).is_none());
+    }
+
+    #[test]
+    fn anonymous_share_must_exceed_the_maximum() {
+        let share = |anonymous: usize, registered: usize| {
+            let votes = (0..anonymous + registered)
+                .map(|i| VoteRow {
+                    anonymous: i < anonymous,
+                    ..vote(1, i as u128 + 1, i as i64, 100, "agree")
+                })
+                .collect();
+            anonymous_share(&proposal(1, votes), 50).is_some()
+        };
+
+        assert!(!share(2, 2), "exactly at the maximum");
+        assert!(share(3, 2));
+        assert!(!share(1, 0), "too few anonymous voters to judge");
+        assert!(share(2, 0));
+    }
+
+    #[test]
+    fn identical_patterns_need_enough_shared_proposals_and_accounts() {
+        let fresh_within = Duration::hours(24);
+        let accounts = |users: &[u128], polls: &[u128]| -> Vec<ProposalVotes> {
+            polls
+                .iter()
+                .map(|&poll| {
+                    let votes = users
+                        .iter()
+                        .map(|&user| vote(poll, user, poll as i64, 1, "agree"))
+                        .collect();
+                    proposal(poll, votes)
+                })
+                .collect()
+        };
+
+        let patterns = identical_patterns(&accounts(&[1, 2, 3], &[10, 11]), fresh_within, 3);
+        assert_eq!(patterns.len(), 1);
+        assert_eq!(patterns[0].accounts.len(), 3);
+        assert_eq!(patterns[0].votes.len(), 2);
+
+        // A single shared proposal is below the minimum overlap
+        assert!(identical_patterns(&accounts(&[1, 2, 3], &[10]), fresh_within, 3).is_empty());
+        // Too few accounts
+        assert!(identical_patterns(&accounts(&[1, 2], &[10, 11]), fresh_within, 3).is_empty());
+
+        // One account voting differently on one proposal breaks the pattern
+        let mut proposals = accounts(&[1, 2, 3], &[10, 11]);
+        proposals[1].votes[2].vote_type = "block".to_string();
+        assert!(identical_patterns(&proposals, fresh_within, 3).is_empty());
+    }
+}
@@ -0,0 +1,282 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::decision::{VoteTally, evaluate, expected_stage, fetch_proposals, now_naive_utc};
+use crate::output::{OutputFormat, Record, Report, render};
+use crate::scope::{PollScope, ScopeNames, resolve_channel_name, resolve_server};
+use crate::utils::{color_stage, format_channel, format_number, format_timestamp};
+use crate::window::TimeWindow;
+
+pub async fn run_poll_audit(
+    pool: &PgPool,
+    window: TimeWindow,
+    server_id: Option<&str>,
+    channel_id: Option<Uuid>,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut names = ScopeNames::default();
+    let server_id = match server_id {
+        Some(id_or_slug) => {
+            let (id, name) = resolve_server(pool, id_or_slug).await?;
+            names.server_name = Some(name);
+            Some(id)
+        }
+        None => None,
+    };
+    if let Some(channel_id) = channel_id {
+        names.channel_name = resolve_channel_name(pool, channel_id).await?;
+    }
+
+    let scope = PollScope {
+        server_id,
+        channel_id,
+        ..PollScope::new(&window)
+    };
+    let proposals = fetch_proposals(pool, &scope).await?;
+    let now = now_naive_utc();
+
+    let mut report = PollAuditReport {
+        window_days: window.days(),
+        window,
+        server_id,
+        channel_id,
+        names,
+        proposals_checked: 0,
+        skipped_revision: 0,
+        mismatches: Vec::new(),
+    };
+
+    for proposal in &proposals {
+        // Nothing in the server moves a proposal into revision, so its stage
+        // can't be re-derived from votes.
+        if proposal.stage == "revision" {
+            report.skipped_revision += 1;
+            continue;
+        }
+        report.proposals_checked += 1;
+
+        let Some(rules) = proposal.rules() else {
+            report.mismatches.push(AuditFinding {
+                poll_id: proposal.poll_id,
+                channel_id: proposal.channel_id,
+                channel_name: proposal.channel_name.clone(),
+                server_name: proposal.server_name.clone(),
+                created_at: proposal.created_at.and_utc(),
+                decision_making_model: proposal.decision_making_model.clone(),
+                stored_stage: proposal.stage.clone(),
+                expected_stage: "unknown".to_string(),
+                tally: proposal.tally(),
+                member_count: proposal.member_count,
+                reasons: vec![format!(
+                    "unrecognized decision-making model '{}'",
+                    proposal.decision_making_model
+                )],
+            });
+            continue;
+        };
+
+        let tally = proposal.tally();
+        let evaluation = evaluate(
+            &rules,
+            &tally,
+            proposal.member_count,
+            proposal.closing_at,
+            now,
+        );
+        let expected = expected_stage(&evaluation, proposal.closing_at, now);
+        if expected == proposal.stage {
+            continue;
+        }
+
+        let closing = proposal
+            .closing_at
+            .map(|ts| format_timestamp(ts.and_utc(), "%Y-%m-%d %H:%M %Z"));
+        let mut reasons = Vec::new();
+        match (proposal.stage.as_str(), expected) {
+            ("voting", "ratified") => {
+                reasons.push("votes meet every ratification rule but it is still voting".into())
+            }
+            ("voting", "closed") => {
+                reasons.push(format!(
+                    "closing time {} has passed without ratification",
+                    closing.unwrap_or_default()
+                ));
+                reasons.extend(evaluation.reasons);
+            }
+            ("ratified", _) => {
+                reasons.push("stored as ratified but current votes fail:".into());
+                reasons.extend(evaluation.reasons);
+            }
+            ("closed", "ratified") => {
+                reasons.push("closed although votes meet every ratification rule".into())
+            }
+            ("closed", "voting") => match closing {
+                Some(closing) => reasons.push(format!("closed before closing time {}", closing)),
+                None => reasons.push("closed without a closing time".into()),
+            },
+            _ => reasons.extend(evaluation.reasons),
+        }
+
+        report.mismatches.push(AuditFinding {
+            poll_id: proposal.poll_id,
+            channel_id: proposal.channel_id,
+            channel_name: proposal.channel_name.clone(),
+            server_name: proposal.server_name.clone(),
+            created_at: proposal.created_at.and_utc(),
+            decision_making_model: proposal.decision_making_model.clone(),
+            stored_stage: proposal.stage.clone(),
+            expected_stage: expected.to_string(),
+            tally,
+            member_count: proposal.member_count,
+            reasons,
+        });
+    }
+
+    render(&report, format)
+}
+
+// ---------------------------------------------------------------------------
+// Report
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PollAuditReport {
+    window_days: i64,
+    #[serde(flatten)]
+    window: TimeWindow,
+    server_id: Option<Uuid>,
+    channel_id: Option<Uuid>,
+    #[serde(flatten)]
+    names: ScopeNames,
+    proposals_checked: i64,
+    skipped_revision: i64,
+    mismatches: Vec<AuditFinding>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AuditFinding {
+    poll_id: Uuid,
+    channel_id: Uuid,
+    channel_name: String,
+    server_name: String,
+    created_at: DateTime<Utc>,
+    decision_making_model: String,
+    stored_stage: String,
+    expected_stage: String,
+    tally: VoteTally,
+    member_count: i64,
+    reasons: Vec<String>,
+}
+
+impl Report for PollAuditReport {
+    const KIND: &'static str = "poll-audit";
+    const SCHEMA_VERSION: u32 = 1;
+
+    fn render_text(&self) {
+        let scope = self
+            .names
+            .describe()
+            .map(|scope| format!(", {}", scope))
+            .unwrap_or_default();
+        println!(
+            "\n{} {}",
+            "Poll Audit".bold().underline(),
+            format!("(proposals from {}{})", self.window.describe(), scope).dimmed()
+        );
+        println!(
+            "{} {} proposals checked, {} mismatched",
+            "•".cyan(),
+            format_number(self.proposals_checked).bold(),
+            format_number(self.mismatches.len() as i64).bold()
+        );
+        if self.skipped_revision > 0 {
+            println!(
+                "{} {} in revision skipped",
+                "•".cyan(),
+                format_number(self.skipped_revision).bold()
+            );
+        }
+
+        if self.mismatches.is_empty() {
+            println!("\n{}", "Every stored stage matches its votes.".green());
+            return;
+        }
+
+        println!("\n{}", "Mismatched Proposals".bold());
+        for finding in &self.mismatches {
+            let VoteTally {
+                agreements,
+                disagreements,
+                abstains,
+                blocks,
+            } = finding.tally;
+            println!(
+                "{} poll {} in {} — stored {}, expected {}",
+                "•".cyan(),
+                finding.poll_id,
+                format_channel(&finding.channel_name, &finding.server_name),
+                color_stage(&finding.stored_stage),
+                color_stage(&finding.expected_stage)
+            );
+            println!(
+                "  {}",
+                format!(
+                    "{}, {} members, {} agree / {} disagree / {} abstain / {} block",
+                    finding.decision_making_model,
+                    finding.member_count,
+                    agreements,
+                    disagreements,
+                    abstains,
+                    blocks
+                )
+                .dimmed()
+            );
+            for reason in &finding.reasons {
+                println!("  {} {}", "→".dimmed(), reason);
+            }
+        }
+    }
+
+    fn records(&self) -> Vec<Record> {
+        let summary = Record::new("summary")
+            .field("windowDays", self.window_days)
+            .field("since", self.window.since.to_rfc3339())
+            .field("until", self.window.until.to_rfc3339())
+            .field("serverId", self.server_id.map(|id| id.to_string()))
+            .field("serverName", self.names.server_name.clone())
+            .field("channelId", self.channel_id.map(|id| id.to_string()))
+            .field("channelName", self.names.channel_name.clone())
+            .field("proposalsChecked", self.proposals_checked)
+            .field("skippedRevision", self.skipped_revision)
+            .field("mismatches", self.mismatches.len());
+
+        let findings = self.mismatches.iter().map(|finding| {
+            Record::new("mismatches")
+                .field("pollId", finding.poll_id.to_string())
+                .field("channelId", finding.channel_id.to_string())
+                .field("channelName", finding.channel_name.as_str())
+                .field("serverName", finding.server_name.as_str())
+                .field("createdAt", finding.created_at.to_rfc3339())
+                .field(
+                    "decisionMakingModel",
+                    finding.decision_making_model.as_str(),
+                )
+                .field("storedStage", finding.stored_stage.as_str())
+                .field("expectedStage", finding.expected_stage.as_str())
+                .field("agreements", finding.tally.agreements)
+                .field("disagreements", finding.tally.disagreements)
+                .field("abstains", finding.tally.abstains)
+                .field("blocks", finding.tally.blocks)
+                .field("memberCount", finding.member_count)
+                .field("reasons", finding.reasons.join("; "))
+        });
+
+        std::iter::once(summary).chain(findings).collect()
+    }
+}
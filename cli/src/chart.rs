@@ -0,0 +1,321 @@
+use owo_colors::{AnsiColors, OwoColorize};
+use serde::Serialize;
+
+use crate::utils::percentile;
+
+const FALLBACK_WIDTH: usize = 80;
+const MIN_BAR_WIDTH: usize = 10;
+
+/// Partial blocks for the fractional end of a bar, in eighths.
+const EIGHTHS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Width of the terminal, falling back to `COLUMNS` and then 80 when stdout
+/// isn't a terminal.
+pub fn terminal_width() -> usize {
+    if let Some((terminal_size::Width(width), _)) = terminal_size::terminal_size() {
+        return width as usize;
+    }
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .unwrap_or(FALLBACK_WIDTH)
+}
+
+/// Columns left for a bar once `reserved` columns of labels are printed.
+fn bar_width(reserved: usize) -> usize {
+    terminal_width().saturating_sub(reserved).max(MIN_BAR_WIDTH)
+}
+
+/// A bar `value / max` of `width` columns long, drawn to an eighth of a
+/// column. Any non-zero value gets at least a sliver.
+pub fn bar(value: i64, max: i64, width: usize) -> String {
+    if value <= 0 || max <= 0 {
+        return String::new();
+    }
+    let eighths = ((value as f64 / max as f64) * (width * 8) as f64).round() as usize;
+    let eighths = eighths.clamp(1, width * 8);
+    let (full, partial) = (eighths / 8, eighths % 8);
+    let mut bar = "█".repeat(full);
+    if partial > 0 {
+        bar.push(EIGHTHS[partial]);
+    }
+    bar
+}
+
+/// One character per value, scaled between zero and the largest value.
+pub fn sparkline(values: &[i64]) -> String {
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|&value| {
+            // Reserve the lowest level for zero so quiet periods stand out
+            if value <= 0 || max <= 0 {
+                return SPARKS[0];
+            }
+            let level = (value as f64 / max as f64 * 7.0).round() as usize;
+            SPARKS[level.clamp(1, 7)]
+        })
+        .collect()
+}
+
+/// Segments laid end to end, each `value / max` of `width` columns. Segment
+/// edges are rounded from the running total so the bar length matches the
+/// row's total.
+pub fn stacked_bar(segments: &[(i64, AnsiColors)], max: i64, width: usize) -> String {
+    if max <= 0 {
+        return String::new();
+    }
+    let scale = width as f64 / max as f64;
+    let mut bar = String::new();
+    let mut total = 0;
+    for &(value, color) in segments {
+        let start = (total as f64 * scale).round() as usize;
+        total += value.max(0);
+        let end = (total as f64 * scale).round() as usize;
+        if end > start {
+            bar.push_str(&"█".repeat(end - start).color(color).to_string());
+        }
+    }
+    bar
+}
+
+/// Prints `label count bar` rows, scaling bars so the largest value fills the
+/// rest of the terminal.
+pub fn print_bars(rows: &[(String, i64)], color: AnsiColors) {
+    let label_width = rows.iter().map(|(label, _)| label.chars().count()).max();
+    let Some(label_width) = label_width else {
+        return;
+    };
+    let count_width = rows
+        .iter()
+        .map(|(_, count)| count.to_string().len())
+        .max()
+        .unwrap_or(1);
+    let max = rows.iter().map(|(_, count)| *count).max().unwrap_or(0);
+    let width = bar_width(label_width + count_width + 4);
+
+    for (label, count) in rows {
+        println!(
+            "  {:<label_width$} {:>count_width$} {}",
+            label.dimmed(),
+            count,
+            bar(*count, max, width).color(color)
+        );
+    }
+}
+
+/// Prints `label total bar` rows with one coloured segment per series, plus a
+/// legend naming each series.
+pub fn print_stacked_bars(series: &[(&str, AnsiColors)], rows: &[(String, Vec<i64>)]) {
+    let label_width = rows.iter().map(|(label, _)| label.chars().count()).max();
+    let Some(label_width) = label_width else {
+        return;
+    };
+    let totals: Vec<i64> = rows.iter().map(|(_, values)| values.iter().sum()).collect();
+    let count_width = totals
+        .iter()
+        .map(|total| total.to_string().len())
+        .max()
+        .unwrap_or(1);
+    let max = totals.iter().copied().max().unwrap_or(0);
+    let width = bar_width(label_width + count_width + 4);
+
+    let legend: Vec<String> = series
+        .iter()
+        .map(|(name, color)| format!("{} {}", "█".color(*color), name))
+        .collect();
+    println!("  {}", legend.join("  "));
+
+    for ((label, values), total) in rows.iter().zip(&totals) {
+        let segments: Vec<(i64, AnsiColors)> = values
+            .iter()
+            .zip(series)
+            .map(|(&value, &(_, color))| (value, color))
+            .collect();
+        println!(
+            "  {:<label_width$} {:>count_width$} {}",
+            label.dimmed(),
+            total,
+            stacked_bar(&segments, max, width)
+        );
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Distributions
+// ---------------------------------------------------------------------------
+
+/// Summary statistics and a histogram for a set of non-negative counts.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Distribution {
+    pub count: usize,
+    pub mean: f64,
+    pub min: i64,
+    pub p25: f64,
+    pub median: f64,
+    pub p75: f64,
+    pub p90: f64,
+    pub max: i64,
+    pub bins: Vec<HistogramBin>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistogramBin {
+    /// Inclusive lower bound
+    pub lower: i64,
+    /// Inclusive upper bound
+    pub upper: i64,
+    pub count: i64,
+}
+
+impl Distribution {
+    /// Bins are one value wide when the range fits in `max_bins`, otherwise
+    /// the range is split into `max_bins` equal-width bins. `None` when there
+    /// are no values.
+    pub fn new(mut values: Vec<i64>, max_bins: usize) -> Option<Self> {
+        values.sort_unstable();
+        let (&min, &max) = (values.first()?, values.last()?);
+        let sorted: Vec<f64> = values.iter().map(|&value| value as f64).collect();
+
+        let span = (max - min + 1) as usize;
+        let bin_width = span.div_ceil(max_bins.max(1)) as i64;
+        let mut bins: Vec<HistogramBin> = (0..span.div_ceil(bin_width as usize))
+            .map(|i| {
+                let lower = min + i as i64 * bin_width;
+                HistogramBin {
+                    lower,
+                    upper: (lower + bin_width - 1).min(max),
+                    count: 0,
+                }
+            })
+            .collect();
+        for &value in &values {
+            bins[((value - min) / bin_width) as usize].count += 1;
+        }
+
+        Some(Self {
+            count: values.len(),
+            mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            min,
+            p25: percentile(&sorted, 25.0)?,
+            median: percentile(&sorted, 50.0)?,
+            p75: percentile(&sorted, 75.0)?,
+            p90: percentile(&sorted, 90.0)?,
+            max,
+            bins,
+        })
+    }
+
+    pub fn print_histogram(&self, color: AnsiColors) {
+        let rows: Vec<(String, i64)> = self
+            .bins
+            .iter()
+            .map(|bin| {
+                let label = if bin.lower == bin.upper {
+                    bin.lower.to_string()
+                } else {
+                    format!("{}–{}", bin.lower, bin.upper)
+                };
+                (label, bin.count)
+            })
+            .collect();
+        print_bars(&rows, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blocks(bar: &str) -> usize {
+        bar.chars().filter(|&c| c == '█').count()
+    }
+
+    #[test]
+    fn bars_scale_to_width() {
+        assert_eq!(bar(5, 0, 10), "");
+        assert_eq!(bar(0, 10, 10), "");
+        assert_eq!(bar(10, 10, 10), "█".repeat(10));
+        // Never longer than the width, even past the max
+        assert_eq!(bar(20, 10, 10), "█".repeat(10));
+        assert_eq!(bar(5, 10, 10), "█".repeat(5));
+        assert_eq!(bar(8, 160, 10), "▌");
+        // A tiny value still gets a sliver
+        assert_eq!(bar(1, 1000, 10), "▏");
+    }
+
+    #[test]
+    fn sparkline_spans_the_glyph_range() {
+        assert_eq!(sparkline(&[]), "");
+        assert_eq!(sparkline(&[0, 0]), "▁▁");
+        assert_eq!(sparkline(&[0, 1, 7]), "▁▂█");
+        assert_eq!(sparkline(&[3, 3]), "██");
+        // Small values are raised above the zero level
+        assert_eq!(sparkline(&[1, 100]), "▂█");
+    }
+
+    #[test]
+    fn stacked_segments_fill_at_most_the_width() {
+        use AnsiColors::{Blue, Green, Red};
+
+        assert_eq!(stacked_bar(&[(3, Green)], 0, 10), "");
+        for (segments, max, width, expected) in [
+            (vec![(1, Green), (1, Red), (1, Blue)], 3, 10, 10),
+            (vec![(1, Green), (1, Red), (1, Blue)], 3, 7, 7),
+            (vec![(2, Green), (2, Red), (2, Blue)], 7, 10, 9),
+            (vec![(1, Green), (0, Red), (-4, Blue)], 1, 10, 10),
+        ] {
+            let bar = stacked_bar(&segments, max, width);
+            assert_eq!(blocks(&bar), expected, "{segments:?} of {max} in {width}");
+        }
+    }
+
+    #[test]
+    fn distribution_bins_and_percentiles() {
+        assert!(Distribution::new(Vec::new(), 12).is_none());
+
+        let single = Distribution::new(vec![4], 12).unwrap();
+        assert_eq!(
+            (single.min, single.max, single.median, single.p90),
+            (4, 4, 4.0, 4.0)
+        );
+        assert_eq!(single.bins.len(), 1);
+        assert_eq!(
+            (
+                single.bins[0].lower,
+                single.bins[0].upper,
+                single.bins[0].count
+            ),
+            (4, 4, 1)
+        );
+
+        let equal = Distribution::new(vec![2, 2, 2], 12).unwrap();
+        assert_eq!((equal.mean, equal.p25, equal.p75), (2.0, 2.0, 2.0));
+        assert_eq!(equal.bins.len(), 1);
+        assert_eq!(equal.bins[0].count, 3);
+
+        let one_bin = Distribution::new(vec![0, 5, 9], 1).unwrap();
+        assert_eq!(one_bin.bins.len(), 1);
+        assert_eq!(
+            (
+                one_bin.bins[0].lower,
+                one_bin.bins[0].upper,
+                one_bin.bins[0].count
+            ),
+            (0, 9, 3)
+        );
+
+        // 0..=10 in 4 bins of width 3, the last one cut off at the max
+        let spread = Distribution::new(vec![0, 1, 3, 6, 8, 10], 4).unwrap();
+        let bins: Vec<(i64, i64, i64)> = spread
+            .bins
+            .iter()
+            .map(|bin| (bin.lower, bin.upper, bin.count))
+            .collect();
+        assert_eq!(bins, vec![(0, 2, 2), (3, 5, 1), (6, 8, 2), (9, 10, 1)]);
+        assert_eq!((spread.median, spread.p25), (4.5, 1.5));
+    }
+}
@@ -1,6 +1,15 @@
-use clap::{Parser, Subcommand};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use clap::{ArgGroup, Args, Parser, Subcommand};
+use serde::Serialize;
 use uuid::Uuid;
 
+use crate::decision::DecisionModel;
+use crate::lint::LintRule;
+use crate::output::OutputFormat;
+use crate::window::{Boundary, Bucket, parse_boundary};
+
 #[derive(Parser, Debug)]
 #[command(
     name = "Praxis CLI",
@@ -12,6 +21,19 @@ pub struct Cli {
     /// Lookback window in days for commands that support it
     #[arg(long, global = true, default_value_t = 30)]
     pub days: i64,
+    /// Start of the window: a date (YYYY-MM-DD) or RFC 3339 timestamp. Overrides --days
+    #[arg(long, global = true, value_parser = parse_boundary)]
+    pub since: Option<Boundary>,
+    /// End of the window: a date (inclusive) or RFC 3339 timestamp. Defaults to now
+    #[arg(long, global = true, value_parser = parse_boundary)]
+    pub until: Option<Boundary>,
+    /// Time zone for dates, buckets and printed timestamps (IANA name). Defaults to
+    /// $TZ, then UTC
+    #[arg(long, global = true)]
+    pub tz: Option<String>,
+    /// Output format for commands that produce reports
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -19,22 +41,61 @@ pub struct Cli {
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     /// Poll and proposal stats with vote breakdown
-    PollStats {
+    PollStats(PollStatsArgs),
+    /// List proposals whose stored stage disagrees with their votes and poll_config
+    PollAudit {
+        /// Optional server scope (ID or slug)
+        #[arg(long)]
+        server_id: Option<String>,
+        /// Optional channel scope
+        #[arg(long)]
+        channel_id: Option<Uuid>,
+    },
+    /// Replay past proposals against alternative decision-making rules
+    PollSimulate {
+        /// Optional server scope (ID or slug)
+        #[arg(long)]
+        server_id: Option<String>,
+        /// Optional channel scope
+        #[arg(long)]
+        channel_id: Option<Uuid>,
+        #[command(flatten)]
+        rules: RuleOverrides,
+    },
+    /// List proposals closing soon and overdue proposals still in voting
+    PollDeadlines {
+        /// Include proposals closing within this many hours
+        #[arg(long, default_value_t = 24)]
+        hours: u32,
+        /// Optional server scope (ID or slug)
+        #[arg(long)]
+        server_id: Option<String>,
         /// Optional channel scope
         #[arg(long)]
         channel_id: Option<Uuid>,
-        /// Focus on a specific poll ID
-        #[arg(long)]
-        poll_id: Option<Uuid>,
-        /// How many high-participation polls to list (ignored when poll_id is set)
-        #[arg(long, default_value_t = 5)]
-        top_polls: u32,
-        /// Limit of top channels to display
-        #[arg(long, default_value_t = 5)]
-        top_channels: u32,
     },
+    /// Block and disagree patterns across proposals
+    PollDissent(PollDissentArgs),
+    /// Breakdown of proposal actions and whether ratified role changes took effect
+    PollActions(PollActionsArgs),
+    /// Co-voting network: how often pairs of members vote the same way, with clusters and outliers
+    VoteGraph(VoteGraphArgs),
+    /// Flag proposals with signs of vote stuffing by fresh or anonymous accounts
+    VoteAnomalies(VoteAnomaliesArgs),
+    /// Timeline of one poll: creation, votes, closing deadline, action and stage
+    PollShow {
+        /// Poll to show
+        poll_id: Uuid,
+    },
+    /// Participation profile for a single member
+    UserStats(UserStatsArgs),
+    /// Serve governance and activity metrics for Prometheus to scrape
+    ServeMetrics(ServeMetricsArgs),
     /// Print the current database schema (tables, columns, indexes, constraints, enums)
-    Schema,
+    Schema(SchemaArgs),
+    /// Inspect the TypeORM migrations in src/database/migrations
+    #[command(subcommand)]
+    Migrations(MigrationsCommand),
     /// Print all Express API routes extracted from router files
     Routes {
         /// Filter routes by path substring
@@ -45,3 +106,203 @@ pub enum Commands {
         tree: bool,
     },
 }
+
+#[derive(Args, Debug)]
+pub struct PollStatsArgs {
+    /// Optional server scope (ID or slug), aggregating every channel in the server
+    #[arg(long)]
+    pub server_id: Option<String>,
+    /// Optional channel scope
+    #[arg(long)]
+    pub channel_id: Option<Uuid>,
+    /// Focus on a specific poll ID
+    #[arg(long)]
+    pub poll_id: Option<Uuid>,
+    /// How many high-participation polls to list (ignored when poll_id is set)
+    #[arg(long, default_value_t = 5)]
+    pub top_polls: u32,
+    /// Limit of top channels to display
+    #[arg(long, default_value_t = 5)]
+    pub top_channels: u32,
+    /// List decided proposals whose turnout (voters / channel members) fell below this percentage
+    #[arg(long, value_parser = parse_percentage)]
+    pub min_turnout: Option<f64>,
+    /// Bucket size for the creation trend
+    #[arg(long, value_enum, default_value_t = Bucket::Day)]
+    pub bucket: Bucket,
+    /// Also compute the previous window of the same length and show the change per metric
+    #[arg(long)]
+    pub compare: bool,
+    /// Print how long each query took to stderr
+    #[arg(long)]
+    pub timings: bool,
+    /// Redraw every SECONDS (default 5) and highlight changes since the last refresh.
+    /// With --poll-id, shows the proposal's live tally against its thresholds
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        num_args = 0..=1,
+        default_missing_value = "5",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub watch: Option<u64>,
+}
+
+#[derive(Args, Debug)]
+pub struct PollDissentArgs {
+    /// Optional server scope (ID or slug), aggregating every channel in the server
+    #[arg(long)]
+    pub server_id: Option<String>,
+    /// Optional channel scope
+    #[arg(long)]
+    pub channel_id: Option<Uuid>,
+    /// How many of the most frequent blockers to list
+    #[arg(long, default_value_t = 10)]
+    pub top_blockers: u32,
+}
+
+#[derive(Args, Debug)]
+pub struct PollActionsArgs {
+    /// Optional server scope (ID or slug), aggregating every channel in the server
+    #[arg(long)]
+    pub server_id: Option<String>,
+    /// Optional channel scope
+    #[arg(long)]
+    pub channel_id: Option<Uuid>,
+}
+
+#[derive(Args, Debug)]
+pub struct VoteGraphArgs {
+    /// Optional server scope (ID or slug), aggregating every channel in the server
+    #[arg(long)]
+    pub server_id: Option<String>,
+    /// Optional channel scope
+    #[arg(long)]
+    pub channel_id: Option<Uuid>,
+    /// Only compare pairs who voted on at least this many of the same proposals
+    #[arg(long, default_value_t = 3, value_parser = clap::value_parser!(u32).range(1..))]
+    pub min_shared: u32,
+    /// Agreement percentage that links two members into the same cluster
+    #[arg(long, default_value_t = 80, value_parser = clap::value_parser!(u32).range(0..=100))]
+    pub min_agreement: u32,
+    /// Write the graph as GraphML to this file
+    #[arg(long)]
+    pub graphml: Option<PathBuf>,
+    /// Write the graph as Graphviz DOT to this file
+    #[arg(long)]
+    pub dot: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct VoteAnomaliesArgs {
+    /// Optional server scope (ID or slug), aggregating every channel in the server
+    #[arg(long)]
+    pub server_id: Option<String>,
+    /// Optional channel scope
+    #[arg(long)]
+    pub channel_id: Option<Uuid>,
+    /// Treat accounts as fresh when they vote within this many hours of being created
+    #[arg(long, default_value_t = 24)]
+    pub fresh_hours: u32,
+    /// Window in minutes that votes from fresh accounts must fall within to form a burst
+    #[arg(long, default_value_t = 60)]
+    pub burst_minutes: u32,
+    /// Fresh account votes within the burst window needed to flag a proposal
+    #[arg(long, default_value_t = 3, value_parser = clap::value_parser!(u32).range(1..))]
+    pub min_burst: u32,
+    /// Flag proposals where anonymous voters exceed this percentage of voters
+    #[arg(long, default_value_t = 50, value_parser = clap::value_parser!(u32).range(0..=100))]
+    pub max_anonymous_share: u32,
+    /// Fresh accounts with identical votes needed to flag a voting pattern
+    #[arg(long, default_value_t = 3, value_parser = clap::value_parser!(u32).range(2..))]
+    pub min_pattern_accounts: u32,
+}
+
+#[derive(Args, Debug)]
+#[command(group(ArgGroup::new("user").required(true).args(["user_id", "name"])))]
+pub struct UserStatsArgs {
+    /// User ID to profile
+    #[arg(long)]
+    pub user_id: Option<Uuid>,
+    /// Username (`user.name`) to profile
+    #[arg(long)]
+    pub name: Option<String>,
+}
+
+/// Alternative `poll_config` values. Anything left unset keeps each
+/// proposal's own value.
+#[derive(Args, Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleOverrides {
+    /// Decision-making model to apply
+    #[arg(long, value_enum)]
+    pub model: Option<DecisionModel>,
+    /// Percentage of agree/disagree votes that must agree
+    #[arg(long, value_parser = clap::value_parser!(i32).range(0..=100))]
+    pub agreement_threshold: Option<i32>,
+    /// Maximum disagreements before consensus or consent fails
+    #[arg(long, value_parser = clap::value_parser!(i32).range(0..))]
+    pub disagreements_limit: Option<i32>,
+    /// Maximum abstains before consensus or consent fails
+    #[arg(long, value_parser = clap::value_parser!(i32).range(0..))]
+    pub abstains_limit: Option<i32>,
+    /// Whether a quorum of channel members must vote
+    #[arg(long)]
+    pub quorum_enabled: Option<bool>,
+    /// Percentage of channel members required for quorum
+    #[arg(long, value_parser = clap::value_parser!(i32).range(0..=100))]
+    pub quorum_threshold: Option<i32>,
+}
+
+fn parse_percentage(value: &str) -> Result<f64, String> {
+    let percentage: f64 = value
+        .parse()
+        .map_err(|_| format!("'{value}' is not a number"))?;
+    if !(0.0..=100.0).contains(&percentage) {
+        return Err("must be between 0 and 100".to_string());
+    }
+    Ok(percentage)
+}
+
+#[derive(Args, Debug)]
+pub struct ServeMetricsArgs {
+    /// Address to serve `/metrics` on
+    #[arg(long, default_value = "127.0.0.1:9187")]
+    pub listen: SocketAddr,
+    /// Seconds between metric refreshes
+    #[arg(long, default_value_t = 60, value_parser = clap::value_parser!(u64).range(1..))]
+    pub refresh: u64,
+    /// Count members as active when their lastActiveAt falls within this many days
+    #[arg(long, default_value_t = 30)]
+    pub active_days: u32,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum MigrationsCommand {
+    /// List applied, pending and unknown migrations and exit non-zero when the database is behind
+    Status,
+    /// Warn about risky SQL in migration files without connecting to the database
+    Lint(MigrationsLintArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct MigrationsLintArgs {
+    /// Only report on these migration files, e.g. the ones a pull request adds
+    pub files: Vec<PathBuf>,
+    /// Accept a rule instead of failing on it (repeatable)
+    #[arg(long, value_enum)]
+    pub allow: Vec<LintRule>,
+}
+
+#[derive(Args, Debug)]
+pub struct SchemaArgs {
+    /// Save the live schema to this file instead of printing it
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["diff", "check_entities"])]
+    pub snapshot: Option<PathBuf>,
+    /// Compare the live schema against a saved snapshot and exit non-zero on drift
+    #[arg(long, value_name = "FILE", conflicts_with = "check_entities")]
+    pub diff: Option<PathBuf>,
+    /// Compare the TypeORM entities under src/ with the live schema and exit non-zero on mismatches
+    #[arg(long)]
+    pub check_entities: bool,
+}
@@ -0,0 +1,139 @@
+use chrono::{DateTime, Utc};
+use owo_colors::OwoColorize;
+use serde::Serialize;
+
+use crate::output::Record;
+use crate::utils::{format_number, format_timestamp};
+
+/// Metrics for the current window alongside the window of the same length
+/// immediately before it.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Comparison {
+    pub previous_since: DateTime<Utc>,
+    pub previous_until: DateTime<Utc>,
+    pub metrics: Vec<MetricDelta>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Unit {
+    Count,
+    Ratio,
+    /// A share in percent, whose delta is in percentage points
+    Percent,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricDelta {
+    pub metric: String,
+    pub label: String,
+    pub unit: Unit,
+    pub current: f64,
+    pub previous: f64,
+    pub delta: f64,
+    /// Relative change from the previous window, `None` when it was zero
+    pub pct_change: Option<f64>,
+}
+
+impl MetricDelta {
+    pub fn new(
+        metric: impl Into<String>,
+        label: impl Into<String>,
+        unit: Unit,
+        current: f64,
+        previous: f64,
+    ) -> Self {
+        Self {
+            metric: metric.into(),
+            label: label.into(),
+            unit,
+            current,
+            previous,
+            delta: current - previous,
+            pct_change: (previous != 0.0).then(|| (current - previous) / previous * 100.0),
+        }
+    }
+}
+
+fn format_value(value: f64, unit: Unit) -> String {
+    match unit {
+        Unit::Count => format_number(value.round() as i64),
+        Unit::Ratio => format!("{:.1}", value),
+        Unit::Percent => format!("{:.1}%", value),
+    }
+}
+
+fn format_delta(delta: f64, unit: Unit) -> String {
+    let sign = if delta < 0.0 { "-" } else { "+" };
+    match unit {
+        Unit::Count => format!("{}{}", sign, format_number(delta.abs().round() as i64)),
+        Unit::Ratio => format!("{}{:.1}", sign, delta.abs()),
+        Unit::Percent => format!("{}{:.1}pp", sign, delta.abs()),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Rendering
+// ---------------------------------------------------------------------------
+
+pub fn print_comparison(comparison: &Comparison) {
+    println!(
+        "\n{} {}",
+        "Compared with Previous Window".bold(),
+        format!(
+            "({} → {})",
+            format_timestamp(comparison.previous_since, "%Y-%m-%d"),
+            format_timestamp(comparison.previous_until, "%Y-%m-%d")
+        )
+        .dimmed()
+    );
+    println!(
+        "  {:<22} {:>10} {:>10}  {}",
+        "",
+        "now".dimmed(),
+        "before".dimmed(),
+        "change".dimmed()
+    );
+
+    for metric in &comparison.metrics {
+        let pct_change = metric
+            .pct_change
+            .map(|pct| format!(" ({:+.1}%)", pct))
+            .unwrap_or_default();
+        let change = format!("{}{}", format_delta(metric.delta, metric.unit), pct_change);
+        let change = if metric.delta > 0.0 {
+            format!("▲ {}", change).green().to_string()
+        } else if metric.delta < 0.0 {
+            format!("▼ {}", change).red().to_string()
+        } else {
+            "= unchanged".dimmed().to_string()
+        };
+        println!(
+            "  {} {:<20} {:>10} {:>10}  {}",
+            "→".dimmed(),
+            metric.label,
+            format_value(metric.current, metric.unit).bold(),
+            format_value(metric.previous, metric.unit),
+            change
+        );
+    }
+}
+
+pub fn comparison_records(comparison: &Comparison) -> Vec<Record> {
+    comparison
+        .metrics
+        .iter()
+        .map(|metric| {
+            Record::new("comparison")
+                .field("metric", metric.metric.as_str())
+                .field("previousSince", comparison.previous_since.to_rfc3339())
+                .field("previousUntil", comparison.previous_until.to_rfc3339())
+                .field("current", metric.current)
+                .field("previous", metric.previous)
+                .field("delta", metric.delta)
+                .field("pctChange", metric.pct_change)
+        })
+        .collect()
+}
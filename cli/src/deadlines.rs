@@ -0,0 +1,300 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::decision::{
+    QuorumStatus, VoteTally, evaluate, expected_stage, fetch_closing_proposals, now_naive_utc,
+    quorum_status,
+};
+use crate::output::{OutputFormat, Record, Report, render};
+use crate::scope::{ScopeNames, resolve_channel_name, resolve_server};
+use crate::utils::{color_stage, format_channel, format_duration, format_number, format_timestamp};
+
+pub async fn run_poll_deadlines(
+    pool: &PgPool,
+    hours: u32,
+    server_id: Option<&str>,
+    channel_id: Option<Uuid>,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut names = ScopeNames::default();
+    let server_id = match server_id {
+        Some(id_or_slug) => {
+            let (id, name) = resolve_server(pool, id_or_slug).await?;
+            names.server_name = Some(name);
+            Some(id)
+        }
+        None => None,
+    };
+    if let Some(channel_id) = channel_id {
+        names.channel_name = resolve_channel_name(pool, channel_id).await?;
+    }
+
+    let now = now_naive_utc();
+    let cutoff = now + Duration::hours(hours as i64);
+    let proposals = fetch_closing_proposals(pool, cutoff, server_id, channel_id).await?;
+
+    let mut report = PollDeadlinesReport {
+        hours,
+        server_id,
+        channel_id,
+        names,
+        overdue: Vec::new(),
+        upcoming: Vec::new(),
+    };
+
+    for proposal in &proposals {
+        let Some(closing_at) = proposal.closing_at else {
+            continue;
+        };
+        let tally = proposal.tally();
+        let rules = proposal.rules();
+
+        let (quorum, ratifiable, next_stage) = match &rules {
+            Some(rules) => {
+                let evaluation = evaluate(rules, &tally, proposal.member_count, None, now);
+                let stage = expected_stage(&evaluation, Some(closing_at), now);
+                (
+                    quorum_status(rules, &tally, proposal.member_count),
+                    Some(evaluation.ratifiable),
+                    stage.to_string(),
+                )
+            }
+            None => (None, None, "unknown".to_string()),
+        };
+
+        let deadline = PollDeadline {
+            poll_id: proposal.poll_id,
+            channel_id: proposal.channel_id,
+            channel_name: proposal.channel_name.clone(),
+            server_name: proposal.server_name.clone(),
+            decision_making_model: proposal.decision_making_model.clone(),
+            closing_at: closing_at.and_utc(),
+            secs_remaining: (closing_at - now).num_seconds(),
+            tally,
+            member_count: proposal.member_count,
+            quorum,
+            ratifiable,
+            next_stage,
+        };
+
+        if closing_at <= now {
+            report.overdue.push(deadline);
+        } else {
+            report.upcoming.push(deadline);
+        }
+    }
+
+    render(&report, format)
+}
+
+// ---------------------------------------------------------------------------
+// Report
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PollDeadlinesReport {
+    hours: u32,
+    server_id: Option<Uuid>,
+    channel_id: Option<Uuid>,
+    #[serde(flatten)]
+    names: ScopeNames,
+    /// Past `closingAt` but still stored as `voting`, oldest first
+    overdue: Vec<PollDeadline>,
+    /// Closing within `hours`, soonest first
+    upcoming: Vec<PollDeadline>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PollDeadline {
+    poll_id: Uuid,
+    channel_id: Uuid,
+    channel_name: String,
+    server_name: String,
+    decision_making_model: String,
+    closing_at: DateTime<Utc>,
+    /// Negative once the poll is overdue
+    secs_remaining: i64,
+    tally: VoteTally,
+    member_count: i64,
+    /// `None` when the poll's rules don't require a quorum
+    quorum: Option<QuorumStatus>,
+    /// Whether the current votes pass every rule, `None` for unknown models
+    ratifiable: Option<bool>,
+    /// Stage `synchronizePolls` would move an overdue poll to, or `voting`
+    next_stage: String,
+}
+
+impl PollDeadline {
+    fn quorum_label(&self) -> String {
+        match self.quorum {
+            Some(QuorumStatus {
+                votes,
+                required,
+                met: true,
+            }) => format!("quorum met ({}/{})", votes, required)
+                .green()
+                .to_string(),
+            Some(QuorumStatus {
+                votes,
+                required,
+                met: false,
+            }) => format!("quorum not met ({}/{})", votes, required)
+                .yellow()
+                .to_string(),
+            None => "no quorum required".dimmed().to_string(),
+        }
+    }
+
+    fn print(&self) {
+        let VoteTally {
+            agreements,
+            disagreements,
+            abstains,
+            blocks,
+        } = self.tally;
+        let timing = if self.secs_remaining > 0 {
+            format!("closes in {}", format_duration(self.secs_remaining as f64))
+        } else {
+            format!(
+                "closed {} ago",
+                format_duration(-self.secs_remaining as f64)
+            )
+        };
+
+        println!(
+            "{} poll {} in {} — {} {}",
+            "•".cyan(),
+            self.poll_id,
+            format_channel(&self.channel_name, &self.server_name),
+            timing.bold(),
+            format!(
+                "({})",
+                format_timestamp(self.closing_at, "%Y-%m-%d %H:%M %Z")
+            )
+            .dimmed()
+        );
+        println!(
+            "  {}",
+            format!(
+                "{}, {} members, {} agree / {} disagree / {} abstain / {} block",
+                self.decision_making_model,
+                self.member_count,
+                agreements,
+                disagreements,
+                abstains,
+                blocks
+            )
+            .dimmed()
+        );
+
+        let outcome = match self.ratifiable {
+            Some(true) => "currently passing".green().to_string(),
+            Some(false) => "currently failing".red().to_string(),
+            None => "unknown decision model".dimmed().to_string(),
+        };
+        let next = if self.secs_remaining > 0 {
+            String::new()
+        } else {
+            format!(", will sync to {}", color_stage(&self.next_stage))
+        };
+        println!(
+            "  {} {}, {}{}",
+            "→".dimmed(),
+            self.quorum_label(),
+            outcome,
+            next
+        );
+    }
+
+    fn record(&self, section: &'static str) -> Record {
+        Record::new(section)
+            .field("pollId", self.poll_id.to_string())
+            .field("channelId", self.channel_id.to_string())
+            .field("channelName", self.channel_name.as_str())
+            .field("serverName", self.server_name.as_str())
+            .field("decisionMakingModel", self.decision_making_model.as_str())
+            .field("closingAt", self.closing_at.to_rfc3339())
+            .field("secsRemaining", self.secs_remaining)
+            .field("agreements", self.tally.agreements)
+            .field("disagreements", self.tally.disagreements)
+            .field("abstains", self.tally.abstains)
+            .field("blocks", self.tally.blocks)
+            .field("memberCount", self.member_count)
+            .field("quorumVotes", self.quorum.map(|quorum| quorum.votes))
+            .field("quorumRequired", self.quorum.map(|quorum| quorum.required))
+            .field("quorumMet", self.quorum.map(|quorum| quorum.met))
+            .field("ratifiable", self.ratifiable)
+            .field("nextStage", self.next_stage.as_str())
+    }
+}
+
+impl Report for PollDeadlinesReport {
+    const KIND: &'static str = "poll-deadlines";
+    const SCHEMA_VERSION: u32 = 1;
+
+    fn render_text(&self) {
+        println!(
+            "\n{} {}",
+            "Poll Deadlines".bold().underline(),
+            format!("(closing within {} hours)", self.hours).dimmed()
+        );
+        if let Some(scope) = self.names.describe() {
+            println!("{} {}", "•".cyan(), scope.bold());
+        }
+        println!(
+            "{} {} overdue, {} closing soon",
+            "•".cyan(),
+            format_number(self.overdue.len() as i64).bold(),
+            format_number(self.upcoming.len() as i64).bold()
+        );
+
+        if !self.overdue.is_empty() {
+            println!(
+                "\n{} {}",
+                "Overdue".bold(),
+                "(past closingAt, still voting until synchronizePolls runs)".dimmed()
+            );
+            for deadline in &self.overdue {
+                deadline.print();
+            }
+        }
+
+        println!("\n{}", "Closing Soon".bold());
+        if self.upcoming.is_empty() {
+            println!("{}", "  none".dimmed());
+        }
+        for deadline in &self.upcoming {
+            deadline.print();
+        }
+    }
+
+    fn records(&self) -> Vec<Record> {
+        let summary = Record::new("summary")
+            .field("hours", self.hours)
+            .field("serverId", self.server_id.map(|id| id.to_string()))
+            .field("serverName", self.names.server_name.clone())
+            .field("channelId", self.channel_id.map(|id| id.to_string()))
+            .field("channelName", self.names.channel_name.clone())
+            .field("overdue", self.overdue.len())
+            .field("upcoming", self.upcoming.len());
+
+        std::iter::once(summary)
+            .chain(
+                self.overdue
+                    .iter()
+                    .map(|deadline| deadline.record("overdue")),
+            )
+            .chain(
+                self.upcoming
+                    .iter()
+                    .map(|deadline| deadline.record("upcoming")),
+            )
+            .collect()
+    }
+}
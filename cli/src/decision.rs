@@ -0,0 +1,596 @@
+use anyhow::Result;
+use chrono::{NaiveDateTime, Utc};
+use clap::ValueEnum;
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::scope::PollScope;
+use crate::utils::format_timestamp;
+
+// ---------------------------------------------------------------------------
+// Rules
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum DecisionModel {
+    Consensus,
+    Consent,
+    MajorityVote,
+}
+
+impl DecisionModel {
+    pub fn from_db(value: &str) -> Option<Self> {
+        match value {
+            "consensus" => Some(Self::Consensus),
+            "consent" => Some(Self::Consent),
+            "majority-vote" => Some(Self::MajorityVote),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Consensus => "consensus",
+            Self::Consent => "consent",
+            Self::MajorityVote => "majority-vote",
+        }
+    }
+}
+
+/// The subset of a `poll_config` row that decides whether a proposal passes.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PollRules {
+    pub decision_making_model: DecisionModel,
+    pub agreement_threshold: i32,
+    pub disagreements_limit: i32,
+    pub abstains_limit: i32,
+    pub quorum_enabled: bool,
+    pub quorum_threshold: i32,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VoteTally {
+    pub agreements: i64,
+    pub disagreements: i64,
+    pub abstains: i64,
+    pub blocks: i64,
+}
+
+impl VoteTally {
+    pub fn total(&self) -> i64 {
+        self.agreements + self.disagreements + self.abstains + self.blocks
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Evaluation {
+    pub ratifiable: bool,
+    /// Every rule the votes currently fail, empty when ratifiable
+    pub reasons: Vec<String>,
+}
+
+/// Mirrors `getRequiredCount` in `common/polls/poll.utils.ts`, including its
+/// floating point rounding.
+pub fn required_count(member_count: i64, threshold: i32) -> i64 {
+    (member_count as f64 * (threshold as f64 * 0.01)).ceil() as i64
+}
+
+/// Votes cast against the quorum a proposal needs.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuorumStatus {
+    pub votes: i64,
+    pub required: i64,
+    pub met: bool,
+}
+
+/// Whether a proposal needs a quorum at all. Consent ignores quorum entirely,
+/// matching `hasConsent`.
+pub fn quorum_applies(model: DecisionModel, quorum_enabled: bool) -> bool {
+    quorum_enabled && model != DecisionModel::Consent
+}
+
+/// `None` when the rules don't require a quorum.
+pub fn quorum_status(
+    rules: &PollRules,
+    tally: &VoteTally,
+    member_count: i64,
+) -> Option<QuorumStatus> {
+    if !quorum_applies(rules.decision_making_model, rules.quorum_enabled) {
+        return None;
+    }
+    let required = required_count(member_count, rules.quorum_threshold);
+    Some(QuorumStatus {
+        votes: tally.total(),
+        required,
+        met: tally.total() >= required,
+    })
+}
+
+/// Evaluates the votes against the rules as of `now`, mirroring `hasConsensus`,
+/// `hasConsent` and `hasMajorityVote` in `src/polls/polls.service.ts`. Unlike
+/// the server, which stops at the first failing check, this collects every
+/// failure so callers can explain the outcome.
+pub fn evaluate(
+    rules: &PollRules,
+    tally: &VoteTally,
+    member_count: i64,
+    closing_at: Option<NaiveDateTime>,
+    now: NaiveDateTime,
+) -> Evaluation {
+    let mut reasons = Vec::new();
+
+    if let Some(closing_at) = closing_at.filter(|closing_at| now < *closing_at) {
+        reasons.push(format!(
+            "voting open until {}",
+            format_timestamp(closing_at.and_utc(), "%Y-%m-%d %H:%M %Z")
+        ));
+    }
+
+    if let Some(quorum) = quorum_status(rules, tally, member_count)
+        && !quorum.met
+    {
+        reasons.push(format!(
+            "quorum not met ({}/{} votes, {}% of {} members)",
+            quorum.votes, quorum.required, rules.quorum_threshold, member_count
+        ));
+    }
+
+    if rules.decision_making_model != DecisionModel::Consent {
+        let participants = tally.agreements + tally.disagreements;
+        if participants == 0 {
+            reasons.push("no agree or disagree votes".to_string());
+        } else {
+            let required = required_count(participants, rules.agreement_threshold);
+            if tally.agreements < required {
+                reasons.push(format!(
+                    "agreement below threshold ({}/{} needed, {}% of {} agree/disagree votes)",
+                    tally.agreements, required, rules.agreement_threshold, participants
+                ));
+            }
+        }
+    }
+
+    if rules.decision_making_model != DecisionModel::MajorityVote {
+        if tally.disagreements > rules.disagreements_limit as i64 {
+            reasons.push(format!(
+                "{} disagreements exceed limit of {}",
+                tally.disagreements, rules.disagreements_limit
+            ));
+        }
+        if tally.abstains > rules.abstains_limit as i64 {
+            reasons.push(format!(
+                "{} abstains exceed limit of {}",
+                tally.abstains, rules.abstains_limit
+            ));
+        }
+        if tally.blocks > 0 {
+            reasons.push(format!("blocked by {} member(s)", tally.blocks));
+        }
+    }
+
+    Evaluation {
+        ratifiable: reasons.is_empty(),
+        reasons,
+    }
+}
+
+/// The stage a proposal should be in once `synchronizePolls` has caught up.
+pub fn expected_stage(
+    evaluation: &Evaluation,
+    closing_at: Option<NaiveDateTime>,
+    now: NaiveDateTime,
+) -> &'static str {
+    if evaluation.ratifiable {
+        "ratified"
+    } else if closing_at.is_some_and(|closing_at| now >= closing_at) {
+        "closed"
+    } else {
+        "voting"
+    }
+}
+
+pub fn now_naive_utc() -> NaiveDateTime {
+    Utc::now().naive_utc()
+}
+
+// ---------------------------------------------------------------------------
+// Queries
+// ---------------------------------------------------------------------------
+
+/// A proposal with its `poll_config` rules, vote tally and channel size.
+#[derive(Debug, Clone, FromRow)]
+pub struct ProposalRow {
+    pub poll_id: Uuid,
+    pub channel_id: Uuid,
+    pub channel_name: String,
+    pub server_name: String,
+    pub stage: String,
+    pub created_at: NaiveDateTime,
+    pub decision_making_model: String,
+    pub agreement_threshold: i32,
+    pub disagreements_limit: i32,
+    pub abstains_limit: i32,
+    pub quorum_enabled: bool,
+    pub quorum_threshold: i32,
+    pub closing_at: Option<NaiveDateTime>,
+    pub agreements: i64,
+    pub disagreements: i64,
+    pub abstains: i64,
+    pub blocks: i64,
+    pub member_count: i64,
+}
+
+impl ProposalRow {
+    /// `None` when the stored decision model is not one the CLI understands.
+    pub fn rules(&self) -> Option<PollRules> {
+        Some(PollRules {
+            decision_making_model: DecisionModel::from_db(&self.decision_making_model)?,
+            agreement_threshold: self.agreement_threshold,
+            disagreements_limit: self.disagreements_limit,
+            abstains_limit: self.abstains_limit,
+            quorum_enabled: self.quorum_enabled,
+            quorum_threshold: self.quorum_threshold,
+        })
+    }
+
+    pub fn tally(&self) -> VoteTally {
+        VoteTally {
+            agreements: self.agreements,
+            disagreements: self.disagreements,
+            abstains: self.abstains,
+            blocks: self.blocks,
+        }
+    }
+}
+
+/// Columns and joins shared by the proposal queries. Callers append their
+/// own `WHERE` clause; the query is grouped and ordered by `PROPOSAL_GROUP`.
+const PROPOSAL_SELECT: &str = r#"
+        SELECT p.id AS poll_id,
+               p."channelId" AS channel_id,
+               c.name AS channel_name,
+               s.name AS server_name,
+               p.stage::text AS stage,
+               p."createdAt" AS created_at,
+               pc."decisionMakingModel"::text AS decision_making_model,
+               pc."agreementThreshold" AS agreement_threshold,
+               pc."disagreementsLimit" AS disagreements_limit,
+               pc."abstainsLimit" AS abstains_limit,
+               pc."quorumEnabled" AS quorum_enabled,
+               pc."quorumThreshold" AS quorum_threshold,
+               pc."closingAt" AS closing_at,
+               COUNT(v.id) FILTER (WHERE v."voteType" = 'agree')::bigint AS agreements,
+               COUNT(v.id) FILTER (WHERE v."voteType" = 'disagree')::bigint AS disagreements,
+               COUNT(v.id) FILTER (WHERE v."voteType" = 'abstain')::bigint AS abstains,
+               COUNT(v.id) FILTER (WHERE v."voteType" = 'block')::bigint AS blocks,
+               (
+                   SELECT COUNT(*)
+                   FROM channel_member cm
+                   WHERE cm."channelId" = p."channelId"
+               )::bigint AS member_count
+        FROM poll p
+        JOIN poll_config pc ON pc."pollId" = p.id
+        JOIN channel c ON c.id = p."channelId"
+        JOIN server s ON s.id = c."serverId"
+        LEFT JOIN vote v ON v."pollId" = p.id
+"#;
+
+const PROPOSAL_GROUP: &str = r#"
+        GROUP BY p.id, pc.id, c.id, s.id
+"#;
+
+/// Fetches every proposal in `scope` along with the inputs the ratification
+/// rules need. Eligible voters are the members of the proposal's channel, as
+/// in `getPollMemberCount`.
+pub async fn fetch_proposals(pool: &PgPool, scope: &PollScope) -> Result<Vec<ProposalRow>> {
+    let sql = format!(
+        r#"{PROPOSAL_SELECT}
+        WHERE p."pollType" = 'proposal'
+          AND p."createdAt" >= $1 AND p."createdAt" < $5
+          AND ($2::uuid IS NULL OR p."channelId" = $2)
+          AND ($3::uuid IS NULL OR p.id = $3)
+          AND ($4::uuid IS NULL OR c."serverId" = $4)
+        {PROPOSAL_GROUP}
+        ORDER BY p."createdAt" DESC
+        "#
+    );
+    let rows = sqlx::query_as(&sql)
+        .bind(scope.since)
+        .bind(scope.channel_id)
+        .bind(scope.poll_id)
+        .bind(scope.server_id)
+        .bind(scope.until)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows)
+}
+
+/// Fetches proposals still in `voting` whose `closingAt` falls before
+/// `cutoff`, including those already past it that `synchronizePolls` hasn't
+/// caught up with yet.
+pub async fn fetch_closing_proposals(
+    pool: &PgPool,
+    cutoff: NaiveDateTime,
+    server_id: Option<Uuid>,
+    channel_id: Option<Uuid>,
+) -> Result<Vec<ProposalRow>> {
+    let sql = format!(
+        r#"{PROPOSAL_SELECT}
+        WHERE p."pollType" = 'proposal'
+          AND p.stage = 'voting'
+          AND pc."closingAt" IS NOT NULL
+          AND pc."closingAt" <= $1
+          AND ($2::uuid IS NULL OR c."serverId" = $2)
+          AND ($3::uuid IS NULL OR p."channelId" = $3)
+        {PROPOSAL_GROUP}
+        ORDER BY pc."closingAt" ASC
+        "#
+    );
+    let rows = sqlx::query_as(&sql)
+        .bind(cutoff)
+        .bind(server_id)
+        .bind(channel_id)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows)
+}
+
+/// Fetches a single proposal by ID, regardless of when it was created.
+pub async fn fetch_proposal(pool: &PgPool, poll_id: Uuid) -> Result<Option<ProposalRow>> {
+    let sql = format!(
+        r#"{PROPOSAL_SELECT}
+        WHERE p.id = $1
+        {PROPOSAL_GROUP}
+        "#
+    );
+    let row = sqlx::query_as(&sql)
+        .bind(poll_id)
+        .fetch_optional(pool)
+        .await?;
+
+    Ok(row)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn at(hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 1, 1)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    fn rules(model: DecisionModel) -> PollRules {
+        PollRules {
+            decision_making_model: model,
+            agreement_threshold: 51,
+            disagreements_limit: 2,
+            abstains_limit: 2,
+            quorum_enabled: false,
+            quorum_threshold: 25,
+        }
+    }
+
+    fn tally(agreements: i64, disagreements: i64, abstains: i64, blocks: i64) -> VoteTally {
+        VoteTally {
+            agreements,
+            disagreements,
+            abstains,
+            blocks,
+        }
+    }
+
+    #[test]
+    fn required_count_matches_get_required_count() {
+        let cases = [
+            // (members, threshold, required)
+            (10, 50, 5),
+            (3, 51, 2),
+            (0, 51, 0),
+            (4, 100, 4),
+            // 70 * 0.01 is 0.7000000000000001, so JS rounds 7.000000000000001 up
+            (10, 70, 8),
+        ];
+        for (members, threshold, required) in cases {
+            assert_eq!(
+                required_count(members, threshold),
+                required,
+                "{members} members at {threshold}%"
+            );
+        }
+    }
+
+    #[test]
+    fn evaluate_matches_the_server_rules() {
+        let quorum = |model, threshold| PollRules {
+            quorum_enabled: true,
+            quorum_threshold: threshold,
+            ..rules(model)
+        };
+        let majority_at = |threshold| PollRules {
+            agreement_threshold: threshold,
+            ..rules(DecisionModel::MajorityVote)
+        };
+
+        let cases = [
+            // (case, rules, tally, members, closingAt, ratifiable, failed rules)
+            (
+                "consensus passes",
+                rules(DecisionModel::Consensus),
+                tally(5, 1, 1, 0),
+                10,
+                Some(at(9)),
+                true,
+                0,
+            ),
+            (
+                "consensus blocked by a single block",
+                rules(DecisionModel::Consensus),
+                tally(9, 0, 0, 1),
+                10,
+                Some(at(9)),
+                false,
+                1,
+            ),
+            (
+                "consensus at the disagreement and abstain limits",
+                rules(DecisionModel::Consensus),
+                tally(6, 2, 2, 0),
+                10,
+                Some(at(9)),
+                true,
+                0,
+            ),
+            (
+                "consensus over the disagreement and abstain limits",
+                rules(DecisionModel::Consensus),
+                tally(6, 3, 3, 0),
+                12,
+                Some(at(9)),
+                false,
+                2,
+            ),
+            (
+                "consensus still open",
+                rules(DecisionModel::Consensus),
+                tally(5, 0, 0, 0),
+                10,
+                Some(at(11)),
+                false,
+                1,
+            ),
+            (
+                "consensus without agree or disagree votes",
+                rules(DecisionModel::Consensus),
+                tally(0, 0, 2, 0),
+                10,
+                Some(at(9)),
+                false,
+                1,
+            ),
+            (
+                "quorum reached",
+                quorum(DecisionModel::Consensus, 50),
+                tally(4, 1, 0, 0),
+                10,
+                Some(at(9)),
+                true,
+                0,
+            ),
+            (
+                "quorum not reached",
+                quorum(DecisionModel::Consensus, 50),
+                tally(4, 0, 0, 0),
+                10,
+                Some(at(9)),
+                false,
+                1,
+            ),
+            (
+                "majority quorum not reached",
+                quorum(DecisionModel::MajorityVote, 50),
+                tally(4, 0, 0, 0),
+                10,
+                Some(at(9)),
+                false,
+                1,
+            ),
+            (
+                "consent ignores quorum",
+                quorum(DecisionModel::Consent, 100),
+                tally(0, 1, 0, 0),
+                10,
+                Some(at(9)),
+                true,
+                0,
+            ),
+            (
+                "consent with no closingAt counts as closed",
+                rules(DecisionModel::Consent),
+                tally(0, 0, 0, 0),
+                10,
+                None,
+                true,
+                0,
+            ),
+            (
+                "consent blocked by a single block",
+                rules(DecisionModel::Consent),
+                tally(3, 0, 0, 1),
+                10,
+                None,
+                false,
+                1,
+            ),
+            (
+                "majority at exactly the threshold",
+                majority_at(50),
+                tally(2, 2, 0, 0),
+                10,
+                Some(at(9)),
+                true,
+                0,
+            ),
+            (
+                "majority one vote below the threshold",
+                majority_at(51),
+                tally(2, 2, 0, 0),
+                10,
+                Some(at(9)),
+                false,
+                1,
+            ),
+            (
+                "majority ignores limits and blocks",
+                majority_at(51),
+                tally(7, 3, 5, 2),
+                20,
+                Some(at(9)),
+                true,
+                0,
+            ),
+        ];
+
+        for (case, rules, tally, members, closing_at, ratifiable, failed) in cases {
+            let evaluation = evaluate(&rules, &tally, members, closing_at, at(10));
+            assert_eq!(evaluation.ratifiable, ratifiable, "{case}");
+            assert_eq!(
+                evaluation.reasons.len(),
+                failed,
+                "{case}: {:?}",
+                evaluation.reasons
+            );
+        }
+    }
+
+    #[test]
+    fn quorum_status_is_none_for_consent_or_when_disabled() {
+        let tally = tally(3, 0, 0, 0);
+        let enabled = PollRules {
+            quorum_enabled: true,
+            quorum_threshold: 50,
+            ..rules(DecisionModel::Consensus)
+        };
+        let consent = PollRules {
+            decision_making_model: DecisionModel::Consent,
+            ..enabled
+        };
+
+        assert!(quorum_status(&rules(DecisionModel::Consensus), &tally, 10).is_none());
+        assert!(quorum_status(&consent, &tally, 10).is_none());
+        let status = quorum_status(&enabled, &tally, 10).unwrap();
+        assert_eq!((status.votes, status.required, status.met), (3, 5, false));
+    }
+}
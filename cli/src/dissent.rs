@@ -0,0 +1,424 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::cli::PollDissentArgs;
+use crate::output::{OutputFormat, Record, Report, render};
+use crate::scope::{PollScope, ScopeNames, resolve_channel_name, resolve_server};
+use crate::utils::{color_stage, color_vote, format_channel, format_number, pct};
+use crate::window::TimeWindow;
+
+pub async fn run_poll_dissent(
+    pool: &PgPool,
+    window: TimeWindow,
+    args: &PollDissentArgs,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut names = ScopeNames::default();
+    let mut server_id = None;
+    if let Some(id_or_slug) = &args.server_id {
+        let (id, name) = resolve_server(pool, id_or_slug).await?;
+        server_id = Some(id);
+        names.server_name = Some(name);
+    }
+    if let Some(channel_id) = args.channel_id {
+        names.channel_name = resolve_channel_name(pool, channel_id).await?;
+    }
+
+    let scope = PollScope {
+        server_id,
+        channel_id: args.channel_id,
+        ..PollScope::new(&window)
+    };
+    let report = collect_dissent(pool, window, scope, names, args.top_blockers).await?;
+    render(&report, format)
+}
+
+async fn collect_dissent(
+    pool: &PgPool,
+    window: TimeWindow,
+    scope: PollScope,
+    names: ScopeNames,
+    top_blockers: u32,
+) -> Result<PollDissentReport> {
+    let proposals: Vec<ProposalDissent> = sqlx::query_as(
+        r#"
+        SELECT p.id AS poll_id,
+               p."channelId" AS channel_id,
+               c.name AS channel_name,
+               s.name AS server_name,
+               p.stage::text AS stage,
+               COUNT(v.id)::bigint AS votes,
+               COUNT(v.id) FILTER (WHERE v."voteType" = 'disagree')::bigint AS disagreements,
+               COUNT(v.id) FILTER (WHERE v."voteType" = 'block')::bigint AS blocks
+        FROM poll p
+        JOIN channel c ON c.id = p."channelId"
+        JOIN server s ON s.id = c."serverId"
+        LEFT JOIN vote v ON v."pollId" = p.id
+        WHERE p."pollType" = 'proposal'
+          AND p."createdAt" >= $1 AND p."createdAt" < $5
+          AND ($2::uuid IS NULL OR p."channelId" = $2)
+          AND ($3::uuid IS NULL OR p.id = $3)
+          AND ($4::uuid IS NULL OR c."serverId" = $4)
+        GROUP BY p.id, c.id, s.id
+        ORDER BY p."createdAt" DESC
+        "#,
+    )
+    .bind(scope.since)
+    .bind(scope.channel_id)
+    .bind(scope.poll_id)
+    .bind(scope.server_id)
+    .bind(scope.until)
+    .fetch_all(pool)
+    .await?;
+
+    let blockers: Vec<Blocker> = sqlx::query_as(
+        r#"
+        SELECT u.id AS user_id,
+               u.name AS user_name,
+               COUNT(*) FILTER (WHERE v."voteType" = 'block')::bigint AS blocks,
+               COUNT(*)::bigint AS votes
+        FROM vote v
+        JOIN poll p ON p.id = v."pollId"
+        JOIN channel c ON c.id = p."channelId"
+        JOIN "user" u ON u.id = v."userId"
+        WHERE p."pollType" = 'proposal'
+          AND p."createdAt" >= $1 AND p."createdAt" < $5
+          AND ($2::uuid IS NULL OR p."channelId" = $2)
+          AND ($3::uuid IS NULL OR p.id = $3)
+          AND ($4::uuid IS NULL OR c."serverId" = $4)
+        GROUP BY u.id
+        HAVING COUNT(*) FILTER (WHERE v."voteType" = 'block') > 0
+        ORDER BY blocks DESC, votes ASC
+        LIMIT $6
+        "#,
+    )
+    .bind(scope.since)
+    .bind(scope.channel_id)
+    .bind(scope.poll_id)
+    .bind(scope.server_id)
+    .bind(scope.until)
+    .bind(top_blockers as i64)
+    .fetch_all(pool)
+    .await?;
+
+    let mut overall = DissentTotals::default();
+    let mut channels: BTreeMap<Uuid, ChannelDissent> = BTreeMap::new();
+    let mut blocked_stages: BTreeMap<String, i64> = BTreeMap::new();
+    for proposal in &proposals {
+        overall.add(proposal);
+        channels
+            .entry(proposal.channel_id)
+            .or_insert_with(|| ChannelDissent {
+                channel_id: proposal.channel_id,
+                channel_name: proposal.channel_name.clone(),
+                server_name: proposal.server_name.clone(),
+                totals: DissentTotals::default(),
+            })
+            .totals
+            .add(proposal);
+        if proposal.blocks > 0 {
+            *blocked_stages.entry(proposal.stage.clone()).or_default() += 1;
+        }
+    }
+
+    let mut by_channel: Vec<ChannelDissent> = channels.into_values().collect();
+    by_channel.sort_by(|a, b| b.totals.block_rate.total_cmp(&a.totals.block_rate));
+
+    let mut multi_block: Vec<ProposalDissent> = proposals
+        .into_iter()
+        .filter(|proposal| proposal.blocks > 1)
+        .collect();
+    multi_block.sort_by_key(|proposal| std::cmp::Reverse(proposal.blocks));
+
+    Ok(PollDissentReport {
+        window_days: window.days(),
+        window,
+        server_id: scope.server_id,
+        channel_id: scope.channel_id,
+        names,
+        overall,
+        by_channel,
+        multi_block,
+        blocked_stages: blocked_stages
+            .into_iter()
+            .map(|(stage, count)| StageCount { stage, count })
+            .collect(),
+        top_blockers: blockers,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Report
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PollDissentReport {
+    window_days: i64,
+    #[serde(flatten)]
+    window: TimeWindow,
+    server_id: Option<Uuid>,
+    channel_id: Option<Uuid>,
+    #[serde(flatten)]
+    names: ScopeNames,
+    overall: DissentTotals,
+    /// Highest block rate first
+    by_channel: Vec<ChannelDissent>,
+    /// Proposals carrying more than one block
+    multi_block: Vec<ProposalDissent>,
+    /// Current stage of every proposal currently carrying a block. Polls keep
+    /// only their latest stage, so this is not the path the proposal took.
+    blocked_stages: Vec<StageCount>,
+    top_blockers: Vec<Blocker>,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DissentTotals {
+    proposals: i64,
+    votes: i64,
+    disagreements: i64,
+    blocks: i64,
+    /// Proposals with at least one block
+    blocked_proposals: i64,
+    /// Blocks as a share of all votes on proposals
+    block_rate: f64,
+    /// Blocks and disagreements as a share of all votes on proposals
+    dissent_rate: f64,
+}
+
+impl DissentTotals {
+    fn add(&mut self, proposal: &ProposalDissent) {
+        self.proposals += 1;
+        self.votes += proposal.votes;
+        self.disagreements += proposal.disagreements;
+        self.blocks += proposal.blocks;
+        self.blocked_proposals += (proposal.blocks > 0) as i64;
+        self.block_rate = pct(self.blocks, self.votes);
+        self.dissent_rate = pct(self.blocks + self.disagreements, self.votes);
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChannelDissent {
+    channel_id: Uuid,
+    channel_name: String,
+    server_name: String,
+    #[serde(flatten)]
+    totals: DissentTotals,
+}
+
+#[derive(Debug, FromRow, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProposalDissent {
+    poll_id: Uuid,
+    channel_id: Uuid,
+    channel_name: String,
+    server_name: String,
+    stage: String,
+    votes: i64,
+    disagreements: i64,
+    blocks: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StageCount {
+    stage: String,
+    count: i64,
+}
+
+#[derive(Debug, FromRow, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Blocker {
+    user_id: Uuid,
+    user_name: String,
+    blocks: i64,
+    /// Every vote the user cast on proposals in scope
+    votes: i64,
+}
+
+impl Report for PollDissentReport {
+    const KIND: &'static str = "poll-dissent";
+    const SCHEMA_VERSION: u32 = 1;
+
+    fn render_text(&self) {
+        let scope = self
+            .names
+            .describe()
+            .map(|scope| format!(", {}", scope))
+            .unwrap_or_default();
+        println!(
+            "\n{} {}",
+            "Poll Dissent".bold().underline(),
+            format!("(proposals from {}{})", self.window.describe(), scope).dimmed()
+        );
+
+        let overall = &self.overall;
+        println!(
+            "{} {} proposals, {} votes",
+            "•".cyan(),
+            format_number(overall.proposals).bold(),
+            format_number(overall.votes).bold()
+        );
+        println!(
+            "{} {} {} ({:.1}% of votes), {} {} ({:.1}% dissent overall)",
+            "•".cyan(),
+            format_number(overall.blocks).bold(),
+            color_vote("block"),
+            overall.block_rate,
+            format_number(overall.disagreements).bold(),
+            color_vote("disagree"),
+            overall.dissent_rate
+        );
+        println!(
+            "{} {} proposals currently carrying a block ({:.1}%)",
+            "•".cyan(),
+            format_number(overall.blocked_proposals).bold(),
+            pct(overall.blocked_proposals, overall.proposals)
+        );
+
+        if self.by_channel.len() > 1 {
+            println!("\n{}", "Block Rate by Channel".bold());
+            for channel in &self.by_channel {
+                let totals = &channel.totals;
+                println!(
+                    "{} {} — {} ({}/{} votes), {} of {} proposals blocked, {:.1}% dissent",
+                    "•".cyan(),
+                    format_channel(&channel.channel_name, &channel.server_name),
+                    format!("{:.1}%", totals.block_rate).bold(),
+                    totals.blocks,
+                    totals.votes,
+                    totals.blocked_proposals,
+                    totals.proposals,
+                    totals.dissent_rate
+                );
+            }
+        }
+
+        if !self.blocked_stages.is_empty() {
+            println!(
+                "\n{} {}",
+                "Currently Blocked Proposals".bold(),
+                "(current stage)".dimmed()
+            );
+            for StageCount { stage, count } in &self.blocked_stages {
+                println!(
+                    "  {} {} {:>6} ({:>5.1}%)",
+                    "→".dimmed(),
+                    color_stage(&format!("{:<10}", stage)),
+                    count.to_string().bold(),
+                    pct(*count, overall.blocked_proposals)
+                );
+            }
+            println!(
+                "  {}",
+                "Blocked proposals that went through revision and were later ratified \
+                 can't be counted: polls keep only their current stage."
+                    .dimmed()
+            );
+        }
+
+        if !self.multi_block.is_empty() {
+            println!("\n{}", "Proposals with Multiple Blocks".bold());
+            for proposal in &self.multi_block {
+                println!(
+                    "{} poll {} in {} — {} blocks, {} disagree of {} votes, {}",
+                    "•".cyan(),
+                    proposal.poll_id,
+                    format_channel(&proposal.channel_name, &proposal.server_name),
+                    proposal.blocks.to_string().bold(),
+                    proposal.disagreements,
+                    proposal.votes,
+                    color_stage(&proposal.stage)
+                );
+            }
+        }
+
+        if !self.top_blockers.is_empty() {
+            println!("\n{}", "Top Blockers".bold());
+            for blocker in &self.top_blockers {
+                println!(
+                    "{} {} — {} blocks ({:.1}% of their {} votes)",
+                    "•".cyan(),
+                    blocker.user_name.bold(),
+                    blocker.blocks.to_string().bold(),
+                    pct(blocker.blocks, blocker.votes),
+                    blocker.votes
+                );
+            }
+        }
+    }
+
+    fn records(&self) -> Vec<Record> {
+        let totals_record = |record: Record, totals: &DissentTotals| {
+            record
+                .field("proposals", totals.proposals)
+                .field("votes", totals.votes)
+                .field("disagreements", totals.disagreements)
+                .field("blocks", totals.blocks)
+                .field("blockedProposals", totals.blocked_proposals)
+                .field("blockRate", totals.block_rate)
+                .field("dissentRate", totals.dissent_rate)
+        };
+
+        let mut records = vec![totals_record(
+            Record::new("summary")
+                .field("windowDays", self.window_days)
+                .field("since", self.window.since.to_rfc3339())
+                .field("until", self.window.until.to_rfc3339())
+                .field("serverId", self.server_id.map(|id| id.to_string()))
+                .field("serverName", self.names.server_name.clone())
+                .field("channelId", self.channel_id.map(|id| id.to_string()))
+                .field("channelName", self.names.channel_name.clone()),
+            &self.overall,
+        )];
+
+        for channel in &self.by_channel {
+            records.push(totals_record(
+                Record::new("byChannel")
+                    .field("channelId", channel.channel_id.to_string())
+                    .field("channelName", channel.channel_name.as_str())
+                    .field("serverName", channel.server_name.as_str()),
+                &channel.totals,
+            ));
+        }
+        for StageCount { stage, count } in &self.blocked_stages {
+            records.push(
+                Record::new("blockedStages")
+                    .field("stage", stage.as_str())
+                    .field("count", *count),
+            );
+        }
+        for proposal in &self.multi_block {
+            records.push(
+                Record::new("multiBlock")
+                    .field("pollId", proposal.poll_id.to_string())
+                    .field("channelId", proposal.channel_id.to_string())
+                    .field("channelName", proposal.channel_name.as_str())
+                    .field("serverName", proposal.server_name.as_str())
+                    .field("stage", proposal.stage.as_str())
+                    .field("votes", proposal.votes)
+                    .field("disagreements", proposal.disagreements)
+                    .field("blocks", proposal.blocks),
+            );
+        }
+        for blocker in &self.top_blockers {
+            records.push(
+                Record::new("topBlockers")
+                    .field("userId", blocker.user_id.to_string())
+                    .field("userName", blocker.user_name.as_str())
+                    .field("blocks", blocker.blocks)
+                    .field("votes", blocker.votes),
+            );
+        }
+
+        records
+    }
+}
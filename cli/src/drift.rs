@@ -0,0 +1,550 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use owo_colors::OwoColorize;
+use serde::Serialize;
+
+use crate::output::{Record, Report};
+use crate::schema::{ColumnInfo, EnumInfo, Schema, TableInfo};
+
+/// Compares the live schema against a saved snapshot. `Added` and `Removed`
+/// are from the live database's point of view: an added column exists in the
+/// database but not in the snapshot.
+pub fn diff_schema(
+    snapshot: &Schema,
+    live: &Schema,
+    path: &Path,
+    taken_at: DateTime<Utc>,
+) -> SchemaDrift {
+    let mut changes = Vec::new();
+    diff_enums(&snapshot.enums, &live.enums, &mut changes);
+    diff_tables(&snapshot.tables, &live.tables, &mut changes);
+
+    SchemaDrift {
+        snapshot: path.display().to_string(),
+        snapshot_taken_at: taken_at,
+        changes,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Report
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaDrift {
+    snapshot: String,
+    snapshot_taken_at: DateTime<Utc>,
+    pub changes: Vec<SchemaChange>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaChange {
+    object: ObjectKind,
+    /// Qualified name, e.g. `vote.pollId` for a column
+    name: String,
+    change: ChangeKind,
+    detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum ObjectKind {
+    Enum,
+    EnumLabel,
+    Table,
+    Column,
+    Index,
+    Constraint,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl ObjectKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            ObjectKind::Enum => "enum",
+            ObjectKind::EnumLabel => "enumLabel",
+            ObjectKind::Table => "table",
+            ObjectKind::Column => "column",
+            ObjectKind::Index => "index",
+            ObjectKind::Constraint => "constraint",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ObjectKind::EnumLabel => "enum label",
+            other => other.as_str(),
+        }
+    }
+}
+
+impl ChangeKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            ChangeKind::Added => "added",
+            ChangeKind::Removed => "removed",
+            ChangeKind::Changed => "changed",
+        }
+    }
+}
+
+impl SchemaChange {
+    fn new(object: ObjectKind, name: String, change: ChangeKind) -> Self {
+        Self {
+            object,
+            name,
+            change,
+            detail: None,
+        }
+    }
+
+    fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+}
+
+impl Report for SchemaDrift {
+    const KIND: &'static str = "schema-diff";
+    const SCHEMA_VERSION: u32 = 1;
+
+    fn render_text(&self) {
+        println!(
+            "\n{} {}",
+            "Schema Drift".bold().underline(),
+            format!(
+                "(live database vs {}, taken {})",
+                self.snapshot,
+                self.snapshot_taken_at.format("%Y-%m-%d %H:%M UTC")
+            )
+            .dimmed()
+        );
+
+        if self.changes.is_empty() {
+            println!("{} The live schema matches the snapshot", "✓".green());
+            return;
+        }
+
+        let count = |kind| self.changes.iter().filter(|c| c.change == kind).count();
+        println!(
+            "{} {} added, {} removed, {} changed",
+            "•".cyan(),
+            count(ChangeKind::Added).to_string().bold(),
+            count(ChangeKind::Removed).to_string().bold(),
+            count(ChangeKind::Changed).to_string().bold()
+        );
+
+        println!();
+        for change in &self.changes {
+            let object = format!("{:<12}", change.object.label());
+            let marker = match change.change {
+                ChangeKind::Added => "+".green().bold().to_string(),
+                ChangeKind::Removed => "-".red().bold().to_string(),
+                ChangeKind::Changed => "~".yellow().bold().to_string(),
+            };
+            let detail = change
+                .detail
+                .as_ref()
+                .map(|detail| format!("  {}", detail))
+                .unwrap_or_default();
+            println!(
+                "  {} {} {}{}",
+                marker,
+                object.dimmed(),
+                change.name,
+                detail.dimmed()
+            );
+        }
+    }
+
+    fn records(&self) -> Vec<Record> {
+        let mut records = vec![
+            Record::new("summary")
+                .field("snapshot", self.snapshot.as_str())
+                .field("snapshotTakenAt", self.snapshot_taken_at.to_rfc3339())
+                .field("changes", self.changes.len()),
+        ];
+        for change in &self.changes {
+            records.push(
+                Record::new("changes")
+                    .field("object", change.object.as_str())
+                    .field("name", change.name.as_str())
+                    .field("change", change.change.as_str())
+                    .field("detail", change.detail.clone()),
+            );
+        }
+        records
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Comparison
+// ---------------------------------------------------------------------------
+
+/// Pairs up items by name, in name order.
+fn pair_by_name<'a, T>(
+    before: &'a [T],
+    after: &'a [T],
+    name: impl Fn(&T) -> &str,
+) -> Vec<(String, Option<&'a T>, Option<&'a T>)> {
+    let mut pairs: BTreeMap<String, (Option<&T>, Option<&T>)> = BTreeMap::new();
+    for item in before {
+        pairs.entry(name(item).to_string()).or_default().0 = Some(item);
+    }
+    for item in after {
+        pairs.entry(name(item).to_string()).or_default().1 = Some(item);
+    }
+    pairs
+        .into_iter()
+        .map(|(name, (before, after))| (name, before, after))
+        .collect()
+}
+
+fn diff_enums(before: &[EnumInfo], after: &[EnumInfo], changes: &mut Vec<SchemaChange>) {
+    for (name, before, after) in pair_by_name(before, after, |e| &e.name) {
+        match (before, after) {
+            (None, Some(after)) => changes.push(
+                SchemaChange::new(ObjectKind::Enum, name, ChangeKind::Added)
+                    .detail(after.values.join(", ")),
+            ),
+            (Some(_), None) => changes.push(SchemaChange::new(
+                ObjectKind::Enum,
+                name,
+                ChangeKind::Removed,
+            )),
+            (Some(before), Some(after)) => {
+                let before_labels: BTreeSet<&String> = before.values.iter().collect();
+                let after_labels: BTreeSet<&String> = after.values.iter().collect();
+                for label in after_labels.difference(&before_labels) {
+                    changes.push(SchemaChange::new(
+                        ObjectKind::EnumLabel,
+                        format!("{}.{}", name, label),
+                        ChangeKind::Added,
+                    ));
+                }
+                for label in before_labels.difference(&after_labels) {
+                    changes.push(SchemaChange::new(
+                        ObjectKind::EnumLabel,
+                        format!("{}.{}", name, label),
+                        ChangeKind::Removed,
+                    ));
+                }
+                // Postgres compares enum values by position, so a reorder
+                // changes how they sort
+                if before_labels == after_labels && before.values != after.values {
+                    changes.push(
+                        SchemaChange::new(ObjectKind::Enum, name, ChangeKind::Changed).detail(
+                            format!(
+                                "label order {} → {}",
+                                before.values.join(", "),
+                                after.values.join(", ")
+                            ),
+                        ),
+                    );
+                }
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+fn diff_tables(before: &[TableInfo], after: &[TableInfo], changes: &mut Vec<SchemaChange>) {
+    for (name, before, after) in pair_by_name(before, after, |t| &t.name) {
+        match (before, after) {
+            (None, Some(after)) => changes.push(
+                SchemaChange::new(ObjectKind::Table, name, ChangeKind::Added)
+                    .detail(format!("{} columns", after.columns.len())),
+            ),
+            (Some(_), None) => changes.push(SchemaChange::new(
+                ObjectKind::Table,
+                name,
+                ChangeKind::Removed,
+            )),
+            (Some(before), Some(after)) => diff_table(&name, before, after, changes),
+            (None, None) => {}
+        }
+    }
+}
+
+fn diff_table(table: &str, before: &TableInfo, after: &TableInfo, changes: &mut Vec<SchemaChange>) {
+    for (name, before, after) in pair_by_name(&before.columns, &after.columns, |c| &c.name) {
+        let name = format!("{}.{}", table, name);
+        match (before, after) {
+            (None, Some(after)) => changes.push(
+                SchemaChange::new(ObjectKind::Column, name, ChangeKind::Added)
+                    .detail(describe_column(after)),
+            ),
+            (Some(before), None) => changes.push(
+                SchemaChange::new(ObjectKind::Column, name, ChangeKind::Removed)
+                    .detail(describe_column(before)),
+            ),
+            (Some(before), Some(after)) => {
+                if let Some(detail) = column_changes(before, after) {
+                    changes.push(
+                        SchemaChange::new(ObjectKind::Column, name, ChangeKind::Changed)
+                            .detail(detail),
+                    );
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    let indexes = pair_by_name(&before.indexes, &after.indexes, |i| &i.name)
+        .into_iter()
+        .map(|(name, before, after)| {
+            (
+                name,
+                before.map(|i| i.definition.as_str()),
+                after.map(|i| i.definition.as_str()),
+            )
+        });
+    diff_definitions(table, ObjectKind::Index, indexes, changes);
+
+    let constraints = pair_by_name(&before.constraints, &after.constraints, |c| &c.name)
+        .into_iter()
+        .map(|(name, before, after)| {
+            (
+                name,
+                before.map(|c| c.definition.as_str()),
+                after.map(|c| c.definition.as_str()),
+            )
+        });
+    diff_definitions(table, ObjectKind::Constraint, constraints, changes);
+}
+
+/// Indexes and constraints are compared by their generated definition.
+fn diff_definitions<'a>(
+    table: &str,
+    object: ObjectKind,
+    pairs: impl Iterator<Item = (String, Option<&'a str>, Option<&'a str>)>,
+    changes: &mut Vec<SchemaChange>,
+) {
+    for (name, before, after) in pairs {
+        let name = format!("{}.{}", table, name);
+        match (before, after) {
+            (None, Some(after)) => {
+                changes.push(SchemaChange::new(object, name, ChangeKind::Added).detail(after))
+            }
+            (Some(before), None) => {
+                changes.push(SchemaChange::new(object, name, ChangeKind::Removed).detail(before))
+            }
+            (Some(before), Some(after)) if before != after => changes.push(
+                SchemaChange::new(object, name, ChangeKind::Changed)
+                    .detail(format!("{} → {}", before, after)),
+            ),
+            _ => {}
+        }
+    }
+}
+
+fn column_changes(before: &ColumnInfo, after: &ColumnInfo) -> Option<String> {
+    let mut details = Vec::new();
+    if before.data_type != after.data_type {
+        details.push(format!("type {} → {}", before.data_type, after.data_type));
+    }
+    if before.nullable != after.nullable {
+        details.push(format!(
+            "{} → {}",
+            nullability(before.nullable),
+            nullability(after.nullable)
+        ));
+    }
+    if before.default_value != after.default_value {
+        details.push(format!(
+            "default {} → {}",
+            before.default_value.as_deref().unwrap_or("none"),
+            after.default_value.as_deref().unwrap_or("none")
+        ));
+    }
+    (!details.is_empty()).then(|| details.join("; "))
+}
+
+fn describe_column(column: &ColumnInfo) -> String {
+    let default = column
+        .default_value
+        .as_ref()
+        .map(|default| format!(" = {}", default))
+        .unwrap_or_default();
+    format!(
+        "{} {}{}",
+        column.data_type,
+        nullability(column.nullable),
+        default
+    )
+}
+
+fn nullability(nullable: bool) -> &'static str {
+    if nullable { "null" } else { "not null" }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::schema::IndexInfo;
+
+    use super::*;
+
+    fn column(name: &str, data_type: &str, nullable: bool, default: Option<&str>) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable,
+            default_value: default.map(str::to_string),
+        }
+    }
+
+    fn vote_table(columns: Vec<ColumnInfo>, index: &str) -> TableInfo {
+        TableInfo {
+            name: "vote".to_string(),
+            columns,
+            indexes: vec![IndexInfo {
+                name: "IDX_vote_poll".to_string(),
+                definition: index.to_string(),
+            }],
+            constraints: Vec::new(),
+        }
+    }
+
+    fn vote_type(values: &[&str]) -> EnumInfo {
+        EnumInfo {
+            name: "vote_votetype_enum".to_string(),
+            values: values.iter().map(|value| value.to_string()).collect(),
+        }
+    }
+
+    fn changes(
+        snapshot: &Schema,
+        live: &Schema,
+    ) -> Vec<(ObjectKind, String, ChangeKind, Option<String>)> {
+        diff_schema(snapshot, live, Path::new("schema.json"), Utc::now())
+            .changes
+            .into_iter()
+            .map(|change| (change.object, change.name, change.change, change.detail))
+            .collect()
+    }
+
+    #[test]
+    fn identical_schemas_have_no_changes() {
+        let schema = || Schema {
+            enums: vec![vote_type(&["agree", "disagree"])],
+            tables: vec![vote_table(
+                vec![column("id", "uuid", false, Some("uuid_generate_v4()"))],
+                r#"CREATE INDEX "IDX_vote_poll" ON public.vote USING btree ("pollId")"#,
+            )],
+        };
+        assert!(changes(&schema(), &schema()).is_empty());
+    }
+
+    #[test]
+    fn reports_column_index_and_table_changes() {
+        let snapshot = Schema {
+            enums: Vec::new(),
+            tables: vec![vote_table(
+                vec![
+                    column("body", "character varying", true, None),
+                    column("weight", "integer", false, Some("1")),
+                    column("removed", "text", true, None),
+                ],
+                r#"CREATE INDEX "IDX_vote_poll" ON public.vote USING btree ("pollId")"#,
+            )],
+        };
+        let live = Schema {
+            enums: Vec::new(),
+            tables: vec![vote_table(
+                vec![
+                    column("body", "text", false, None),
+                    column("weight", "integer", false, Some("0")),
+                    column("added", "boolean", false, Some("false")),
+                ],
+                r#"CREATE UNIQUE INDEX "IDX_vote_poll" ON public.vote USING btree ("pollId", "userId")"#,
+            )],
+        };
+
+        assert_eq!(
+            changes(&snapshot, &live),
+            vec![
+                (
+                    ObjectKind::Column,
+                    "vote.added".to_string(),
+                    ChangeKind::Added,
+                    Some("boolean not null = false".to_string()),
+                ),
+                (
+                    ObjectKind::Column,
+                    "vote.body".to_string(),
+                    ChangeKind::Changed,
+                    Some("type character varying → text; null → not null".to_string()),
+                ),
+                (
+                    ObjectKind::Column,
+                    "vote.removed".to_string(),
+                    ChangeKind::Removed,
+                    Some("text null".to_string()),
+                ),
+                (
+                    ObjectKind::Column,
+                    "vote.weight".to_string(),
+                    ChangeKind::Changed,
+                    Some("default 1 → 0".to_string()),
+                ),
+                (
+                    ObjectKind::Index,
+                    "vote.IDX_vote_poll".to_string(),
+                    ChangeKind::Changed,
+                    Some(
+                        r#"CREATE INDEX "IDX_vote_poll" ON public.vote USING btree ("pollId") → CREATE UNIQUE INDEX "IDX_vote_poll" ON public.vote USING btree ("pollId", "userId")"#
+                            .to_string()
+                    ),
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn enum_reorder_is_a_change_not_an_add_and_remove() {
+        let snapshot = Schema {
+            enums: vec![vote_type(&["agree", "disagree", "block"])],
+            tables: Vec::new(),
+        };
+        let reordered = Schema {
+            enums: vec![vote_type(&["block", "agree", "disagree"])],
+            tables: Vec::new(),
+        };
+        assert_eq!(
+            changes(&snapshot, &reordered),
+            vec![(
+                ObjectKind::Enum,
+                "vote_votetype_enum".to_string(),
+                ChangeKind::Changed,
+                Some("label order agree, disagree, block → block, agree, disagree".to_string()),
+            )]
+        );
+
+        let relabelled = Schema {
+            enums: vec![vote_type(&["agree", "disagree", "abstain"])],
+            tables: Vec::new(),
+        };
+        let kinds: Vec<(String, ChangeKind)> = changes(&snapshot, &relabelled)
+            .into_iter()
+            .map(|(_, name, change, _)| (name, change))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("vote_votetype_enum.abstain".to_string(), ChangeKind::Added),
+                ("vote_votetype_enum.block".to_string(), ChangeKind::Removed),
+            ]
+        );
+    }
+}
@@ -0,0 +1,1272 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use anyhow::{Context, Result};
+use glob::glob;
+use owo_colors::OwoColorize;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::output::{Record, Report};
+use crate::routes::find_src_dir;
+use crate::schema::{ColumnInfo, Schema, TableInfo};
+
+// ---------------------------------------------------------------------------
+// Pre-compiled regexes
+// ---------------------------------------------------------------------------
+
+/// Matches `@Entity(...)` followed by `export class Foo`
+static ENTITY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"@Entity\(([^)]*)\)\s*(?:@\w+\([^)]*\)\s*)*export\s+class\s+(\w+)").unwrap()
+});
+
+/// Matches the start of a decorator call, e.g. `@Column(`
+static DECORATOR_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^@(\w+)\(").unwrap());
+
+/// Matches a property declaration, e.g. `pollId?: string | null;`
+static PROPERTY_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\w+)[?!]?\s*:\s*([^;=]+)").unwrap());
+
+/// Matches `key: value` pairs in an options object literal
+static OPTION_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(\w+)\s*:\s*('[^']*'|"[^"]*"|\[[^\]]*\]|\(\)\s*=>\s*(?:'[^']*'|"[^"]*")|[^,}\s]+)"#,
+    )
+    .unwrap()
+});
+
+/// Matches `() => Target` in a relation decorator
+static TARGET_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*\(\s*\)\s*=>\s*(\w+)").unwrap());
+
+/// Matches `export const FOO = [...]`
+static CONST_ARRAY_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^(?:export\s+)?const\s+(\w+)\s*=\s*\[([^\]]*)\]").unwrap());
+
+/// Matches `export enum Foo { ... }`
+static TS_ENUM_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^(?:export\s+)?enum\s+(\w+)\s*\{([^}]*)\}").unwrap());
+
+/// Matches a `Member = value` entry in a TypeScript enum
+static TS_ENUM_MEMBER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\w+)\s*=\s*([^,]+)").unwrap());
+
+/// Matches a quoted string
+static STRING_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"'([^']*)'|"([^"]*)""#).unwrap());
+
+/// Matches `// ...` line comments
+static LINE_COMMENT_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?m)^\s*//.*$").unwrap());
+
+// ---------------------------------------------------------------------------
+// Data structures
+// ---------------------------------------------------------------------------
+
+/// Everything we extract from one `@Entity()` class.
+#[derive(Debug)]
+struct ParsedEntity {
+    class: String,
+    table: String,
+    /// Path relative to the repository root
+    file: String,
+    columns: Vec<EntityColumn>,
+    relations: Vec<EntityRelation>,
+}
+
+/// A property declared with a column decorator.
+#[derive(Debug)]
+struct EntityColumn {
+    name: String,
+    decorator: String,
+    /// The options object plus any leading `'type'` argument
+    options: HashMap<String, String>,
+    /// The TypeScript type, used when the decorator doesn't give one
+    ts_type: String,
+}
+
+/// A `@ManyToOne`, `@OneToOne` or `@ManyToMany` property.
+#[derive(Debug)]
+struct EntityRelation {
+    property: String,
+    target: String,
+    nullable: bool,
+    /// `@JoinColumn()` name, or `None` when the relation has no join column
+    join_column: Option<String>,
+    /// `@JoinTable()` name, or `None` when the relation has no join table
+    join_table: Option<String>,
+}
+
+/// Constants an entity can refer to for enum values and defaults.
+#[derive(Debug, Default)]
+struct Constants {
+    arrays: HashMap<String, Vec<String>>,
+    enums: HashMap<String, Vec<(String, String)>>,
+}
+
+// ---------------------------------------------------------------------------
+// Entry point
+// ---------------------------------------------------------------------------
+
+/// Compares the TypeORM entities under `src/` with the live schema.
+pub fn check_entities(schema: &Schema) -> Result<EntityCheck> {
+    let src_dir = find_src_dir()?;
+    let root = src_dir.parent().unwrap_or(&src_dir).to_path_buf();
+
+    let constants = collect_constants(&root)?;
+    let mut entities = Vec::new();
+    for file_path in entity_files(&src_dir)? {
+        entities.extend(parse_entity_file(&file_path, &root)?);
+    }
+    entities.sort_by(|a, b| a.table.cmp(&b.table));
+
+    let mismatches = compare(&entities, schema, &constants);
+    Ok(EntityCheck {
+        entities: entities.len(),
+        tables: schema.tables.len(),
+        mismatches,
+    })
+}
+
+fn entity_files(src_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = BTreeSet::new();
+    // `*.entity.ts` also covers the files under `entities/` directories
+    let pattern = src_dir.join("**/*.entity.ts");
+    for entry in glob(&pattern.to_string_lossy()).context("failed to read glob pattern")? {
+        files.insert(entry?);
+    }
+    Ok(files.into_iter().collect())
+}
+
+// ---------------------------------------------------------------------------
+// Parse entity files
+// ---------------------------------------------------------------------------
+
+fn parse_entity_file(file_path: &Path, root: &Path) -> Result<Vec<ParsedEntity>> {
+    let content = std::fs::read_to_string(file_path)
+        .with_context(|| format!("failed to read {}", file_path.display()))?;
+    let file = file_path
+        .strip_prefix(root)
+        .unwrap_or(file_path)
+        .display()
+        .to_string();
+    Ok(parse_entities(&content, &file))
+}
+
+/// Every `@Entity()` class in the source of one file.
+fn parse_entities(content: &str, file: &str) -> Vec<ParsedEntity> {
+    let content = LINE_COMMENT_RE.replace_all(content, "");
+    let starts: Vec<_> = ENTITY_RE.captures_iter(&content).collect();
+    let mut entities = Vec::new();
+    for (i, cap) in starts.iter().enumerate() {
+        let whole = cap.get(0).unwrap();
+        let class = cap[2].to_string();
+        let table = entity_table_name(&cap[1]).unwrap_or_else(|| snake_case(&class));
+
+        // The class body runs until the next entity in the same file
+        let end = starts
+            .get(i + 1)
+            .map(|next| next.get(0).unwrap().start())
+            .unwrap_or(content.len());
+        let body = &content[whole.end()..end];
+
+        let mut entity = ParsedEntity {
+            class,
+            table,
+            file: file.to_string(),
+            columns: Vec::new(),
+            relations: Vec::new(),
+        };
+        parse_members(body, &mut entity);
+        entities.push(entity);
+    }
+    entities
+}
+
+/// `@Entity('name')` or `@Entity({ name: 'name' })`
+fn entity_table_name(args: &str) -> Option<String> {
+    let args = args.trim();
+    if let Some(value) = unquote(args) {
+        return Some(value);
+    }
+    parse_options(args)
+        .get("name")
+        .and_then(|name| unquote(name))
+}
+
+/// Walks the class body, attaching each run of decorators to the property
+/// that follows it.
+fn parse_members(body: &str, entity: &mut ParsedEntity) {
+    let mut decorators: Vec<(String, String)> = Vec::new();
+    let mut rest = body;
+
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+
+        if let Some(cap) = DECORATOR_RE.captures(rest) {
+            let name = cap[1].to_string();
+            let open = cap.get(0).unwrap().end();
+            let Some(close) = matching_paren(rest, open) else {
+                break;
+            };
+            decorators.push((name, rest[open..close].to_string()));
+            rest = &rest[close + 1..];
+            continue;
+        }
+
+        if let Some(cap) = PROPERTY_RE.captures(rest) {
+            if !decorators.is_empty() {
+                add_member(
+                    entity,
+                    &cap[1],
+                    cap[2].trim(),
+                    std::mem::take(&mut decorators),
+                );
+            }
+            rest = &rest[cap.get(0).unwrap().end()..];
+            rest = rest.trim_start_matches([';', '=']);
+            continue;
+        }
+
+        // Skip anything else (methods, closing braces) one line at a time
+        decorators.clear();
+        rest = rest.split_once('\n').map(|(_, tail)| tail).unwrap_or("");
+    }
+}
+
+fn add_member(
+    entity: &mut ParsedEntity,
+    property: &str,
+    ts_type: &str,
+    decorators: Vec<(String, String)>,
+) {
+    let join_column = decorators
+        .iter()
+        .find(|(name, _)| name == "JoinColumn")
+        .map(|(_, args)| {
+            parse_options(args)
+                .get("name")
+                .and_then(|name| unquote(name))
+                .unwrap_or_else(|| format!("{}Id", property))
+        });
+    let join_table = decorators
+        .iter()
+        .find(|(name, _)| name == "JoinTable")
+        .map(|(_, args)| {
+            parse_options(args)
+                .get("name")
+                .and_then(|name| unquote(name))
+                .unwrap_or_default()
+        });
+
+    for (decorator, args) in &decorators {
+        match decorator.as_str() {
+            "Column"
+            | "PrimaryColumn"
+            | "PrimaryGeneratedColumn"
+            | "CreateDateColumn"
+            | "UpdateDateColumn"
+            | "DeleteDateColumn" => {
+                let mut options = parse_options(args);
+                if let Some(first) = args.split(',').next().and_then(unquote) {
+                    options
+                        .entry("type".to_string())
+                        .or_insert(format!("'{}'", first));
+                }
+                let name = options
+                    .get("name")
+                    .and_then(|name| unquote(name))
+                    .unwrap_or_else(|| property.to_string());
+                entity.columns.push(EntityColumn {
+                    name,
+                    decorator: decorator.clone(),
+                    options,
+                    ts_type: ts_type.to_string(),
+                });
+            }
+            "ManyToOne" | "OneToOne" | "ManyToMany" => {
+                let Some(target) = TARGET_RE.captures(args).map(|cap| cap[1].to_string()) else {
+                    continue;
+                };
+                // Relations are nullable unless they say otherwise
+                let nullable = parse_options(args)
+                    .get("nullable")
+                    .is_none_or(|value| value != "false");
+                entity.relations.push(EntityRelation {
+                    property: property.to_string(),
+                    target,
+                    nullable,
+                    // A many-to-one always owns its foreign key column
+                    join_column: if decorator == "ManyToOne" {
+                        join_column
+                            .clone()
+                            .or_else(|| Some(format!("{}Id", property)))
+                    } else if decorator == "OneToOne" {
+                        join_column.clone()
+                    } else {
+                        None
+                    },
+                    join_table: if decorator == "ManyToMany" {
+                        join_table.clone()
+                    } else {
+                        None
+                    },
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Index of the `)` closing the `(` just before `open`.
+fn matching_paren(text: &str, open: usize) -> Option<usize> {
+    let mut depth = 1;
+    let mut quote = None;
+    for (i, c) in text[open..].char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The top-level `key: value` pairs of the first options object in `args`.
+fn parse_options(args: &str) -> HashMap<String, String> {
+    let Some(start) = args.find('{') else {
+        return HashMap::new();
+    };
+    let object = &args[start + 1..];
+    let mut options = HashMap::new();
+    let mut depth = 0;
+    let mut top_level = String::new();
+    for c in object.chars() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => break,
+            '}' => depth -= 1,
+            _ if depth == 0 => top_level.push(c),
+            _ => {}
+        }
+    }
+    for cap in OPTION_RE.captures_iter(&top_level) {
+        options.insert(cap[1].to_string(), cap[2].trim().to_string());
+    }
+    options
+}
+
+fn unquote(value: &str) -> Option<String> {
+    let value = value.trim();
+    let cap = STRING_RE.captures(value)?;
+    (cap.get(0)?.as_str() == value).then(|| cap.get(1).or(cap.get(2)).unwrap().as_str().to_string())
+}
+
+/// TypeORM's default table naming: `PollActionRole` becomes
+/// `poll_action_role`.
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_lower) {
+                out.push('_');
+            }
+        }
+        out.extend(c.to_lowercase());
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Parse constants
+// ---------------------------------------------------------------------------
+
+/// String arrays (`export const VOTE_TYPES = [...] as const`) and TypeScript
+/// enums from `src/` and `common/`.
+fn collect_constants(root: &Path) -> Result<Constants> {
+    let mut constants = Constants::default();
+    for dir in ["src", "common"] {
+        let pattern = root.join(dir).join("**/*.ts");
+        for entry in glob(&pattern.to_string_lossy()).context("failed to read glob pattern")? {
+            let file_path = entry?;
+            let content = std::fs::read_to_string(&file_path)
+                .with_context(|| format!("failed to read {}", file_path.display()))?;
+
+            for cap in CONST_ARRAY_RE.captures_iter(&content) {
+                let values: Vec<String> = STRING_RE
+                    .captures_iter(&cap[2])
+                    .map(|s| s.get(1).or(s.get(2)).unwrap().as_str().to_string())
+                    .collect();
+                if !values.is_empty() {
+                    constants.arrays.insert(cap[1].to_string(), values);
+                }
+            }
+            for cap in TS_ENUM_RE.captures_iter(&content) {
+                let members = TS_ENUM_MEMBER_RE
+                    .captures_iter(&cap[2])
+                    .filter_map(|m| Some((m[1].to_string(), evaluate(&m[2])?)))
+                    .collect();
+                constants.enums.insert(cap[1].to_string(), members);
+            }
+        }
+    }
+    Ok(constants)
+}
+
+/// A string literal, or an integer literal or product like `60 * 24`.
+fn evaluate(expression: &str) -> Option<String> {
+    let expression = expression.trim();
+    if let Some(value) = unquote(expression) {
+        return Some(value);
+    }
+    expression
+        .split('*')
+        .map(|factor| factor.trim().parse::<i64>().ok())
+        .product::<Option<i64>>()
+        .map(|value| value.to_string())
+}
+
+impl Constants {
+    /// Values for an `enum:` option: an inline array, a constant array or a
+    /// TypeScript enum.
+    fn enum_values(&self, option: &str) -> Option<Vec<String>> {
+        if option.starts_with('[') {
+            return Some(
+                STRING_RE
+                    .captures_iter(option)
+                    .map(|s| s.get(1).or(s.get(2)).unwrap().as_str().to_string())
+                    .collect(),
+            );
+        }
+        if let Some(values) = self.arrays.get(option) {
+            return Some(values.clone());
+        }
+        self.enums
+            .get(option)
+            .map(|members| members.iter().map(|(_, value)| value.clone()).collect())
+    }
+
+    /// A `default:` option as the value Postgres would show, or `None` when
+    /// it can't be resolved statically.
+    fn default_value(&self, option: &str) -> Option<String> {
+        let option = option.trim();
+        if let Some(sql) = option.strip_prefix("()") {
+            let sql = sql.trim_start().strip_prefix("=>")?;
+            return unquote(sql).map(|sql| normalize_sql_default(&sql));
+        }
+        if let Some(value) = unquote(option) {
+            return Some(value);
+        }
+        if option == "true" || option == "false" || option.parse::<f64>().is_ok() {
+            return Some(option.to_string());
+        }
+        let (name, member) = option.split_once('.')?;
+        self.enums
+            .get(name)?
+            .iter()
+            .find(|(key, _)| key == member)
+            .map(|(_, value)| value.clone())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Compare with the database
+// ---------------------------------------------------------------------------
+
+/// What the entity says a column should look like, in the same terms as
+/// `information_schema.columns`.
+struct ExpectedColumn {
+    name: String,
+    data_type: Option<String>,
+    nullable: bool,
+    default_value: Option<Option<String>>,
+    enum_values: Option<Vec<String>>,
+}
+
+fn compare(entities: &[ParsedEntity], schema: &Schema, constants: &Constants) -> Vec<Mismatch> {
+    let tables: HashMap<&str, &TableInfo> = schema
+        .tables
+        .iter()
+        .map(|table| (table.name.as_str(), table))
+        .collect();
+    let by_class: HashMap<&str, &ParsedEntity> = entities
+        .iter()
+        .map(|entity| (entity.class.as_str(), entity))
+        .collect();
+
+    let mut mismatches = Vec::new();
+    let mut known_tables: BTreeSet<String> = BTreeSet::from(["migrations".to_string()]);
+
+    for entity in entities {
+        known_tables.insert(entity.table.clone());
+        for relation in &entity.relations {
+            if let (Some(name), Some(target)) =
+                (&relation.join_table, by_class.get(relation.target.as_str()))
+            {
+                known_tables.insert(if name.is_empty() {
+                    snake_case(&format!(
+                        "{}_{}_{}",
+                        entity.table, relation.property, target.table
+                    ))
+                } else {
+                    name.clone()
+                });
+            }
+        }
+
+        let Some(table) = tables.get(entity.table.as_str()) else {
+            mismatches.push(
+                Mismatch::new(entity, None, MismatchKind::MissingTable).detail(format!(
+                    "{} maps to {}, which doesn't exist",
+                    entity.class, entity.table
+                )),
+            );
+            continue;
+        };
+
+        let expected = expected_columns(entity, &by_class, constants);
+        for column in &expected {
+            let Some(actual) = table.columns.iter().find(|c| c.name == column.name) else {
+                mismatches.push(
+                    Mismatch::new(entity, Some(&column.name), MismatchKind::MissingColumn)
+                        .expected(column.data_type.clone().unwrap_or_default()),
+                );
+                continue;
+            };
+            compare_column(entity, column, actual, schema, &mut mismatches);
+        }
+
+        for actual in &table.columns {
+            if !expected.iter().any(|column| column.name == actual.name) {
+                mismatches.push(
+                    Mismatch::new(entity, Some(&actual.name), MismatchKind::UndeclaredColumn)
+                        .actual(actual.data_type.clone()),
+                );
+            }
+        }
+
+        compare_foreign_keys(entity, &by_class, constants, &mut mismatches);
+    }
+
+    for table in &schema.tables {
+        if !known_tables.contains(&table.name) {
+            mismatches.push(Mismatch {
+                entity: None,
+                file: None,
+                table: table.name.clone(),
+                column: None,
+                kind: MismatchKind::UndeclaredTable,
+                expected: None,
+                actual: None,
+                detail: Some("no entity maps to this table".to_string()),
+            });
+        }
+    }
+
+    mismatches
+}
+
+fn compare_column(
+    entity: &ParsedEntity,
+    expected: &ExpectedColumn,
+    actual: &ColumnInfo,
+    schema: &Schema,
+    mismatches: &mut Vec<Mismatch>,
+) {
+    let column = Some(expected.name.as_str());
+
+    if let Some(data_type) = &expected.data_type
+        && *data_type != actual.data_type
+    {
+        mismatches.push(
+            Mismatch::new(entity, column, MismatchKind::Type)
+                .expected(data_type.clone())
+                .actual(actual.data_type.clone()),
+        );
+    }
+
+    if expected.nullable != actual.nullable {
+        mismatches.push(
+            Mismatch::new(entity, column, MismatchKind::Nullable)
+                .expected(nullability(expected.nullable))
+                .actual(nullability(actual.nullable)),
+        );
+    }
+
+    if let Some(default_value) = &expected.default_value {
+        let actual_default = actual.default_value.as_deref().map(normalize_db_default);
+        if *default_value != actual_default {
+            mismatches.push(
+                Mismatch::new(entity, column, MismatchKind::Default)
+                    .expected(default_value.clone().unwrap_or_else(|| "none".to_string()))
+                    .actual(actual_default.unwrap_or_else(|| "none".to_string())),
+            );
+        }
+    }
+
+    if let Some(values) = &expected.enum_values {
+        let labels = schema
+            .enums
+            .iter()
+            .find(|e| e.name == actual.data_type.trim_start_matches('_'))
+            .map(|e| &e.values);
+        if let Some(labels) = labels
+            && labels != values
+        {
+            mismatches.push(
+                Mismatch::new(entity, column, MismatchKind::EnumLabels)
+                    .expected(values.join(", "))
+                    .actual(labels.join(", ")),
+            );
+        }
+    }
+}
+
+/// A foreign key column declared with its own `@Column` must have the same
+/// type as the primary key it references.
+fn compare_foreign_keys(
+    entity: &ParsedEntity,
+    by_class: &HashMap<&str, &ParsedEntity>,
+    constants: &Constants,
+    mismatches: &mut Vec<Mismatch>,
+) {
+    for relation in &entity.relations {
+        let (Some(join_column), Some(target)) = (
+            &relation.join_column,
+            by_class.get(relation.target.as_str()),
+        ) else {
+            continue;
+        };
+        let Some(column) = entity.columns.iter().find(|c| c.name == *join_column) else {
+            continue;
+        };
+        let Some(primary) = target
+            .columns
+            .iter()
+            .find(|c| c.decorator.starts_with("Primary"))
+        else {
+            continue;
+        };
+
+        let column_type = expected_type(entity, column, constants);
+        let primary_type = expected_type(target, primary, constants);
+        if let (Some(column_type), Some(primary_type)) = (column_type, primary_type)
+            && column_type != primary_type
+        {
+            mismatches.push(
+                Mismatch::new(entity, Some(join_column), MismatchKind::ForeignKeyType)
+                    .expected(column_type.clone())
+                    .actual(primary_type.clone())
+                    .detail(format!(
+                        "{}.{} is {} but references {}.{} ({})",
+                        entity.table,
+                        join_column,
+                        column_type,
+                        target.table,
+                        primary.name,
+                        primary_type
+                    )),
+            );
+        }
+    }
+}
+
+fn expected_columns(
+    entity: &ParsedEntity,
+    by_class: &HashMap<&str, &ParsedEntity>,
+    constants: &Constants,
+) -> Vec<ExpectedColumn> {
+    let mut columns: Vec<ExpectedColumn> = entity
+        .columns
+        .iter()
+        .map(|column| {
+            let generated = matches!(
+                column.decorator.as_str(),
+                "PrimaryGeneratedColumn" | "CreateDateColumn" | "UpdateDateColumn"
+            );
+            let nullable = match column.decorator.as_str() {
+                "PrimaryColumn"
+                | "PrimaryGeneratedColumn"
+                | "CreateDateColumn"
+                | "UpdateDateColumn" => false,
+                "DeleteDateColumn" => true,
+                _ => column.options.get("nullable").is_some_and(|v| v == "true"),
+            };
+            // Generated columns get their defaults from TypeORM itself, and
+            // defaults that refer to other code can't be resolved statically
+            let default_value = if generated {
+                None
+            } else {
+                match column.options.get("default") {
+                    Some(option) => constants.default_value(option).map(Some),
+                    None => Some(None),
+                }
+            };
+            ExpectedColumn {
+                name: column.name.clone(),
+                data_type: expected_type(entity, column, constants),
+                nullable,
+                default_value,
+                enum_values: column
+                    .options
+                    .get("enum")
+                    .and_then(|option| constants.enum_values(option)),
+            }
+        })
+        .collect();
+
+    // Join columns that aren't declared separately take the type of the
+    // primary key they reference
+    for relation in &entity.relations {
+        let Some(join_column) = &relation.join_column else {
+            continue;
+        };
+        if columns.iter().any(|column| column.name == *join_column) {
+            continue;
+        }
+        let data_type = by_class.get(relation.target.as_str()).and_then(|target| {
+            target
+                .columns
+                .iter()
+                .find(|c| c.decorator.starts_with("Primary"))
+                .and_then(|primary| expected_type(target, primary, constants))
+        });
+        columns.push(ExpectedColumn {
+            name: join_column.clone(),
+            data_type,
+            nullable: relation.nullable,
+            default_value: Some(None),
+            enum_values: None,
+        });
+    }
+
+    columns
+}
+
+/// The column's type as `schema` reports it, e.g. `character varying` or
+/// `vote_votetype_enum`.
+fn expected_type(
+    entity: &ParsedEntity,
+    column: &EntityColumn,
+    constants: &Constants,
+) -> Option<String> {
+    let option = column.options.get("type").and_then(|t| unquote(t));
+    let base = match column.decorator.as_str() {
+        "PrimaryGeneratedColumn" => match option.as_deref() {
+            Some("uuid") => "uuid".to_string(),
+            _ => "integer".to_string(),
+        },
+        "CreateDateColumn" | "UpdateDateColumn" | "DeleteDateColumn" => {
+            option.unwrap_or_else(|| "timestamp".to_string())
+        }
+        _ => match option {
+            Some(option) => option,
+            None if column.options.contains_key("enum") => "enum".to_string(),
+            None => match column.ts_type.split('|').next().unwrap_or_default().trim() {
+                "string" => "varchar".to_string(),
+                "number" => "integer".to_string(),
+                "boolean" => "boolean".to_string(),
+                "Date" => "timestamp".to_string(),
+                _ => return None,
+            },
+        },
+    };
+
+    let array = column.options.get("array").is_some_and(|v| v == "true");
+    if base == "enum" || base == "simple-enum" {
+        let name = column
+            .options
+            .get("enumName")
+            .and_then(|name| unquote(name))
+            .unwrap_or_else(|| format!("{}_{}_enum", entity.table, column.name.to_lowercase()));
+        // Only enums we can resolve are compared by name
+        column
+            .options
+            .get("enum")
+            .and_then(|option| constants.enum_values(option))?;
+        return Some(if array { format!("_{}", name) } else { name });
+    }
+
+    let (data_type, udt) = match base.as_str() {
+        "varchar" | "character varying" => ("character varying", "varchar"),
+        "char" | "character" => ("character", "bpchar"),
+        "text" => ("text", "text"),
+        "uuid" => ("uuid", "uuid"),
+        "int" | "integer" | "int4" => ("integer", "int4"),
+        "smallint" | "int2" => ("smallint", "int2"),
+        "bigint" | "int8" => ("bigint", "int8"),
+        "boolean" | "bool" => ("boolean", "bool"),
+        "float" | "double precision" | "float8" => ("double precision(53,0)", "float8"),
+        "real" | "float4" => ("real(24,0)", "float4"),
+        "timestamp" | "timestamp without time zone" => ("timestamp without time zone", "timestamp"),
+        "timestamptz" | "timestamp with time zone" => ("timestamp with time zone", "timestamptz"),
+        "date" => ("date", "date"),
+        "bytea" => ("bytea", "bytea"),
+        "json" => ("json", "json"),
+        "jsonb" => ("jsonb", "jsonb"),
+        other => return Some(other.to_string()),
+    };
+    if array {
+        return Some(format!("_{}", udt));
+    }
+    Some(match column.options.get("length") {
+        Some(length) => format!("{}({})", data_type, length.trim_matches(['\'', '"'])),
+        None => data_type.to_string(),
+    })
+}
+
+/// Strips casts and quotes: `'consensus'::poll_config_decisionmakingmodel_enum`
+/// becomes `consensus`.
+fn normalize_db_default(default: &str) -> String {
+    let mut value = default.trim();
+    while let Some((head, cast)) = value.rsplit_once("::") {
+        if cast.contains(['\'', '(']) {
+            break;
+        }
+        value = head;
+    }
+    let value = value.trim_start_matches('(').trim_end_matches(')');
+    match value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        Some(inner) => inner.replace("''", "'"),
+        None => normalize_sql_default(default.trim()),
+    }
+}
+
+fn normalize_sql_default(sql: &str) -> String {
+    match sql.to_lowercase().as_str() {
+        "current_timestamp" | "now()" => "now()".to_string(),
+        _ => sql.to_string(),
+    }
+}
+
+fn nullability(nullable: bool) -> &'static str {
+    if nullable { "null" } else { "not null" }
+}
+
+// ---------------------------------------------------------------------------
+// Report
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityCheck {
+    entities: usize,
+    tables: usize,
+    pub mismatches: Vec<Mismatch>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Mismatch {
+    entity: Option<String>,
+    file: Option<String>,
+    table: String,
+    column: Option<String>,
+    kind: MismatchKind,
+    /// What the entity declares
+    expected: Option<String>,
+    /// What the database has, or for `foreignKeyType` the type of the
+    /// referenced primary key
+    actual: Option<String>,
+    detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum MismatchKind {
+    MissingTable,
+    UndeclaredTable,
+    MissingColumn,
+    UndeclaredColumn,
+    Type,
+    Nullable,
+    Default,
+    EnumLabels,
+    ForeignKeyType,
+}
+
+impl MismatchKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            MismatchKind::MissingTable => "missingTable",
+            MismatchKind::UndeclaredTable => "undeclaredTable",
+            MismatchKind::MissingColumn => "missingColumn",
+            MismatchKind::UndeclaredColumn => "undeclaredColumn",
+            MismatchKind::Type => "type",
+            MismatchKind::Nullable => "nullable",
+            MismatchKind::Default => "default",
+            MismatchKind::EnumLabels => "enumLabels",
+            MismatchKind::ForeignKeyType => "foreignKeyType",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            MismatchKind::MissingTable => "table missing from database",
+            MismatchKind::UndeclaredTable => "table without entity",
+            MismatchKind::MissingColumn => "column missing from database",
+            MismatchKind::UndeclaredColumn => "column not in entity",
+            MismatchKind::Type => "type",
+            MismatchKind::Nullable => "nullability",
+            MismatchKind::Default => "default",
+            MismatchKind::EnumLabels => "enum labels",
+            MismatchKind::ForeignKeyType => "foreign key type",
+        }
+    }
+}
+
+impl Mismatch {
+    fn new(entity: &ParsedEntity, column: Option<&str>, kind: MismatchKind) -> Self {
+        Self {
+            entity: Some(entity.class.clone()),
+            file: Some(entity.file.clone()),
+            table: entity.table.clone(),
+            column: column.map(str::to_string),
+            kind,
+            expected: None,
+            actual: None,
+            detail: None,
+        }
+    }
+
+    fn expected(mut self, value: impl Into<String>) -> Self {
+        self.expected = Some(value.into());
+        self
+    }
+
+    fn actual(mut self, value: impl Into<String>) -> Self {
+        self.actual = Some(value.into());
+        self
+    }
+
+    fn detail(mut self, value: impl Into<String>) -> Self {
+        self.detail = Some(value.into());
+        self
+    }
+
+    fn describe(&self) -> String {
+        if let Some(detail) = &self.detail {
+            return detail.clone();
+        }
+        match (&self.expected, &self.actual) {
+            (Some(expected), Some(actual)) => {
+                format!("entity {}, database {}", expected, actual)
+            }
+            (Some(expected), None) => format!("entity {}", expected),
+            (None, Some(actual)) => format!("database {}", actual),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+impl Report for EntityCheck {
+    const KIND: &'static str = "entity-check";
+    const SCHEMA_VERSION: u32 = 1;
+
+    fn render_text(&self) {
+        println!(
+            "\n{} {}",
+            "Entity Check".bold().underline(),
+            format!(
+                "({} entities vs {} database tables)",
+                self.entities, self.tables
+            )
+            .dimmed()
+        );
+
+        if self.mismatches.is_empty() {
+            println!("{} Every entity matches the database", "✓".green());
+            return;
+        }
+        println!(
+            "{} {} mismatches",
+            "•".cyan(),
+            self.mismatches.len().to_string().bold()
+        );
+
+        let mut current_table = None;
+        for mismatch in &self.mismatches {
+            if current_table != Some(&mismatch.table) {
+                current_table = Some(&mismatch.table);
+                let source = match (&mismatch.entity, &mismatch.file) {
+                    (Some(entity), Some(file)) => format!(" ({} in {})", entity, file),
+                    _ => String::new(),
+                };
+                println!("\n{}{}", mismatch.table.green().bold(), source.dimmed());
+            }
+            let target = match &mismatch.column {
+                Some(column) => format!("{}.{}", mismatch.table, column),
+                None => mismatch.table.clone(),
+            };
+            println!(
+                "  {} {} {} {}",
+                "✗".red(),
+                target,
+                format!("{}:", mismatch.kind.label()).yellow(),
+                mismatch.describe()
+            );
+        }
+    }
+
+    fn records(&self) -> Vec<Record> {
+        let mut records = vec![
+            Record::new("summary")
+                .field("entities", self.entities)
+                .field("tables", self.tables)
+                .field("mismatches", self.mismatches.len()),
+        ];
+        for mismatch in &self.mismatches {
+            records.push(
+                Record::new("mismatches")
+                    .field("table", mismatch.table.as_str())
+                    .field("column", mismatch.column.clone())
+                    .field("kind", mismatch.kind.as_str())
+                    .field("expected", mismatch.expected.clone())
+                    .field("actual", mismatch.actual.clone())
+                    .field("detail", mismatch.detail.clone())
+                    .field("entity", mismatch.entity.clone())
+                    .field("file", mismatch.file.clone()),
+            );
+        }
+        records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLL: &str = r#"
+import { Column, Entity, OneToMany, PrimaryGeneratedColumn } from 'typeorm';
+
+@Entity()
+export class Poll {
+  @PrimaryGeneratedColumn('uuid')
+  id: string;
+
+  @Column({
+    type: 'enum',
+    enum: PollStage,
+    default: PollStage.Voting,
+  })
+  stage: PollStage;
+
+  @OneToMany(() => Vote, (vote) => vote.poll)
+  votes: Vote[];
+}
+"#;
+
+    const VOTE: &str = r#"
+@Entity()
+@Unique(['pollId', 'userId'])
+export class Vote {
+  @PrimaryGeneratedColumn('uuid')
+  id: string;
+
+  @Column({ type: 'enum', enum: VOTE_TYPES })
+  voteType: VoteType;
+
+  @ManyToOne(() => Poll, (poll) => poll.votes, {
+    onDelete: 'CASCADE',
+  })
+  poll?: Poll;
+
+  @Column({ type: 'varchar', nullable: true })
+  pollId: string | null;
+}
+"#;
+
+    fn parse(source: &str) -> Vec<ParsedEntity> {
+        parse_entities(source, "src/test.entity.ts")
+    }
+
+    fn constants() -> Constants {
+        Constants {
+            arrays: HashMap::from([(
+                "VOTE_TYPES".to_string(),
+                vec![
+                    "agree".to_string(),
+                    "disagree".to_string(),
+                    "block".to_string(),
+                ],
+            )]),
+            enums: HashMap::from([(
+                "PollStage".to_string(),
+                vec![
+                    ("Voting".to_string(), "voting".to_string()),
+                    ("Ratified".to_string(), "ratified".to_string()),
+                ],
+            )]),
+        }
+    }
+
+    fn column(name: &str, data_type: &str, nullable: bool) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            nullable,
+            default_value: None,
+        }
+    }
+
+    fn table(name: &str, columns: Vec<ColumnInfo>) -> TableInfo {
+        TableInfo {
+            name: name.to_string(),
+            columns,
+            indexes: Vec::new(),
+            constraints: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn table_names_from_entity_decorator() {
+        let entities = parse(
+            r#"
+@Entity('poll_configs')
+export class PollConfig {}
+
+@Entity({ name: 'server_roles', schema: 'public' })
+export class ServerRole {}
+
+@Entity()
+export class PollActionRole {}
+"#,
+        );
+        let tables: Vec<&str> = entities.iter().map(|e| e.table.as_str()).collect();
+        assert_eq!(tables, ["poll_configs", "server_roles", "poll_action_role"]);
+    }
+
+    #[test]
+    fn multi_line_enum_column_with_default() {
+        let entities = parse(POLL);
+        let poll = &entities[0];
+        let stage = poll.columns.iter().find(|c| c.name == "stage").unwrap();
+        assert_eq!(stage.options["type"], "'enum'");
+        assert_eq!(stage.options["enum"], "PollStage");
+        assert_eq!(stage.options["default"], "PollStage.Voting");
+
+        let expected = expected_columns(poll, &HashMap::new(), &constants());
+        let stage = expected.iter().find(|c| c.name == "stage").unwrap();
+        assert_eq!(stage.data_type.as_deref(), Some("poll_stage_enum"));
+        assert_eq!(stage.default_value, Some(Some("voting".to_string())));
+        assert_eq!(
+            stage.enum_values,
+            Some(vec!["voting".to_string(), "ratified".to_string()])
+        );
+        assert!(!stage.nullable);
+    }
+
+    #[test]
+    fn relations_with_and_without_join_column() {
+        let entities = parse(
+            r#"
+@Entity()
+export class Channel {
+  @ManyToOne(() => Server, (server) => server.channels)
+  server: Server;
+
+  @ManyToOne(() => Channel, { nullable: false })
+  @JoinColumn({ name: 'parentChannelId' })
+  parent: Channel;
+
+  @OneToOne(() => ChannelConfig)
+  config: ChannelConfig;
+
+  @OneToOne(() => Image)
+  @JoinColumn()
+  image: Image;
+
+  @ManyToMany(() => User)
+  @JoinTable()
+  members: User[];
+}
+"#,
+        );
+        let relations: Vec<(&str, &str, Option<&str>, bool)> = entities[0]
+            .relations
+            .iter()
+            .map(|r| {
+                (
+                    r.property.as_str(),
+                    r.target.as_str(),
+                    r.join_column.as_deref(),
+                    r.nullable,
+                )
+            })
+            .collect();
+        assert_eq!(
+            relations,
+            [
+                ("server", "Server", Some("serverId"), true),
+                ("parent", "Channel", Some("parentChannelId"), false),
+                ("config", "ChannelConfig", None, true),
+                ("image", "Image", Some("imageId"), true),
+                ("members", "User", None, true),
+            ]
+        );
+        assert_eq!(entities[0].relations[4].join_table.as_deref(), Some(""));
+    }
+
+    #[test]
+    fn varchar_foreign_key_to_uuid_primary_key() {
+        let entities: Vec<ParsedEntity> = parse(POLL).into_iter().chain(parse(VOTE)).collect();
+        let by_class: HashMap<&str, &ParsedEntity> =
+            entities.iter().map(|e| (e.class.as_str(), e)).collect();
+
+        let mut mismatches = Vec::new();
+        compare_foreign_keys(by_class["Vote"], &by_class, &constants(), &mut mismatches);
+        assert_eq!(mismatches.len(), 1);
+        let mismatch = &mismatches[0];
+        assert_eq!(mismatch.kind, MismatchKind::ForeignKeyType);
+        assert_eq!(mismatch.column.as_deref(), Some("pollId"));
+        assert_eq!(mismatch.expected.as_deref(), Some("character varying"));
+        assert_eq!(mismatch.actual.as_deref(), Some("uuid"));
+
+        // Against a database where the column is uuid, the column type
+        // differs too
+        let schema = Schema {
+            enums: Vec::new(),
+            tables: vec![table(
+                "vote",
+                vec![
+                    column("id", "uuid", false),
+                    column("voteType", "vote_votetype_enum", false),
+                    column("pollId", "uuid", true),
+                ],
+            )],
+        };
+        let mismatches = compare(&parse(VOTE), &schema, &constants());
+        let kinds: Vec<(MismatchKind, Option<&str>)> = mismatches
+            .iter()
+            .map(|m| (m.kind, m.column.as_deref()))
+            .collect();
+        assert_eq!(kinds, [(MismatchKind::Type, Some("pollId"))]);
+        assert_eq!(mismatches[0].expected.as_deref(), Some("character varying"));
+        assert_eq!(mismatches[0].actual.as_deref(), Some("uuid"));
+    }
+
+    #[test]
+    fn uuid_foreign_key_matches() {
+        let vote = VOTE.replace("type: 'varchar'", "type: 'uuid'");
+        let entities: Vec<ParsedEntity> = parse(POLL).into_iter().chain(parse(&vote)).collect();
+        let by_class: HashMap<&str, &ParsedEntity> =
+            entities.iter().map(|e| (e.class.as_str(), e)).collect();
+
+        let mut mismatches = Vec::new();
+        compare_foreign_keys(by_class["Vote"], &by_class, &constants(), &mut mismatches);
+        assert!(mismatches.is_empty());
+    }
+}
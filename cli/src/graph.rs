@@ -0,0 +1,801 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::cli::VoteGraphArgs;
+use crate::output::{OutputFormat, Record, Report, render};
+use crate::scope::{PollScope, ScopeNames, resolve_channel_name, resolve_server};
+use crate::utils::{format_number, pct};
+use crate::window::TimeWindow;
+
+/// Most divided pairs listed in text output.
+const DIVIDED_PAIRS: usize = 10;
+
+pub async fn run_vote_graph(
+    pool: &PgPool,
+    window: TimeWindow,
+    args: &VoteGraphArgs,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut names = ScopeNames::default();
+    let mut server_id = None;
+    if let Some(id_or_slug) = &args.server_id {
+        let (id, name) = resolve_server(pool, id_or_slug).await?;
+        server_id = Some(id);
+        names.server_name = Some(name);
+    }
+    if let Some(channel_id) = args.channel_id {
+        names.channel_name = resolve_channel_name(pool, channel_id).await?;
+    }
+
+    let scope = PollScope {
+        server_id,
+        channel_id: args.channel_id,
+        ..PollScope::new(&window)
+    };
+    let report = collect_vote_graph(pool, window, scope, names, args).await?;
+
+    if let Some(path) = &args.graphml {
+        write_export(path, &report.to_graphml())?;
+    }
+    if let Some(path) = &args.dot {
+        write_export(path, &report.to_dot())?;
+    }
+    render(&report, format)
+}
+
+/// Written before the report is rendered, with a note on stderr so
+/// machine-readable output on stdout is unaffected.
+fn write_export(path: &Path, contents: &str) -> Result<()> {
+    fs::write(path, contents).with_context(|| format!("failed to write {}", path.display()))?;
+    eprintln!(
+        "{}",
+        format!("Wrote vote graph to {}", path.display()).dimmed()
+    );
+    Ok(())
+}
+
+async fn collect_vote_graph(
+    pool: &PgPool,
+    window: TimeWindow,
+    scope: PollScope,
+    names: ScopeNames,
+    args: &VoteGraphArgs,
+) -> Result<VoteGraphReport> {
+    let members_query = sqlx::query_as::<_, MemberRow>(
+        r#"
+        SELECT u.id AS user_id,
+               u.name AS user_name,
+               COUNT(*)::bigint AS votes
+        FROM vote v
+        JOIN poll p ON p.id = v."pollId"
+        JOIN "user" u ON u.id = v."userId"
+        WHERE p."pollType" = 'proposal'
+          AND p."createdAt" >= $1 AND p."createdAt" < $5
+          AND ($2::uuid IS NULL OR p."channelId" = $2)
+          AND ($3::uuid IS NULL OR p.id = $3)
+          AND ($4::uuid IS NULL OR p."channelId" IN (SELECT id FROM channel WHERE "serverId" = $4))
+        GROUP BY u.id
+        ORDER BY u.name
+        "#,
+    )
+    .bind(scope.since)
+    .bind(scope.channel_id)
+    .bind(scope.poll_id)
+    .bind(scope.server_id)
+    .bind(scope.until)
+    .fetch_all(pool);
+
+    // Each unordered pair once, counted over the proposals both voted on
+    let pairs_query = sqlx::query_as::<_, PairRow>(
+        r#"
+        WITH scoped_votes AS (
+            SELECT v."pollId", v."userId", v."voteType"
+            FROM vote v
+            JOIN poll p ON p.id = v."pollId"
+            WHERE p."pollType" = 'proposal'
+              AND p."createdAt" >= $1 AND p."createdAt" < $5
+              AND ($2::uuid IS NULL OR p."channelId" = $2)
+              AND ($3::uuid IS NULL OR p.id = $3)
+              AND ($4::uuid IS NULL OR p."channelId" IN (SELECT id FROM channel WHERE "serverId" = $4))
+        )
+        SELECT a."userId" AS source,
+               b."userId" AS target,
+               COUNT(*)::bigint AS shared,
+               COUNT(*) FILTER (WHERE a."voteType" = b."voteType")::bigint AS same
+        FROM scoped_votes a
+        JOIN scoped_votes b ON b."pollId" = a."pollId" AND b."userId" > a."userId"
+        GROUP BY a."userId", b."userId"
+        HAVING COUNT(*) >= $6
+        ORDER BY a."userId", b."userId"
+        "#,
+    )
+    .bind(scope.since)
+    .bind(scope.channel_id)
+    .bind(scope.poll_id)
+    .bind(scope.server_id)
+    .bind(scope.until)
+    .bind(args.min_shared as i64)
+    .fetch_all(pool);
+
+    let (members, pairs) = tokio::try_join!(members_query, pairs_query)?;
+
+    let mut edges: Vec<GraphEdge> = pairs
+        .into_iter()
+        .map(|pair| GraphEdge {
+            source: pair.source,
+            target: pair.target,
+            shared: pair.shared,
+            same: pair.same,
+            different: pair.shared - pair.same,
+            agreement: pct(pair.same, pair.shared),
+        })
+        .collect();
+    edges.sort_by(|a, b| {
+        b.agreement
+            .total_cmp(&a.agreement)
+            .then(b.shared.cmp(&a.shared))
+    });
+
+    let mut adjacency: HashMap<Uuid, Vec<&GraphEdge>> = HashMap::new();
+    for edge in &edges {
+        adjacency.entry(edge.source).or_default().push(edge);
+        adjacency.entry(edge.target).or_default().push(edge);
+    }
+    let mut nodes: Vec<GraphNode> = members
+        .into_iter()
+        .map(|member| {
+            let partner_edges = adjacency
+                .get(&member.user_id)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            GraphNode {
+                user_id: member.user_id,
+                user_name: member.user_name,
+                votes: member.votes,
+                partners: partner_edges.len(),
+                mean_agreement: weighted_agreement(partner_edges),
+                cluster: None,
+            }
+        })
+        .collect();
+
+    let clusters = find_clusters(&mut nodes, &edges, args.min_agreement as f64);
+    let outliers = find_outliers(&nodes);
+
+    Ok(VoteGraphReport {
+        window_days: window.days(),
+        window,
+        server_id: scope.server_id,
+        channel_id: scope.channel_id,
+        names,
+        min_shared: args.min_shared,
+        min_agreement: args.min_agreement,
+        nodes,
+        edges,
+        clusters,
+        outliers,
+    })
+}
+
+/// Agreement across `edges`, weighting each pair by the proposals it shares.
+fn weighted_agreement(edges: &[&GraphEdge]) -> Option<f64> {
+    let shared: i64 = edges.iter().map(|edge| edge.shared).sum();
+    let same: i64 = edges.iter().map(|edge| edge.same).sum();
+    (shared > 0).then(|| pct(same, shared))
+}
+
+/// Groups members linked by pairs that agree at least `min_agreement`
+/// percent of the time into clusters: the connected components of that
+/// graph, largest first. Members without such a link stay unclustered.
+fn find_clusters(nodes: &mut [GraphNode], edges: &[GraphEdge], min_agreement: f64) -> Vec<Cluster> {
+    let index: HashMap<Uuid, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| (node.user_id, i))
+        .collect();
+    let strong: Vec<(usize, usize)> = edges
+        .iter()
+        .filter(|edge| edge.agreement >= min_agreement)
+        .filter_map(|edge| Some((*index.get(&edge.source)?, *index.get(&edge.target)?)))
+        .collect();
+
+    let mut parent: Vec<usize> = (0..nodes.len()).collect();
+    let mut linked = vec![false; nodes.len()];
+    for &(a, b) in &strong {
+        let (a_root, b_root) = (find_root(&mut parent, a), find_root(&mut parent, b));
+        parent[a_root.max(b_root)] = a_root.min(b_root);
+        linked[a] = true;
+        linked[b] = true;
+    }
+
+    let mut components: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for i in (0..nodes.len()).filter(|&i| linked[i]) {
+        let root = find_root(&mut parent, i);
+        components.entry(root).or_default().push(i);
+    }
+    let mut components: Vec<Vec<usize>> = components.into_values().collect();
+    components.sort_by_key(|members| std::cmp::Reverse(members.len()));
+
+    let cluster_of: HashMap<Uuid, usize> = components
+        .iter()
+        .enumerate()
+        .flat_map(|(i, members)| members.iter().map(move |&member| (member, i + 1)))
+        .map(|(member, id)| (nodes[member].user_id, id))
+        .collect();
+    for node in nodes.iter_mut() {
+        node.cluster = cluster_of.get(&node.user_id).copied();
+    }
+
+    components
+        .iter()
+        .enumerate()
+        .map(|(i, members)| {
+            let id = i + 1;
+            let internal: Vec<&GraphEdge> = edges
+                .iter()
+                .filter(|edge| {
+                    cluster_of.get(&edge.source) == Some(&id)
+                        && cluster_of.get(&edge.target) == Some(&id)
+                })
+                .collect();
+            Cluster {
+                id,
+                members: members
+                    .iter()
+                    .map(|&i| nodes[i].user_name.clone())
+                    .collect(),
+                internal_agreement: weighted_agreement(&internal),
+                external_agreement: weighted_agreement(
+                    &edges
+                        .iter()
+                        .filter(|edge| {
+                            (cluster_of.get(&edge.source) == Some(&id))
+                                != (cluster_of.get(&edge.target) == Some(&id))
+                        })
+                        .collect::<Vec<_>>(),
+                ),
+            }
+        })
+        .collect()
+}
+
+/// Union-find lookup with path halving.
+fn find_root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+/// Members who share too few proposals with anyone to be compared, and
+/// members whose agreement with everyone else is more than one standard
+/// deviation below the average member's, least agreeing first.
+fn find_outliers(nodes: &[GraphNode]) -> Vec<Outlier> {
+    let agreements: Vec<f64> = nodes
+        .iter()
+        .filter_map(|node| node.mean_agreement)
+        .collect();
+    let mean = agreements.iter().sum::<f64>() / agreements.len().max(1) as f64;
+    let variance = agreements
+        .iter()
+        .map(|agreement| (agreement - mean).powi(2))
+        .sum::<f64>()
+        / agreements.len().max(1) as f64;
+    let cutoff = mean - variance.sqrt();
+
+    let mut outliers: Vec<Outlier> = nodes
+        .iter()
+        .filter_map(|node| {
+            let reason = match node.mean_agreement {
+                None => "shares too few proposals with anyone".to_string(),
+                Some(agreement) if agreements.len() > 2 && agreement < cutoff => format!(
+                    "agrees {:.1}% of the time, against {:.1}% for the average member",
+                    agreement, mean
+                ),
+                Some(_) => return None,
+            };
+            Some(Outlier {
+                user_id: node.user_id,
+                user_name: node.user_name.clone(),
+                votes: node.votes,
+                mean_agreement: node.mean_agreement,
+                reason,
+            })
+        })
+        .collect();
+    outliers.sort_by(|a, b| {
+        a.mean_agreement
+            .unwrap_or(-1.0)
+            .total_cmp(&b.mean_agreement.unwrap_or(-1.0))
+    });
+    outliers
+}
+
+// ---------------------------------------------------------------------------
+// Rows
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, FromRow)]
+struct MemberRow {
+    user_id: Uuid,
+    user_name: String,
+    votes: i64,
+}
+
+#[derive(Debug, FromRow)]
+struct PairRow {
+    source: Uuid,
+    target: Uuid,
+    shared: i64,
+    same: i64,
+}
+
+// ---------------------------------------------------------------------------
+// Report
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VoteGraphReport {
+    window_days: i64,
+    #[serde(flatten)]
+    window: TimeWindow,
+    server_id: Option<Uuid>,
+    channel_id: Option<Uuid>,
+    #[serde(flatten)]
+    names: ScopeNames,
+    min_shared: u32,
+    min_agreement: u32,
+    /// Every member who voted on a proposal in scope
+    nodes: Vec<GraphNode>,
+    /// Pairs sharing at least `minShared` proposals, most agreeing first
+    edges: Vec<GraphEdge>,
+    /// Largest first
+    clusters: Vec<Cluster>,
+    outliers: Vec<Outlier>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphNode {
+    user_id: Uuid,
+    user_name: String,
+    votes: i64,
+    /// Members they share at least `minShared` proposals with
+    partners: usize,
+    /// Share of votes matching their partners', weighted by shared proposals
+    mean_agreement: Option<f64>,
+    cluster: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphEdge {
+    source: Uuid,
+    target: Uuid,
+    /// Proposals both members voted on
+    shared: i64,
+    /// Shared proposals where they cast the same vote type
+    same: i64,
+    different: i64,
+    /// `same` as a percentage of `shared`
+    agreement: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Cluster {
+    id: usize,
+    members: Vec<String>,
+    /// Agreement between pairs inside the cluster
+    internal_agreement: Option<f64>,
+    /// Agreement between members of the cluster and everyone else
+    external_agreement: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Outlier {
+    user_id: Uuid,
+    user_name: String,
+    votes: i64,
+    mean_agreement: Option<f64>,
+    reason: String,
+}
+
+impl VoteGraphReport {
+    fn name_of(&self, user_id: Uuid) -> &str {
+        self.nodes
+            .iter()
+            .find(|node| node.user_id == user_id)
+            .map(|node| node.user_name.as_str())
+            .unwrap_or("unknown")
+    }
+
+    fn to_graphml(&self) -> String {
+        let mut xml = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="name" for="node" attr.name="name" attr.type="string"/>
+  <key id="votes" for="node" attr.name="votes" attr.type="long"/>
+  <key id="cluster" for="node" attr.name="cluster" attr.type="int"/>
+  <key id="meanAgreement" for="node" attr.name="meanAgreement" attr.type="double"/>
+  <key id="shared" for="edge" attr.name="shared" attr.type="long"/>
+  <key id="same" for="edge" attr.name="same" attr.type="long"/>
+  <key id="different" for="edge" attr.name="different" attr.type="long"/>
+  <key id="agreement" for="edge" attr.name="agreement" attr.type="double"/>
+  <graph id="covoting" edgedefault="undirected">
+"#,
+        );
+        for node in &self.nodes {
+            let _ = writeln!(xml, r#"    <node id="{}">"#, node.user_id);
+            let _ = writeln!(
+                xml,
+                r#"      <data key="name">{}</data>"#,
+                escape_xml(&node.user_name)
+            );
+            let _ = writeln!(xml, r#"      <data key="votes">{}</data>"#, node.votes);
+            if let Some(cluster) = node.cluster {
+                let _ = writeln!(xml, r#"      <data key="cluster">{}</data>"#, cluster);
+            }
+            if let Some(agreement) = node.mean_agreement {
+                let _ = writeln!(
+                    xml,
+                    r#"      <data key="meanAgreement">{:.2}</data>"#,
+                    agreement
+                );
+            }
+            xml.push_str("    </node>\n");
+        }
+        for edge in &self.edges {
+            let _ = writeln!(
+                xml,
+                r#"    <edge source="{}" target="{}">"#,
+                edge.source, edge.target
+            );
+            let _ = writeln!(xml, r#"      <data key="shared">{}</data>"#, edge.shared);
+            let _ = writeln!(xml, r#"      <data key="same">{}</data>"#, edge.same);
+            let _ = writeln!(
+                xml,
+                r#"      <data key="different">{}</data>"#,
+                edge.different
+            );
+            let _ = writeln!(
+                xml,
+                r#"      <data key="agreement">{:.2}</data>"#,
+                edge.agreement
+            );
+            xml.push_str("    </edge>\n");
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+
+    /// Edges are drawn thicker the more a pair agrees, and members of the
+    /// same cluster share a fill color.
+    fn to_dot(&self) -> String {
+        const PALETTE: [&str; 8] = [
+            "#8dd3c7", "#ffffb3", "#bebada", "#fb8072", "#80b1d3", "#fdb462", "#b3de69", "#fccde5",
+        ];
+        let mut dot = String::from("graph covoting {\n  node [shape=ellipse, style=filled];\n");
+        for node in &self.nodes {
+            let fill = node
+                .cluster
+                .map(|cluster| PALETTE[(cluster - 1) % PALETTE.len()])
+                .unwrap_or("#eeeeee");
+            let _ = writeln!(
+                dot,
+                r#"  "{}" [label="{}", fillcolor="{}"];"#,
+                node.user_id,
+                escape_dot(&node.user_name),
+                fill
+            );
+        }
+        for edge in &self.edges {
+            let _ = writeln!(
+                dot,
+                r#"  "{}" -- "{}" [label="{:.0}% of {}", penwidth={:.2}, weight={}];"#,
+                edge.source,
+                edge.target,
+                edge.agreement,
+                edge.shared,
+                0.5 + edge.agreement / 25.0,
+                edge.same
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn escape_dot(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl Report for VoteGraphReport {
+    const KIND: &'static str = "vote-graph";
+    const SCHEMA_VERSION: u32 = 1;
+
+    fn render_text(&self) {
+        let scope = self
+            .names
+            .describe()
+            .map(|scope| format!(", {}", scope))
+            .unwrap_or_default();
+        println!(
+            "\n{} {}",
+            "Vote Graph".bold().underline(),
+            format!("(proposals from {}{})", self.window.describe(), scope).dimmed()
+        );
+        println!(
+            "{} {} members, {} pairs sharing at least {} proposals",
+            "•".cyan(),
+            format_number(self.nodes.len() as i64).bold(),
+            format_number(self.edges.len() as i64).bold(),
+            self.min_shared
+        );
+        if let Some(agreement) = weighted_agreement(&self.edges.iter().collect::<Vec<_>>()) {
+            println!(
+                "{} pairs vote the same way {} of the time",
+                "•".cyan(),
+                format!("{:.1}%", agreement).bold()
+            );
+        }
+
+        println!(
+            "\n{} {}",
+            "Clusters".bold(),
+            format!("(linked by at least {}% agreement)", self.min_agreement).dimmed()
+        );
+        if self.clusters.is_empty() {
+            println!("{}", "  none".dimmed());
+        }
+        for cluster in &self.clusters {
+            let agreement = |value: Option<f64>| {
+                value
+                    .map(|value| format!("{:.1}%", value))
+                    .unwrap_or_else(|| "n/a".to_string())
+            };
+            println!(
+                "{} cluster {} — {} members, {} agreement inside, {} with everyone else",
+                "•".cyan(),
+                cluster.id,
+                cluster.members.len(),
+                agreement(cluster.internal_agreement).bold(),
+                agreement(cluster.external_agreement)
+            );
+            println!("  {} {}", "→".dimmed(), cluster.members.join(", "));
+        }
+
+        let mut divided: Vec<&GraphEdge> = self.edges.iter().collect();
+        divided.sort_by(|a, b| {
+            a.agreement
+                .total_cmp(&b.agreement)
+                .then(b.shared.cmp(&a.shared))
+        });
+        if !divided.is_empty() {
+            println!("\n{}", "Most Divided Pairs".bold());
+            for edge in divided.iter().take(DIVIDED_PAIRS) {
+                println!(
+                    "  {} {} and {} — {} on {} shared proposals, {} different",
+                    "→".dimmed(),
+                    self.name_of(edge.source).bold(),
+                    self.name_of(edge.target).bold(),
+                    format!("{:.1}% agree", edge.agreement).red(),
+                    edge.shared,
+                    edge.different
+                );
+            }
+        }
+
+        if !self.outliers.is_empty() {
+            println!("\n{}", "Outliers".bold());
+            for outlier in &self.outliers {
+                println!(
+                    "  {} {} — {} {}",
+                    "→".dimmed(),
+                    outlier.user_name.bold(),
+                    outlier.reason,
+                    format!("({} votes)", outlier.votes).dimmed()
+                );
+            }
+        }
+    }
+
+    fn records(&self) -> Vec<Record> {
+        let mut records = vec![
+            Record::new("summary")
+                .field("windowDays", self.window_days)
+                .field("since", self.window.since.to_rfc3339())
+                .field("until", self.window.until.to_rfc3339())
+                .field("serverId", self.server_id.map(|id| id.to_string()))
+                .field("serverName", self.names.server_name.clone())
+                .field("channelId", self.channel_id.map(|id| id.to_string()))
+                .field("channelName", self.names.channel_name.clone())
+                .field("minShared", self.min_shared)
+                .field("minAgreement", self.min_agreement)
+                .field("members", self.nodes.len())
+                .field("pairs", self.edges.len())
+                .field("clusters", self.clusters.len()),
+        ];
+
+        for node in &self.nodes {
+            records.push(
+                Record::new("nodes")
+                    .field("userId", node.user_id.to_string())
+                    .field("userName", node.user_name.as_str())
+                    .field("votes", node.votes)
+                    .field("partners", node.partners)
+                    .field("meanAgreement", node.mean_agreement)
+                    .field("cluster", node.cluster),
+            );
+        }
+        for edge in &self.edges {
+            records.push(
+                Record::new("edges")
+                    .field("source", edge.source.to_string())
+                    .field("target", edge.target.to_string())
+                    .field("shared", edge.shared)
+                    .field("same", edge.same)
+                    .field("different", edge.different)
+                    .field("agreement", edge.agreement),
+            );
+        }
+        for cluster in &self.clusters {
+            records.push(
+                Record::new("clusters")
+                    .field("cluster", cluster.id)
+                    .field("members", cluster.members.join(";"))
+                    .field("internalAgreement", cluster.internal_agreement)
+                    .field("externalAgreement", cluster.external_agreement),
+            );
+        }
+        for outlier in &self.outliers {
+            records.push(
+                Record::new("outliers")
+                    .field("userId", outlier.user_id.to_string())
+                    .field("userName", outlier.user_name.as_str())
+                    .field("votes", outlier.votes)
+                    .field("meanAgreement", outlier.mean_agreement)
+                    .field("reason", outlier.reason.as_str()),
+            );
+        }
+        records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+
+    fn id(n: u128) -> Uuid {
+        Uuid::from_u128(n)
+    }
+
+    fn node(n: u128, name: &str, mean_agreement: Option<f64>) -> GraphNode {
+        GraphNode {
+            user_id: id(n),
+            user_name: name.to_string(),
+            votes: 10,
+            partners: 0,
+            mean_agreement,
+            cluster: None,
+        }
+    }
+
+    fn edge(source: u128, target: u128, shared: i64, same: i64) -> GraphEdge {
+        GraphEdge {
+            source: id(source),
+            target: id(target),
+            shared,
+            same,
+            different: shared - same,
+            agreement: pct(same, shared),
+        }
+    }
+
+    #[test]
+    fn clusters_follow_edges_at_or_above_min_agreement() {
+        let edges = [edge(1, 2, 10, 8), edge(2, 3, 10, 7), edge(1, 3, 10, 5)];
+
+        let mut nodes = vec![
+            node(1, "ann", None),
+            node(2, "bo", None),
+            node(3, "cy", None),
+        ];
+        let clusters = find_clusters(&mut nodes, &edges, 80.0);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].members, vec!["ann", "bo"]);
+        assert_eq!(clusters[0].internal_agreement, Some(80.0));
+        assert_eq!(clusters[0].external_agreement, Some(60.0));
+        let assigned: Vec<Option<usize>> = nodes.iter().map(|node| node.cluster).collect();
+        assert_eq!(assigned, vec![Some(1), Some(1), None]);
+
+        let mut nodes = vec![
+            node(1, "ann", None),
+            node(2, "bo", None),
+            node(3, "cy", None),
+        ];
+        let clusters = find_clusters(&mut nodes, &edges, 70.0);
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].members, vec!["ann", "bo", "cy"]);
+
+        let mut nodes = vec![
+            node(1, "ann", None),
+            node(2, "bo", None),
+            node(3, "cy", None),
+        ];
+        assert!(find_clusters(&mut nodes, &edges, 81.0).is_empty());
+        assert!(nodes.iter().all(|node| node.cluster.is_none()));
+    }
+
+    #[test]
+    fn outliers_are_unlinked_or_a_deviation_below_average() {
+        let nodes = [
+            node(1, "ann", Some(90.0)),
+            node(2, "bo", Some(88.0)),
+            node(3, "cy", Some(92.0)),
+            node(4, "dee", Some(40.0)),
+            node(5, "eli", None),
+        ];
+        let outliers: Vec<String> = find_outliers(&nodes)
+            .into_iter()
+            .map(|outlier| outlier.user_name)
+            .collect();
+        assert_eq!(outliers, vec!["eli", "dee"]);
+
+        // Two members can't be told apart from the average
+        let pair = [node(1, "ann", Some(90.0)), node(2, "bo", Some(10.0))];
+        assert!(find_outliers(&pair).is_empty());
+    }
+
+    #[test]
+    fn exports_escape_member_names() {
+        let window = TimeWindow {
+            since: Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(),
+            until: Utc.with_ymd_and_hms(2026, 2, 1, 0, 0, 0).unwrap(),
+            timezone: chrono_tz::Tz::UTC,
+            last_days: None,
+            until_now: false,
+        };
+        let report = VoteGraphReport {
+            window_days: window.days(),
+            window,
+            server_id: None,
+            channel_id: None,
+            names: ScopeNames::default(),
+            min_shared: 3,
+            min_agreement: 80,
+            nodes: vec![node(1, r#"Ann "A" <a> & \b"#, Some(80.0))],
+            edges: Vec::new(),
+            clusters: Vec::new(),
+            outliers: Vec::new(),
+        };
+
+        assert!(
+            report
+                .to_graphml()
+                .contains(r#"<data key="name">Ann &quot;A&quot; &lt;a&gt; &amp; \b</data>"#)
+        );
+        assert!(report.to_dot().contains(r#"label="Ann \"A\" <a> & \\b""#));
+    }
+}
@@ -0,0 +1,343 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::decision::{DecisionModel, quorum_applies, required_count};
+use crate::output::Record;
+use crate::scope::PollScope;
+use crate::timing::QueryTimer;
+use crate::utils::{format_channel, format_duration, percentile};
+
+/// Time-to-decision, time-to-first-vote and time-to-quorum for proposals,
+/// overall and broken down by decision model and channel.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LifecycleStats {
+    pub overall: LifecycleMetrics,
+    pub by_model: Vec<LifecycleMetrics>,
+    pub by_channel: Vec<LifecycleMetrics>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LifecycleMetrics {
+    /// `overall`, a decision model, or a channel ID
+    pub group: String,
+    /// `group` with channel IDs resolved to channel and server names
+    pub label: String,
+    pub proposals: i64,
+    pub to_decision: DurationSummary,
+    pub to_first_vote: DurationSummary,
+    pub to_quorum: DurationSummary,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DurationSummary {
+    /// Number of proposals that reached this milestone
+    pub count: i64,
+    pub median_secs: Option<f64>,
+    pub p90_secs: Option<f64>,
+}
+
+impl DurationSummary {
+    fn from_samples(mut samples: Vec<f64>) -> Self {
+        samples.sort_by(f64::total_cmp);
+        Self {
+            count: samples.len() as i64,
+            median_secs: percentile(&samples, 50.0),
+            p90_secs: percentile(&samples, 90.0),
+        }
+    }
+
+    fn display(&self) -> String {
+        match (self.median_secs, self.p90_secs) {
+            (Some(median), Some(p90)) => {
+                format!("{} / {}", format_duration(median), format_duration(p90))
+            }
+            _ => "—".to_string(),
+        }
+    }
+}
+
+pub async fn collect_lifecycle(
+    pool: &PgPool,
+    scope: &PollScope,
+    timer: &QueryTimer,
+) -> Result<LifecycleStats> {
+    // Polls are only ever updated to change stage, so `updatedAt` marks when a
+    // proposal left voting. Polls closed by `synchronizePolls` are updated
+    // lazily, so the decision time is capped at `closingAt`.
+    let query = sqlx::query_as::<_, LifecycleRow>(
+        r#"
+        SELECT p."channelId" AS channel_id,
+               c.name AS channel_name,
+               s.name AS server_name,
+               pc."decisionMakingModel"::text AS decision_making_model,
+               p."createdAt" AS created_at,
+               CASE WHEN p.stage <> 'voting'
+                    THEN LEAST(p."updatedAt", COALESCE(pc."closingAt", p."updatedAt"))
+               END AS decided_at,
+               pc."quorumEnabled" AS quorum_enabled,
+               pc."quorumThreshold" AS quorum_threshold,
+               (
+                   SELECT COUNT(*)
+                   FROM channel_member cm
+                   WHERE cm."channelId" = p."channelId"
+               )::bigint AS member_count,
+               COALESCE(
+                   ARRAY_AGG(v."createdAt" ORDER BY v."createdAt")
+                       FILTER (WHERE v.id IS NOT NULL),
+                   '{}'
+               ) AS vote_times
+        FROM poll p
+        JOIN poll_config pc ON pc."pollId" = p.id
+        JOIN channel c ON c.id = p."channelId"
+        JOIN server s ON s.id = c."serverId"
+        LEFT JOIN vote v ON v."pollId" = p.id
+        WHERE p."pollType" = 'proposal'
+          AND p."createdAt" >= $1 AND p."createdAt" < $5
+          AND ($2::uuid IS NULL OR p."channelId" = $2)
+          AND ($3::uuid IS NULL OR p.id = $3)
+          AND ($4::uuid IS NULL OR c."serverId" = $4)
+        GROUP BY p.id, pc.id, c.id, s.id
+        "#,
+    )
+    .bind(scope.since)
+    .bind(scope.channel_id)
+    .bind(scope.poll_id)
+    .bind(scope.server_id)
+    .bind(scope.until)
+    .fetch_all(pool);
+    let rows = timer.time("lifecycle", query).await?;
+
+    let overall = summarize("overall".to_string(), "overall".to_string(), rows.iter());
+
+    let mut models: BTreeMap<&str, Vec<&LifecycleRow>> = BTreeMap::new();
+    let mut channels: BTreeMap<Uuid, Vec<&LifecycleRow>> = BTreeMap::new();
+    for row in &rows {
+        models
+            .entry(&row.decision_making_model)
+            .or_default()
+            .push(row);
+        channels.entry(row.channel_id).or_default().push(row);
+    }
+
+    let by_model = models
+        .into_iter()
+        .map(|(model, rows)| summarize(model.to_string(), model.to_string(), rows.into_iter()))
+        .collect();
+
+    let mut by_channel: Vec<LifecycleMetrics> = channels
+        .into_iter()
+        .map(|(channel_id, rows)| {
+            let label = format_channel(&rows[0].channel_name, &rows[0].server_name);
+            summarize(channel_id.to_string(), label, rows.into_iter())
+        })
+        .collect();
+    // Slowest channels first so stalls stand out
+    by_channel.sort_by(|a, b| {
+        b.to_decision
+            .median_secs
+            .unwrap_or(f64::INFINITY)
+            .total_cmp(&a.to_decision.median_secs.unwrap_or(f64::INFINITY))
+    });
+
+    Ok(LifecycleStats {
+        overall,
+        by_model,
+        by_channel,
+    })
+}
+
+fn summarize<'a>(
+    group: String,
+    label: String,
+    rows: impl Iterator<Item = &'a LifecycleRow>,
+) -> LifecycleMetrics {
+    let mut proposals = 0;
+    let mut to_decision = Vec::new();
+    let mut to_first_vote = Vec::new();
+    let mut to_quorum = Vec::new();
+
+    for row in rows {
+        proposals += 1;
+        let since_created = |ts: NaiveDateTime| (ts - row.created_at).num_seconds() as f64;
+
+        if let Some(decided_at) = row.decided_at {
+            to_decision.push(since_created(decided_at));
+        }
+        if let Some(first_vote) = row.vote_times.first() {
+            to_first_vote.push(since_created(*first_vote));
+        }
+        let needs_quorum = DecisionModel::from_db(&row.decision_making_model)
+            .is_some_and(|model| quorum_applies(model, row.quorum_enabled));
+        if needs_quorum {
+            // Quorum is reached by the Nth vote, where N is the same required
+            // count the server computes from current channel membership
+            let required = required_count(row.member_count, row.quorum_threshold);
+            if required <= 0 {
+                to_quorum.push(0.0);
+            } else if let Some(ts) = row.vote_times.get(required as usize - 1) {
+                to_quorum.push(since_created(*ts));
+            }
+        }
+    }
+
+    LifecycleMetrics {
+        group,
+        label,
+        proposals,
+        to_decision: DurationSummary::from_samples(to_decision),
+        to_first_vote: DurationSummary::from_samples(to_first_vote),
+        to_quorum: DurationSummary::from_samples(to_quorum),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Rendering
+// ---------------------------------------------------------------------------
+
+pub fn print_lifecycle(stats: &LifecycleStats) {
+    if stats.overall.proposals == 0 {
+        return;
+    }
+
+    println!(
+        "\n{} {}",
+        "Proposal Lifecycle".bold(),
+        "(median / p90)".dimmed()
+    );
+    println!(
+        "  {:<38} {:>5}  {:<17} {:<17} {:<17}",
+        "",
+        "n".dimmed(),
+        "to decision".dimmed(),
+        "to first vote".dimmed(),
+        "to quorum".dimmed()
+    );
+    print_metrics_row(&stats.overall);
+
+    if stats.by_model.len() > 1 {
+        println!("  {}", "By decision model".dimmed());
+        for metrics in &stats.by_model {
+            print_metrics_row(metrics);
+        }
+    }
+
+    if stats.by_channel.len() > 1 {
+        println!("  {}", "By channel (slowest first)".dimmed());
+        for metrics in &stats.by_channel {
+            print_metrics_row(metrics);
+        }
+    }
+}
+
+fn print_metrics_row(metrics: &LifecycleMetrics) {
+    println!(
+        "  {} {:<36} {:>5}  {:<17} {:<17} {:<17}",
+        "→".dimmed(),
+        metrics.label,
+        metrics.proposals.to_string().bold(),
+        metrics.to_decision.display(),
+        metrics.to_first_vote.display(),
+        metrics.to_quorum.display()
+    );
+}
+
+pub fn lifecycle_records(stats: &LifecycleStats) -> Vec<Record> {
+    let groups = std::iter::once(("overall", &stats.overall))
+        .chain(stats.by_model.iter().map(|m| ("model", m)))
+        .chain(stats.by_channel.iter().map(|m| ("channel", m)));
+
+    let mut records = Vec::new();
+    for (group_type, metrics) in groups {
+        for (milestone, summary) in [
+            ("decision", &metrics.to_decision),
+            ("firstVote", &metrics.to_first_vote),
+            ("quorum", &metrics.to_quorum),
+        ] {
+            records.push(
+                Record::new("lifecycle")
+                    .field("groupType", group_type)
+                    .field("group", metrics.group.as_str())
+                    .field("label", metrics.label.as_str())
+                    .field("proposals", metrics.proposals)
+                    .field("milestone", milestone)
+                    .field("count", summary.count)
+                    .field("medianSecs", summary.median_secs)
+                    .field("p90Secs", summary.p90_secs),
+            );
+        }
+    }
+    records
+}
+
+#[derive(Debug, FromRow)]
+struct LifecycleRow {
+    channel_id: Uuid,
+    channel_name: String,
+    server_name: String,
+    decision_making_model: String,
+    created_at: NaiveDateTime,
+    decided_at: Option<NaiveDateTime>,
+    quorum_enabled: bool,
+    quorum_threshold: i32,
+    member_count: i64,
+    vote_times: Vec<NaiveDateTime>,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, NaiveDate};
+
+    use super::*;
+
+    fn row(model: &str, quorum_enabled: bool, vote_minutes: &[i64]) -> LifecycleRow {
+        let created_at = NaiveDate::from_ymd_opt(2026, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        LifecycleRow {
+            channel_id: Uuid::nil(),
+            channel_name: "general".to_string(),
+            server_name: "praxis".to_string(),
+            decision_making_model: model.to_string(),
+            created_at,
+            decided_at: None,
+            quorum_enabled,
+            quorum_threshold: 50,
+            member_count: 4,
+            vote_times: vote_minutes
+                .iter()
+                .map(|minutes| created_at + Duration::minutes(*minutes))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn quorum_time_is_the_nth_vote() {
+        let rows = [row("consensus", true, &[10, 20, 30])];
+        let metrics = summarize("overall".into(), "overall".into(), rows.iter());
+        assert_eq!(metrics.to_quorum.count, 1);
+        assert_eq!(metrics.to_quorum.median_secs, Some(20.0 * 60.0));
+    }
+
+    #[test]
+    fn consent_and_disabled_quorum_have_no_quorum_time() {
+        let rows = [
+            row("consent", true, &[10, 20, 30]),
+            row("majority-vote", false, &[10, 20, 30]),
+        ];
+        let metrics = summarize("overall".into(), "overall".into(), rows.iter());
+        assert_eq!(metrics.proposals, 2);
+        assert_eq!(metrics.to_first_vote.count, 2);
+        assert_eq!(metrics.to_quorum.count, 0);
+        assert_eq!(metrics.to_quorum.median_secs, None);
+    }
+}
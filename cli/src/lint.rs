@@ -0,0 +1,884 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+use std::sync::LazyLock;
+
+use anyhow::{Result, bail};
+use clap::ValueEnum;
+use owo_colors::OwoColorize;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::cli::MigrationsLintArgs;
+use crate::migrations::{MigrationFile, migration_files};
+use crate::output::{OutputFormat, Record, Report, render};
+
+// ---------------------------------------------------------------------------
+// Pre-compiled regexes
+// ---------------------------------------------------------------------------
+
+/// Matches the start of `up(` or `down(` in a migration class
+static METHOD_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?:public\s+)?async\s+(up|down)\s*\(").unwrap());
+
+/// Matches `queryRunner.query(` followed by a template, single or double
+/// quoted string
+static QUERY_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"queryRunner\.query\(\s*(?:`([^`]*)`|'((?:[^'\\]|\\.)*)'|"((?:[^"\\]|\\.)*)")"#)
+        .unwrap()
+});
+
+/// An optionally schema-qualified, optionally quoted identifier; captures
+/// the unqualified name
+const IDENT: &str = r#"(?:"?\w+"?\.)?"?(\w+)"?"#;
+
+static CREATE_TABLE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i)^CREATE\s+TABLE\s+(?:IF\s+NOT\s+EXISTS\s+)?{IDENT}"
+    ))
+    .unwrap()
+});
+
+static DROP_TABLE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(r"(?i)^DROP\s+TABLE\s+(?:IF\s+EXISTS\s+)?{IDENT}")).unwrap()
+});
+
+static ALTER_TABLE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?is)^ALTER\s+TABLE\s+(?:ONLY\s+)?(?:IF\s+EXISTS\s+)?{IDENT}\s+(.*)$"
+    ))
+    .unwrap()
+});
+
+static DROP_COLUMN_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i)^DROP\s+(?:COLUMN\s+)?(?:IF\s+EXISTS\s+)?{IDENT}"
+    ))
+    .unwrap()
+});
+
+static ADD_COLUMN_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?is)^ADD\s+(?:COLUMN\s+)?(?:IF\s+NOT\s+EXISTS\s+)?{IDENT}\s+(.*)$"
+    ))
+    .unwrap()
+});
+
+static ALTER_COLUMN_TYPE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?is)^ALTER\s+(?:COLUMN\s+)?{IDENT}\s+(?:SET\s+DATA\s+)?TYPE\s+(.*)$"
+    ))
+    .unwrap()
+});
+
+static SET_NOT_NULL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i)^ALTER\s+(?:COLUMN\s+)?{IDENT}\s+SET\s+NOT\s+NULL"
+    ))
+    .unwrap()
+});
+
+static CREATE_INDEX_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?is)^CREATE\s+(?:UNIQUE\s+)?INDEX\s+(CONCURRENTLY\s+)?.*?\sON\s+(?:ONLY\s+)?{IDENT}"
+    ))
+    .unwrap()
+});
+
+static CREATE_ENUM_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?is)^CREATE\s+TYPE\s+{IDENT}\s+AS\s+ENUM\s*\((.*)\)"
+    ))
+    .unwrap()
+});
+
+static RENAME_TYPE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i)^ALTER\s+TYPE\s+{IDENT}\s+RENAME\s+TO\s+{IDENT}"
+    ))
+    .unwrap()
+});
+
+static ADD_VALUE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i)^ALTER\s+TYPE\s+{IDENT}\s+ADD\s+VALUE\s+(?:IF\s+NOT\s+EXISTS\s+)?'([^']*)'"
+    ))
+    .unwrap()
+});
+
+static RENAME_VALUE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"(?i)^ALTER\s+TYPE\s+{IDENT}\s+RENAME\s+VALUE\s+'([^']*)'\s+TO\s+'([^']*)'"
+    ))
+    .unwrap()
+});
+
+static DROP_TYPE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(r"(?i)^DROP\s+TYPE\s+(?:IF\s+EXISTS\s+)?{IDENT}")).unwrap()
+});
+
+/// Matches `NOT NULL`, `DEFAULT` and types that generate their own values
+static NOT_NULL_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\bNOT\s+NULL\b").unwrap());
+static DEFAULT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\bDEFAULT\b|\bGENERATED\b|\b(?:big|small)?serial\b").unwrap()
+});
+
+static LABEL_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"'((?:[^']|'')*)'").unwrap());
+
+static WHITESPACE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\s+").unwrap());
+
+// ---------------------------------------------------------------------------
+// Rules
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintRule {
+    /// `DROP COLUMN` in `up()`
+    DropColumn,
+    /// `DROP TABLE` in `up()`
+    DropTable,
+    /// A `NOT NULL` column added without a default, or `SET NOT NULL`, on an
+    /// existing table
+    NotNullWithoutDefault,
+    /// `ALTER COLUMN ... TYPE`, or a column dropped and re-added
+    ColumnTypeChange,
+    /// `CREATE INDEX` on an existing table without `CONCURRENTLY`
+    IndexWithoutConcurrently,
+    /// An enum recreated without some of its labels, or a label renamed
+    EnumLabelRemoval,
+    /// No `down()` method
+    MissingDown,
+    /// A `down()` method with no statements
+    EmptyDown,
+}
+
+impl LintRule {
+    fn as_str(self) -> &'static str {
+        match self {
+            LintRule::DropColumn => "drop-column",
+            LintRule::DropTable => "drop-table",
+            LintRule::NotNullWithoutDefault => "not-null-without-default",
+            LintRule::ColumnTypeChange => "column-type-change",
+            LintRule::IndexWithoutConcurrently => "index-without-concurrently",
+            LintRule::EnumLabelRemoval => "enum-label-removal",
+            LintRule::MissingDown => "missing-down",
+            LintRule::EmptyDown => "empty-down",
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Entry point
+// ---------------------------------------------------------------------------
+
+pub fn run_migrations_lint(args: &MigrationsLintArgs, format: OutputFormat) -> Result<()> {
+    let files = migration_files()?;
+    let allowed: HashSet<LintRule> = args.allow.iter().copied().collect();
+
+    // Every file is scanned so enum labels can be followed across
+    // migrations, but only the requested ones are reported
+    let selected: Option<HashSet<String>> = (!args.files.is_empty()).then(|| {
+        args.files
+            .iter()
+            .filter_map(|path| path.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .collect()
+    });
+    if let Some(selected) = &selected {
+        let scanned: HashSet<String> = files.iter().map(|file| file_name(&file.file)).collect();
+        let mut missing: Vec<&str> = selected.difference(&scanned).map(String::as_str).collect();
+        if !missing.is_empty() {
+            missing.sort();
+            bail!(
+                "not found among the migrations in src/database/migrations \
+                 (named like 1768785488966-Quorum.ts): {}",
+                missing.join(", ")
+            );
+        }
+    }
+
+    let mut enums = EnumState::default();
+    let mut warnings = Vec::new();
+    let mut linted = 0;
+    for file in &files {
+        let file_warnings = lint_file(file, &mut enums);
+        if selected
+            .as_ref()
+            .is_some_and(|selected| !selected.contains(&file_name(&file.file)))
+        {
+            continue;
+        }
+        linted += 1;
+        warnings.extend(
+            file_warnings
+                .into_iter()
+                .filter(|warning| !allowed.contains(&warning.rule)),
+        );
+    }
+
+    let mut allowed: Vec<LintRule> = allowed.into_iter().collect();
+    allowed.sort();
+    let report = LintReport {
+        files: linted,
+        allowed,
+        warnings,
+    };
+    render(&report, format)?;
+
+    if !report.warnings.is_empty() {
+        bail!(
+            "{} migration lint warning(s); pass --allow <rule> to accept a rule",
+            report.warnings.len()
+        );
+    }
+    Ok(())
+}
+
+fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+// ---------------------------------------------------------------------------
+// Scan migration files
+// ---------------------------------------------------------------------------
+
+/// One SQL statement passed to `queryRunner.query`.
+struct Statement {
+    sql: String,
+    line: usize,
+}
+
+fn lint_file(file: &MigrationFile, enums: &mut EnumState) -> Vec<LintWarning> {
+    let mut warnings = Vec::new();
+    let warn = |rule, line, message: String, sql: Option<&str>| LintWarning {
+        migration: file.name.clone(),
+        file: file.file.clone(),
+        line,
+        rule,
+        message,
+        sql: sql.map(str::to_string),
+    };
+
+    let methods = methods(&file.content);
+    if let Some((_, body_start, body)) = methods.get("up") {
+        let statements = statements(&file.content, *body_start, body);
+        for (rule, line, message, sql) in lint_up(&statements, enums) {
+            warnings.push(warn(rule, line, message, Some(sql)));
+        }
+    }
+
+    match methods.get("down") {
+        None => warnings.push(warn(
+            LintRule::MissingDown,
+            1,
+            "no down() method, so the migration can't be reverted".to_string(),
+            None,
+        )),
+        Some((line, _, body)) if is_empty_body(body) => warnings.push(warn(
+            LintRule::EmptyDown,
+            *line,
+            "down() is empty, so reverting leaves the schema changed".to_string(),
+            None,
+        )),
+        Some(_) => {}
+    }
+
+    warnings
+}
+
+/// `up` and `down` with the line they start on, where their body starts in
+/// the file and the body itself.
+fn methods(content: &str) -> HashMap<String, (usize, usize, String)> {
+    let mut methods = HashMap::new();
+    for cap in METHOD_RE.captures_iter(content) {
+        let whole = cap.get(0).unwrap();
+        // Skip the parameter list and return type to the opening brace
+        let Some(open) = content[whole.end()..].find('{').map(|i| whole.end() + i) else {
+            continue;
+        };
+        let Some(close) = matching_brace(content, open) else {
+            continue;
+        };
+        methods.insert(
+            cap[1].to_string(),
+            (
+                line_of(content, whole.start()),
+                open + 1,
+                content[open + 1..close].to_string(),
+            ),
+        );
+    }
+    methods
+}
+
+/// Index of the `}` closing the `{` at `open`, skipping strings and comments.
+fn matching_brace(text: &str, open: usize) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut depth = 0;
+    let mut quote: Option<u8> = None;
+    let mut i = open;
+    while i < bytes.len() {
+        let c = bytes[i];
+        match quote {
+            Some(q) => {
+                if c == b'\\' {
+                    i += 1;
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None => match c {
+                b'\'' | b'"' | b'`' => quote = Some(c),
+                b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                    i = text[i..].find('\n').map(|n| i + n).unwrap_or(bytes.len());
+                }
+                b'{' => depth += 1,
+                b'}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(i);
+                    }
+                }
+                _ => {}
+            },
+        }
+        i += 1;
+    }
+    None
+}
+
+fn is_empty_body(body: &str) -> bool {
+    body.lines()
+        .map(str::trim)
+        .all(|line| line.is_empty() || line.starts_with("//"))
+}
+
+fn line_of(content: &str, offset: usize) -> usize {
+    content[..offset].matches('\n').count() + 1
+}
+
+/// Every statement passed to `queryRunner.query` in a method body, with
+/// whitespace collapsed.
+fn statements(content: &str, body_start: usize, body: &str) -> Vec<Statement> {
+    let mut statements = Vec::new();
+    for cap in QUERY_RE.captures_iter(body) {
+        let line = line_of(content, body_start + cap.get(0).unwrap().start());
+        let sql = cap
+            .get(1)
+            .or(cap.get(2))
+            .or(cap.get(3))
+            .map(|m| m.as_str())
+            .unwrap_or_default();
+        for part in split_top_level(sql, ';') {
+            let sql = WHITESPACE_RE.replace_all(part.trim(), " ").to_string();
+            if !sql.is_empty() {
+                statements.push(Statement { sql, line });
+            }
+        }
+    }
+    statements
+}
+
+/// Splits on `separator` outside quotes and parentheses.
+fn split_top_level(sql: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in sql.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => depth -= 1,
+            (None, c) if c == separator && depth == 0 => {
+                parts.push(&sql[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&sql[start..]);
+    parts
+}
+
+// ---------------------------------------------------------------------------
+// Rules for up()
+// ---------------------------------------------------------------------------
+
+/// Enum labels as of the migrations scanned so far, and the names enums were
+/// renamed from while TypeORM recreates them.
+#[derive(Debug, Default)]
+struct EnumState {
+    labels: HashMap<String, Vec<String>>,
+    renamed_from: HashMap<String, String>,
+}
+
+fn lint_up<'a>(
+    statements: &'a [Statement],
+    enums: &mut EnumState,
+) -> Vec<(LintRule, usize, String, &'a str)> {
+    let mut warnings = Vec::new();
+    // Tables created in this migration are empty, so locking and rewriting
+    // them is harmless
+    let mut created: HashSet<String> = HashSet::new();
+    let mut dropped_columns: HashMap<(String, String), usize> = HashMap::new();
+
+    for statement in statements {
+        let sql = statement.sql.as_str();
+        let mut warn = |rule, message: String| {
+            warnings.push((rule, statement.line, message, sql));
+        };
+
+        if let Some(cap) = CREATE_TABLE_RE.captures(sql) {
+            created.insert(cap[1].to_string());
+        } else if let Some(cap) = DROP_TABLE_RE.captures(sql) {
+            warn(
+                LintRule::DropTable,
+                format!("drops table {} and all of its data", &cap[1]),
+            );
+        } else if let Some(cap) = ALTER_TABLE_RE.captures(sql) {
+            let table = cap[1].to_string();
+            let is_new = created.contains(&table);
+            for action in split_top_level(&cap[2], ',') {
+                let action = action.trim();
+                if let Some(column) = DROP_COLUMN_RE.captures(action) {
+                    if action.to_uppercase().starts_with("DROP CONSTRAINT") {
+                        continue;
+                    }
+                    dropped_columns.insert((table.clone(), column[1].to_string()), statement.line);
+                    warn(
+                        LintRule::DropColumn,
+                        format!("drops column {}.{} and its data", table, &column[1]),
+                    );
+                } else if let Some(column) = ADD_COLUMN_RE.captures(action) {
+                    if action.to_uppercase().starts_with("ADD CONSTRAINT") {
+                        continue;
+                    }
+                    let name = column[1].to_string();
+                    let definition = &column[2];
+                    if dropped_columns.contains_key(&(table.clone(), name.clone())) {
+                        warn(
+                            LintRule::ColumnTypeChange,
+                            format!(
+                                "re-adds {}.{} after dropping it, losing its data; alter the column in place instead",
+                                table, name
+                            ),
+                        );
+                    }
+                    if !is_new
+                        && NOT_NULL_RE.is_match(definition)
+                        && !DEFAULT_RE.is_match(definition)
+                    {
+                        warn(
+                            LintRule::NotNullWithoutDefault,
+                            format!(
+                                "adds NOT NULL column {}.{} without a default, which fails if {} has rows",
+                                table, name, table
+                            ),
+                        );
+                    }
+                } else if let Some(column) = ALTER_COLUMN_TYPE_RE.captures(action) {
+                    if !is_new {
+                        let new_type = column[2]
+                            .replace('"', "")
+                            .split_whitespace()
+                            .next()
+                            .unwrap_or_default()
+                            .to_string();
+                        warn(
+                            LintRule::ColumnTypeChange,
+                            format!(
+                                "changes the type of {}.{} to {}, which can rewrite {} under an exclusive lock",
+                                table, &column[1], new_type, table
+                            ),
+                        );
+                    }
+                } else if let Some(column) = SET_NOT_NULL_RE.captures(action)
+                    && !is_new
+                {
+                    warn(
+                        LintRule::NotNullWithoutDefault,
+                        format!(
+                            "sets {}.{} NOT NULL, which scans {} under an exclusive lock and fails on existing nulls",
+                            table, &column[1], table
+                        ),
+                    );
+                }
+            }
+        } else if let Some(cap) = CREATE_INDEX_RE.captures(sql) {
+            let table = &cap[2];
+            if cap.get(1).is_none() && !created.contains(table) {
+                warn(
+                    LintRule::IndexWithoutConcurrently,
+                    format!(
+                        "creates an index on {} without CONCURRENTLY, blocking writes while it builds",
+                        table
+                    ),
+                );
+            }
+        } else if let Some(cap) = CREATE_ENUM_RE.captures(sql) {
+            let labels = LABEL_RE
+                .captures_iter(&cap[2])
+                .map(|label| label[1].replace("''", "'"))
+                .collect();
+            enums.labels.insert(cap[1].to_string(), labels);
+        } else if let Some(cap) = RENAME_TYPE_RE.captures(sql) {
+            if let Some(labels) = enums.labels.remove(&cap[1]) {
+                enums.labels.insert(cap[2].to_string(), labels);
+            }
+            enums
+                .renamed_from
+                .insert(cap[2].to_string(), cap[1].to_string());
+        } else if let Some(cap) = ADD_VALUE_RE.captures(sql) {
+            enums
+                .labels
+                .entry(cap[1].to_string())
+                .or_default()
+                .push(cap[2].to_string());
+        } else if let Some(cap) = RENAME_VALUE_RE.captures(sql) {
+            warn(
+                LintRule::EnumLabelRemoval,
+                format!(
+                    "renames label '{}' of {} to '{}', breaking code and rows that still use it",
+                    &cap[2], &cap[1], &cap[3]
+                ),
+            );
+            if let Some(labels) = enums.labels.get_mut(&cap[1]) {
+                for label in labels.iter_mut().filter(|label| **label == cap[2]) {
+                    *label = cap[3].to_string();
+                }
+            }
+        } else if let Some(cap) = DROP_TYPE_RE.captures(sql) {
+            // TypeORM changes enums by renaming the old type, creating the
+            // new one and dropping the old: compare the two
+            let old = enums.labels.remove(&cap[1]);
+            let replacement = enums
+                .renamed_from
+                .remove(&cap[1])
+                .and_then(|name| enums.labels.get(&name).map(|labels| (name, labels)));
+            if let (Some(old), Some((name, new))) = (old, replacement) {
+                let new: BTreeSet<&String> = new.iter().collect();
+                let removed: Vec<&str> = old
+                    .iter()
+                    .filter(|label| !new.contains(label))
+                    .map(String::as_str)
+                    .collect();
+                if !removed.is_empty() {
+                    warn(
+                        LintRule::EnumLabelRemoval,
+                        format!(
+                            "removes {} from {}, which fails if any row still uses them",
+                            removed
+                                .iter()
+                                .map(|label| format!("'{}'", label))
+                                .collect::<Vec<_>>()
+                                .join(", "),
+                            name
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    warnings
+}
+
+// ---------------------------------------------------------------------------
+// Report
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LintReport {
+    files: usize,
+    allowed: Vec<LintRule>,
+    warnings: Vec<LintWarning>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LintWarning {
+    migration: String,
+    file: String,
+    line: usize,
+    rule: LintRule,
+    message: String,
+    sql: Option<String>,
+}
+
+/// SQL longer than this is cut short in text output.
+const SQL_PREVIEW: usize = 100;
+
+impl Report for LintReport {
+    const KIND: &'static str = "migrations-lint";
+    const SCHEMA_VERSION: u32 = 1;
+
+    fn render_text(&self) {
+        let allowed = if self.allowed.is_empty() {
+            String::new()
+        } else {
+            let rules: Vec<&str> = self.allowed.iter().map(|rule| rule.as_str()).collect();
+            format!(", allowing {}", rules.join(", "))
+        };
+        println!(
+            "\n{} {}",
+            "Migration Lint".bold().underline(),
+            format!("({} files{})", self.files, allowed).dimmed()
+        );
+
+        if self.warnings.is_empty() {
+            println!("{} No risky operations found", "✓".green());
+            return;
+        }
+        println!(
+            "{} {} warnings",
+            "•".cyan(),
+            self.warnings.len().to_string().bold()
+        );
+
+        let mut current_file = None;
+        for warning in &self.warnings {
+            if current_file != Some(&warning.file) {
+                current_file = Some(&warning.file);
+                println!(
+                    "\n{} {}",
+                    warning.migration.green().bold(),
+                    format!("({})", warning.file).dimmed()
+                );
+            }
+            println!(
+                "  {} {} {} {}",
+                "⚠".yellow(),
+                format!("line {:<4}", warning.line).dimmed(),
+                format!("[{}]", warning.rule.as_str()).yellow(),
+                warning.message
+            );
+            if let Some(sql) = &warning.sql {
+                let preview = if sql.chars().count() > SQL_PREVIEW {
+                    format!("{}…", sql.chars().take(SQL_PREVIEW).collect::<String>())
+                } else {
+                    sql.clone()
+                };
+                println!("      {}", preview.dimmed());
+            }
+        }
+    }
+
+    fn records(&self) -> Vec<Record> {
+        let mut records = vec![
+            Record::new("summary")
+                .field("files", self.files)
+                .field("warnings", self.warnings.len())
+                .field(
+                    "allowed",
+                    self.allowed
+                        .iter()
+                        .map(|rule| rule.as_str())
+                        .collect::<Vec<_>>()
+                        .join(";"),
+                ),
+        ];
+        for warning in &self.warnings {
+            records.push(
+                Record::new("warnings")
+                    .field("migration", warning.migration.as_str())
+                    .field("file", warning.file.as_str())
+                    .field("line", warning.line)
+                    .field("rule", warning.rule.as_str())
+                    .field("message", warning.message.as_str())
+                    .field("sql", warning.sql.clone()),
+            );
+        }
+        records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A migration whose `up()` runs `up` and whose `down()` body is `down`.
+    fn migration(up: &[&str], down: Option<&str>) -> MigrationFile {
+        let queries: String = up
+            .iter()
+            .map(|sql| format!("        await queryRunner.query(`{}`);\n", sql))
+            .collect();
+        let down = down
+            .map(|body| {
+                format!(
+                    "\n    public async down(queryRunner: QueryRunner): Promise<void> {{\n{}\n    }}\n",
+                    body
+                )
+            })
+            .unwrap_or_default();
+        let content = format!(
+            "import {{ MigrationInterface, QueryRunner }} from 'typeorm';\n\n\
+             export class Test1700000000000 implements MigrationInterface {{\n\
+             \x20   public async up(queryRunner: QueryRunner): Promise<void> {{\n{}    }}\n{}}}\n",
+            queries, down
+        );
+        MigrationFile {
+            name: "Test1700000000000".to_string(),
+            timestamp: 1700000000000,
+            file: "src/database/migrations/1700000000000-Test.ts".to_string(),
+            content,
+        }
+    }
+
+    const REVERTIBLE: Option<&str> = Some("        await queryRunner.query(`SELECT 1`);");
+
+    fn rules(up: &[&str], down: Option<&str>) -> Vec<LintRule> {
+        lint_file(&migration(up, down), &mut EnumState::default())
+            .into_iter()
+            .map(|warning| warning.rule)
+            .collect()
+    }
+
+    #[test]
+    fn drop_column_warns_but_drop_constraint_does_not() {
+        assert_eq!(
+            rules(&[r#"ALTER TABLE "poll" DROP COLUMN "body""#], REVERTIBLE),
+            vec![LintRule::DropColumn]
+        );
+        assert!(
+            rules(
+                &[r#"ALTER TABLE "vote" DROP CONSTRAINT "FK_vote_poll""#],
+                REVERTIBLE
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn not_null_column_needs_a_default_on_existing_tables() {
+        assert_eq!(
+            rules(
+                &[r#"ALTER TABLE "poll" ADD "quorum" integer NOT NULL"#],
+                REVERTIBLE
+            ),
+            vec![LintRule::NotNullWithoutDefault]
+        );
+        assert!(
+            rules(
+                &[r#"ALTER TABLE "poll" ADD "quorum" integer NOT NULL DEFAULT '0'"#],
+                REVERTIBLE
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn not_null_column_is_fine_on_a_table_created_in_the_same_migration() {
+        assert!(
+            rules(
+                &[
+                    r#"CREATE TABLE "quorum" ("id" uuid NOT NULL DEFAULT uuid_generate_v4(), CONSTRAINT "PK_quorum" PRIMARY KEY ("id"))"#,
+                    r#"ALTER TABLE "quorum" ADD "size" integer NOT NULL"#,
+                ],
+                REVERTIBLE
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn index_needs_concurrently_on_existing_tables() {
+        assert_eq!(
+            rules(
+                &[r#"CREATE INDEX "IDX_vote_poll" ON "vote" ("pollId")"#],
+                REVERTIBLE
+            ),
+            vec![LintRule::IndexWithoutConcurrently]
+        );
+        assert!(
+            rules(
+                &[r#"CREATE INDEX CONCURRENTLY "IDX_vote_poll" ON "vote" ("pollId")"#],
+                REVERTIBLE
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn enum_label_removal_through_renamed_type() {
+        let mut enums = EnumState::default();
+        let created = migration(
+            &[
+                r#"CREATE TYPE "public"."poll_stage_enum" AS ENUM('voting', 'revision', 'ratified', 'cancelled')"#,
+            ],
+            REVERTIBLE,
+        );
+        assert!(lint_file(&created, &mut enums).is_empty());
+
+        // How TypeORM regenerates an enum with a label removed
+        let changed = migration(
+            &[
+                r#"ALTER TYPE "public"."poll_stage_enum" RENAME TO "poll_stage_enum_old""#,
+                r#"CREATE TYPE "public"."poll_stage_enum" AS ENUM('voting', 'revision', 'ratified')"#,
+                r#"ALTER TABLE "poll" ALTER COLUMN "stage" TYPE "public"."poll_stage_enum" USING "stage"::"text"::"public"."poll_stage_enum""#,
+                r#"DROP TYPE "public"."poll_stage_enum_old""#,
+            ],
+            REVERTIBLE,
+        );
+        let warnings = lint_file(&changed, &mut enums);
+        let removal: Vec<&LintWarning> = warnings
+            .iter()
+            .filter(|warning| warning.rule == LintRule::EnumLabelRemoval)
+            .collect();
+        assert_eq!(removal.len(), 1);
+        assert!(removal[0].message.contains("'cancelled'"));
+        assert!(removal[0].message.contains("poll_stage_enum"));
+    }
+
+    #[test]
+    fn enum_recreated_with_the_same_labels_is_fine() {
+        let mut enums = EnumState::default();
+        let changed = migration(
+            &[
+                r#"CREATE TYPE "public"."vote_votetype_enum" AS ENUM('agree', 'disagree')"#,
+                r#"ALTER TYPE "public"."vote_votetype_enum" RENAME TO "vote_votetype_enum_old""#,
+                r#"CREATE TYPE "public"."vote_votetype_enum" AS ENUM('agree', 'disagree', 'block')"#,
+                r#"DROP TYPE "public"."vote_votetype_enum_old""#,
+            ],
+            REVERTIBLE,
+        );
+        assert!(
+            !lint_file(&changed, &mut enums)
+                .iter()
+                .any(|warning| warning.rule == LintRule::EnumLabelRemoval)
+        );
+    }
+
+    #[test]
+    fn missing_and_empty_down() {
+        let up = [r#"ALTER TABLE "poll" ADD "quorum" integer"#];
+        assert_eq!(rules(&up, None), vec![LintRule::MissingDown]);
+        assert_eq!(rules(&up, Some("")), vec![LintRule::EmptyDown]);
+        assert_eq!(
+            rules(&up, Some("        // nothing to undo")),
+            vec![LintRule::EmptyDown]
+        );
+        assert!(rules(&up, REVERTIBLE).is_empty());
+    }
+
+    #[test]
+    fn warnings_point_at_the_query_line() {
+        let file = migration(
+            &[
+                r#"ALTER TABLE "poll" ADD "quorum" integer"#,
+                r#"ALTER TABLE "poll" DROP COLUMN "body""#,
+            ],
+            REVERTIBLE,
+        );
+        let warnings = lint_file(&file, &mut EnumState::default());
+        assert_eq!(warnings.len(), 1);
+        let line = file.content.lines().nth(warnings[0].line - 1).unwrap();
+        assert!(line.contains("DROP COLUMN"));
+    }
+}
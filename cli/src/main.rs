@@ -1,20 +1,59 @@
+mod actions;
+mod anomalies;
+mod audit;
+mod chart;
 mod cli;
+mod compare;
 mod db;
+mod deadlines;
+mod decision;
+mod dissent;
+mod drift;
+mod entities;
+mod graph;
+mod lifecycle;
+mod lint;
+mod metrics;
+mod migrations;
+mod output;
 mod poll;
 mod routes;
 mod schema;
+mod scope;
+mod show;
+mod simulate;
+mod tally;
+mod timing;
+mod turnout;
+mod user;
 mod utils;
+mod watch;
+mod window;
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use clap::Parser;
+use owo_colors::OwoColorize;
 use sqlx::postgres::PgPoolOptions;
 
-use cli::{Cli, Commands};
+use actions::run_poll_actions;
+use anomalies::run_vote_anomalies;
+use audit::run_poll_audit;
+use cli::{Cli, Commands, MigrationsCommand};
 use db::build_database_url_from_env;
+use deadlines::run_poll_deadlines;
+use dissent::run_poll_dissent;
+use graph::run_vote_graph;
+use lint::run_migrations_lint;
+use metrics::run_serve_metrics;
+use migrations::run_migrations_status;
 use poll::run_poll_stats;
 use routes::run_routes;
 use schema::run_schema;
-use utils::normalize_window;
+use show::run_poll_show;
+use simulate::run_poll_simulate;
+use user::run_user_stats;
+use utils::{normalize_window, parse_timezone, set_timezone};
+use window::TimeWindow;
 
 const DEFAULT_MAX_CONNECTIONS: u32 = 5;
 
@@ -23,22 +62,62 @@ async fn main() -> Result<()> {
     dotenvy::dotenv().ok();
     let cli = Cli::parse();
 
+    // Document formats only make sense for the schema itself, so reject them
+    // before running anything else
+    let renders_documents = matches!(
+        &cli.command,
+        Commands::Schema(args) if args.diff.is_none() && !args.check_entities
+    );
+    if cli.format.is_document() && !renders_documents {
+        bail!(
+            "--format {} is only supported by schema, without --diff or --check-entities",
+            cli.format.name()
+        );
+    }
+
     // Routes command doesn't need a database connection
     if let Commands::Routes { path, tree } = cli.command {
         return run_routes(path, tree);
     }
 
+    // Neither does the migration lint, so it can run in CI
+    if let Commands::Migrations(MigrationsCommand::Lint(args)) = &cli.command {
+        return run_migrations_lint(args, cli.format);
+    }
+
+    // An explicit --tz has to be valid, but a TZ inherited from the shell may
+    // be something we can't read, e.g. a POSIX rule like `EST5EDT,M3.2.0`
+    match &cli.tz {
+        Some(tz) => set_timezone(parse_timezone(tz)?),
+        None => match std::env::var("TZ") {
+            Ok(tz) if !tz.is_empty() => match parse_timezone(&tz) {
+                Ok(tz) => set_timezone(tz),
+                Err(_) => eprintln!(
+                    "{}",
+                    format!(
+                        "Warning: ignoring TZ={}, which isn't an IANA time zone name; using UTC",
+                        tz
+                    )
+                    .yellow()
+                ),
+            },
+            _ => {}
+        },
+    }
+    let window = TimeWindow::resolve(normalize_window(cli.days), cli.since, cli.until)?;
     let database_url = build_database_url_from_env()?;
 
-    let day_window = normalize_window(cli.days);
     let pool = PgPoolOptions::new()
         .max_connections(DEFAULT_MAX_CONNECTIONS)
         .after_connect(|conn, _meta| {
             Box::pin(async move {
                 // Guard rail: ensure each pooled connection stays read-only.
                 sqlx::query("SET default_transaction_read_only = on;")
-                    .execute(conn)
+                    .execute(&mut *conn)
                     .await?;
+                // Timestamp columns hold UTC, so compare them against
+                // `timestamptz` values in UTC rather than the server's zone.
+                sqlx::query("SET TIME ZONE 'UTC';").execute(conn).await?;
                 Ok(())
             })
         })
@@ -47,18 +126,74 @@ async fn main() -> Result<()> {
         .context("failed to connect to PostgreSQL")?;
 
     match cli.command {
-        Commands::PollStats {
+        Commands::PollStats(args) => {
+            run_poll_stats(&pool, window, &args, cli.format).await?;
+        }
+        Commands::PollAudit {
+            server_id,
             channel_id,
-            poll_id,
-            top_polls,
-            top_channels,
         } => {
-            run_poll_stats(&pool, day_window, channel_id, poll_id, top_polls, top_channels).await?;
+            run_poll_audit(&pool, window, server_id.as_deref(), channel_id, cli.format).await?;
+        }
+        Commands::PollSimulate {
+            server_id,
+            channel_id,
+            rules,
+        } => {
+            run_poll_simulate(
+                &pool,
+                window,
+                server_id.as_deref(),
+                channel_id,
+                rules,
+                cli.format,
+            )
+            .await?;
+        }
+        Commands::PollDeadlines {
+            hours,
+            server_id,
+            channel_id,
+        } => {
+            run_poll_deadlines(&pool, hours, server_id.as_deref(), channel_id, cli.format).await?;
+        }
+        Commands::PollDissent(args) => {
+            run_poll_dissent(&pool, window, &args, cli.format).await?;
+        }
+        Commands::PollActions(args) => {
+            run_poll_actions(&pool, window, &args, cli.format).await?;
+        }
+        Commands::VoteGraph(args) => {
+            run_vote_graph(&pool, window, &args, cli.format).await?;
+        }
+        Commands::VoteAnomalies(args) => {
+            run_vote_anomalies(&pool, window, &args, cli.format).await?;
+        }
+        Commands::PollShow { poll_id } => {
+            run_poll_show(&pool, poll_id, cli.format).await?;
+        }
+        Commands::UserStats(args) => {
+            run_user_stats(
+                &pool,
+                window,
+                args.user_id,
+                args.name.as_deref(),
+                cli.format,
+            )
+            .await?;
+        }
+        Commands::ServeMetrics(args) => {
+            run_serve_metrics(&pool, &args).await?;
+        }
+        Commands::Schema(args) => {
+            run_schema(&pool, &args, cli.format).await?;
+        }
+        Commands::Migrations(MigrationsCommand::Status) => {
+            run_migrations_status(&pool, cli.format).await?;
         }
-        Commands::Schema => {
-            run_schema(&pool).await?;
+        Commands::Routes { .. } | Commands::Migrations(MigrationsCommand::Lint(_)) => {
+            unreachable!()
         }
-        Commands::Routes { .. } => unreachable!(),
     }
 
     Ok(())
@@ -0,0 +1,492 @@
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{Duration as ChronoDuration, NaiveDateTime, Utc};
+use sqlx::{FromRow, PgPool};
+use tiny_http::{Header, Method, Response, Server};
+use uuid::Uuid;
+
+use crate::cli::ServeMetricsArgs;
+use crate::decision::now_naive_utc;
+use crate::watch::wait;
+
+/// Serves the latest metrics on `/metrics` while refreshing them on an
+/// interval. A failed refresh keeps serving the previous values.
+pub async fn run_serve_metrics(pool: &PgPool, args: &ServeMetricsArgs) -> Result<()> {
+    let initial = collect_metrics(pool, args)
+        .await
+        .context("failed to collect initial metrics")?;
+    let state = Arc::new(MetricsState {
+        body: RwLock::new(initial),
+        refresh_failures: AtomicU64::new(0),
+    });
+
+    let server = Server::http(args.listen)
+        .map_err(|err| anyhow!("failed to listen on {}: {}", args.listen, err))?;
+    eprintln!(
+        "Serving metrics on http://{}/metrics, refreshing every {}s",
+        args.listen, args.refresh
+    );
+    let served = Arc::clone(&state);
+    thread::spawn(move || serve(server, &served));
+
+    let interval = Duration::from_secs(args.refresh);
+    while wait(interval).await {
+        match collect_metrics(pool, args).await {
+            Ok(body) => {
+                if let Ok(mut current) = state.body.write() {
+                    *current = body;
+                }
+            }
+            Err(err) => {
+                state.refresh_failures.fetch_add(1, Ordering::Relaxed);
+                eprintln!("Metrics refresh failed, serving previous values: {:#}", err);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+struct MetricsState {
+    /// Exposition text from the last successful refresh
+    body: RwLock<String>,
+    refresh_failures: AtomicU64,
+}
+
+impl MetricsState {
+    fn exposition(&self) -> String {
+        let mut body = self
+            .body
+            .read()
+            .map(|body| body.clone())
+            .unwrap_or_default();
+        let mut failures = MetricFamily::counter(
+            "praxis_metrics_refresh_failures_total",
+            "Refreshes that failed since the exporter started",
+        );
+        failures.push(vec![], self.refresh_failures.load(Ordering::Relaxed) as f64);
+        failures.write(&mut body);
+        body
+    }
+}
+
+fn serve(server: Server, state: &MetricsState) {
+    let content_type: Option<Header> = "Content-Type: text/plain; version=0.0.4; charset=utf-8"
+        .parse()
+        .ok();
+
+    for request in server.incoming_requests() {
+        let path = request.url().split('?').next().unwrap_or_default();
+        let response = match (request.method(), path) {
+            (Method::Get, "/metrics") => {
+                let response = Response::from_string(state.exposition());
+                match &content_type {
+                    Some(header) => response.with_header(header.clone()),
+                    None => response,
+                }
+            }
+            (Method::Get, "/") => Response::from_string("Praxis metrics exporter, see /metrics\n"),
+            _ => Response::from_string("Not found\n").with_status_code(404),
+        };
+        if let Err(err) = request.respond(response) {
+            eprintln!("Failed to send metrics response: {}", err);
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Collection
+// ---------------------------------------------------------------------------
+
+async fn collect_metrics(pool: &PgPool, args: &ServeMetricsArgs) -> Result<String> {
+    let started = Instant::now();
+    let now = now_naive_utc();
+    let active_since = now - ChronoDuration::days(args.active_days as i64);
+
+    let polls = sqlx::query_as::<_, PollCount>(
+        r#"
+        SELECT COALESCE("pollType", 'poll')::text AS poll_type,
+               stage::text AS stage,
+               COUNT(*)::bigint AS count
+        FROM poll
+        GROUP BY "pollType", stage
+        "#,
+    )
+    .fetch_all(pool);
+
+    let votes = sqlx::query_as::<_, VoteCount>(
+        r#"
+        SELECT "voteType"::text AS vote_type, COUNT(*)::bigint AS count
+        FROM vote
+        GROUP BY "voteType"
+        "#,
+    )
+    .fetch_all(pool);
+
+    let members = sqlx::query_as::<_, ServerMembers>(
+        r#"
+        SELECT s.id AS server_id,
+               s.name AS server_name,
+               COUNT(sm.id)::bigint AS members,
+               COUNT(sm.id) FILTER (WHERE sm."lastActiveAt" >= $1)::bigint AS active
+        FROM server s
+        LEFT JOIN server_member sm ON sm."serverId" = s.id
+        GROUP BY s.id
+        "#,
+    )
+    .bind(active_since)
+    .fetch_all(pool);
+
+    let messages = sqlx::query_as::<_, ChannelMessages>(
+        r#"
+        SELECT c.id AS channel_id,
+               c.name AS channel_name,
+               s.id AS server_id,
+               s.name AS server_name,
+               COUNT(m.id)::bigint AS messages
+        FROM channel c
+        JOIN server s ON s.id = c."serverId"
+        LEFT JOIN message m ON m."channelId" = c.id
+        GROUP BY c.id, s.id
+        "#,
+    )
+    .fetch_all(pool);
+
+    // Mirrors the checks in `validateInvite`: expired once `expiresAt` has
+    // passed, exhausted once a non-zero `maxUses` is reached
+    let invites = sqlx::query_as::<_, ServerInvites>(
+        r#"
+        SELECT s.id AS server_id,
+               s.name AS server_name,
+               COUNT(i.id)::bigint AS total,
+               COUNT(i.id) FILTER (WHERE i."expiresAt" <= $1)::bigint AS expired,
+               COUNT(i.id) FILTER (
+                   WHERE (i."expiresAt" IS NULL OR i."expiresAt" > $1)
+                     AND i."maxUses" > 0 AND i.uses >= i."maxUses"
+               )::bigint AS exhausted,
+               COALESCE(SUM(i.uses), 0)::bigint AS uses
+        FROM server s
+        LEFT JOIN invite i ON i."serverId" = s.id
+        GROUP BY s.id
+        "#,
+    )
+    .bind(now)
+    .fetch_all(pool);
+
+    let keys = sqlx::query_as::<_, ChannelKeys>(
+        r#"
+        SELECT c.id AS channel_id,
+               c.name AS channel_name,
+               s.id AS server_id,
+               s.name AS server_name,
+               COUNT(k.id)::bigint AS keys,
+               MAX(k."createdAt") AS newest_key_at
+        FROM channel_key k
+        JOIN channel c ON c.id = k."channelId"
+        JOIN server s ON s.id = c."serverId"
+        GROUP BY c.id, s.id
+        "#,
+    )
+    .fetch_all(pool);
+
+    let (polls, votes, members, messages, invites, keys) =
+        tokio::try_join!(polls, votes, members, messages, invites, keys)?;
+
+    // ── Gauges ───────────────────────────────────────────────────────
+
+    let mut poll_gauge = MetricFamily::gauge("praxis_polls", "Polls by type and current stage");
+    for PollCount {
+        poll_type,
+        stage,
+        count,
+    } in polls
+    {
+        poll_gauge.push(
+            vec![("poll_type", poll_type), ("stage", stage)],
+            count as f64,
+        );
+    }
+
+    let mut vote_gauge = MetricFamily::gauge("praxis_votes", "Votes by voteType");
+    for VoteCount { vote_type, count } in votes {
+        let vote_type = vote_type.unwrap_or_else(|| "unknown".to_string());
+        vote_gauge.push(vec![("vote_type", vote_type)], count as f64);
+    }
+
+    let mut member_gauge = MetricFamily::gauge("praxis_server_members", "Members per server");
+    let mut active_gauge = MetricFamily::gauge(
+        "praxis_server_active_members",
+        "Members per server whose lastActiveAt falls within --active-days",
+    );
+    for server in members {
+        let labels = server_labels(server.server_id, &server.server_name);
+        member_gauge.push(labels.clone(), server.members as f64);
+        active_gauge.push(labels, server.active as f64);
+    }
+
+    let mut message_gauge = MetricFamily::gauge("praxis_channel_messages", "Messages per channel");
+    for channel in messages {
+        message_gauge.push(
+            channel_labels(
+                channel.channel_id,
+                &channel.channel_name,
+                channel.server_id,
+                &channel.server_name,
+            ),
+            channel.messages as f64,
+        );
+    }
+
+    let mut invite_gauge = MetricFamily::gauge(
+        "praxis_invites",
+        "Invites per server by state (valid, expired or exhausted)",
+    );
+    let mut invite_uses_gauge = MetricFamily::gauge(
+        "praxis_invite_uses",
+        "Times a server's invites have been used",
+    );
+    for server in invites {
+        let valid = server.total - server.expired - server.exhausted;
+        for (state, count) in [
+            ("valid", valid),
+            ("expired", server.expired),
+            ("exhausted", server.exhausted),
+        ] {
+            let mut labels = server_labels(server.server_id, &server.server_name);
+            labels.push(("state", state.to_string()));
+            invite_gauge.push(labels, count as f64);
+        }
+        invite_uses_gauge.push(
+            server_labels(server.server_id, &server.server_name),
+            server.uses as f64,
+        );
+    }
+
+    let mut key_gauge = MetricFamily::gauge("praxis_channel_keys", "Encryption keys per channel");
+    let mut key_age_gauge = MetricFamily::gauge(
+        "praxis_channel_key_age_seconds",
+        "Age of each channel's newest encryption key",
+    );
+    for channel in keys {
+        let labels = channel_labels(
+            channel.channel_id,
+            &channel.channel_name,
+            channel.server_id,
+            &channel.server_name,
+        );
+        key_gauge.push(labels.clone(), channel.keys as f64);
+        key_age_gauge.push(
+            labels,
+            (now - channel.newest_key_at).num_seconds().max(0) as f64,
+        );
+    }
+
+    let mut refreshed_gauge = MetricFamily::gauge(
+        "praxis_metrics_refresh_timestamp_seconds",
+        "Unix time of the last successful refresh",
+    );
+    refreshed_gauge.push(vec![], Utc::now().timestamp() as f64);
+    let mut duration_gauge = MetricFamily::gauge(
+        "praxis_metrics_refresh_duration_seconds",
+        "How long the last successful refresh took",
+    );
+    duration_gauge.push(vec![], started.elapsed().as_secs_f64());
+
+    let mut body = String::new();
+    for gauge in [
+        poll_gauge,
+        vote_gauge,
+        member_gauge,
+        active_gauge,
+        message_gauge,
+        invite_gauge,
+        invite_uses_gauge,
+        key_gauge,
+        key_age_gauge,
+        refreshed_gauge,
+        duration_gauge,
+    ] {
+        gauge.write(&mut body);
+    }
+    Ok(body)
+}
+
+fn server_labels(server_id: Uuid, server_name: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("server_id", server_id.to_string()),
+        ("server", server_name.to_string()),
+    ]
+}
+
+fn channel_labels(
+    channel_id: Uuid,
+    channel_name: &str,
+    server_id: Uuid,
+    server_name: &str,
+) -> Vec<(&'static str, String)> {
+    let mut labels = server_labels(server_id, server_name);
+    labels.push(("channel_id", channel_id.to_string()));
+    labels.push(("channel", channel_name.to_string()));
+    labels
+}
+
+#[derive(FromRow)]
+struct PollCount {
+    poll_type: String,
+    stage: String,
+    count: i64,
+}
+
+#[derive(FromRow)]
+struct VoteCount {
+    vote_type: Option<String>,
+    count: i64,
+}
+
+#[derive(FromRow)]
+struct ServerMembers {
+    server_id: Uuid,
+    server_name: String,
+    members: i64,
+    active: i64,
+}
+
+#[derive(FromRow)]
+struct ChannelMessages {
+    channel_id: Uuid,
+    channel_name: String,
+    server_id: Uuid,
+    server_name: String,
+    messages: i64,
+}
+
+#[derive(FromRow)]
+struct ServerInvites {
+    server_id: Uuid,
+    server_name: String,
+    total: i64,
+    expired: i64,
+    exhausted: i64,
+    uses: i64,
+}
+
+#[derive(FromRow)]
+struct ChannelKeys {
+    channel_id: Uuid,
+    channel_name: String,
+    server_id: Uuid,
+    server_name: String,
+    keys: i64,
+    newest_key_at: NaiveDateTime,
+}
+
+// ---------------------------------------------------------------------------
+// Exposition format
+// ---------------------------------------------------------------------------
+
+/// One metric family in the Prometheus text exposition format. Everything
+/// the exporter serves is a gauge except its own failure counter.
+struct MetricFamily {
+    name: &'static str,
+    help: &'static str,
+    kind: &'static str,
+    samples: Vec<(Vec<(&'static str, String)>, f64)>,
+}
+
+impl MetricFamily {
+    fn gauge(name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            kind: "gauge",
+            samples: Vec::new(),
+        }
+    }
+
+    fn counter(name: &'static str, help: &'static str) -> Self {
+        Self {
+            kind: "counter",
+            ..Self::gauge(name, help)
+        }
+    }
+
+    fn push(&mut self, labels: Vec<(&'static str, String)>, value: f64) {
+        self.samples.push((labels, value));
+    }
+
+    fn write(&self, out: &mut String) {
+        let _ = writeln!(out, "# HELP {} {}", self.name, self.help);
+        let _ = writeln!(out, "# TYPE {} {}", self.name, self.kind);
+        for (labels, value) in &self.samples {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(name, value)| format!("{}=\"{}\"", name, escape_label(value)))
+                .collect();
+            if labels.is_empty() {
+                let _ = writeln!(out, "{} {}", self.name, value);
+            } else {
+                let _ = writeln!(out, "{}{{{}}} {}", self.name, labels.join(","), value);
+            }
+        }
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_help_type_and_samples() {
+        let mut polls = MetricFamily::gauge("praxis_polls", "Polls by type and current stage");
+        polls.push(
+            vec![
+                ("poll_type", "proposal".to_string()),
+                ("stage", "voting".to_string()),
+            ],
+            3.0,
+        );
+        let mut failures = MetricFamily::counter("praxis_failures_total", "Failed refreshes");
+        failures.push(vec![], 0.0);
+
+        let mut out = String::new();
+        polls.write(&mut out);
+        failures.write(&mut out);
+        assert_eq!(
+            out,
+            "# HELP praxis_polls Polls by type and current stage\n\
+             # TYPE praxis_polls gauge\n\
+             praxis_polls{poll_type=\"proposal\",stage=\"voting\"} 3\n\
+             # HELP praxis_failures_total Failed refreshes\n\
+             # TYPE praxis_failures_total counter\n\
+             praxis_failures_total 0\n"
+        );
+    }
+
+    #[test]
+    fn escapes_label_values() {
+        assert_eq!(escape_label("plain"), "plain");
+        assert_eq!(
+            escape_label("C:\\praxis \"general\"\nsecond"),
+            r#"C:\\praxis \"general\"\nsecond"#
+        );
+
+        let mut messages = MetricFamily::gauge("praxis_channel_messages", "Messages per channel");
+        messages.push(vec![("channel", "a\"b\nc".to_string())], 1.5);
+        let mut out = String::new();
+        messages.write(&mut out);
+        assert_eq!(out.lines().count(), 3);
+        assert!(out.ends_with("praxis_channel_messages{channel=\"a\\\"b\\nc\"} 1.5\n"));
+    }
+}
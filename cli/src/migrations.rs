@@ -0,0 +1,337 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use glob::glob;
+use owo_colors::{AnsiColors, OwoColorize};
+use regex::Regex;
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+
+use crate::output::{OutputFormat, Record, Report, render};
+use crate::routes::find_src_dir;
+use crate::utils::format_timestamp;
+
+// ---------------------------------------------------------------------------
+// Pre-compiled regexes
+// ---------------------------------------------------------------------------
+
+/// Matches `1768785488966-Quorum.ts`
+static FILE_NAME_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\d{13})-([\w-]+)\.ts$").unwrap());
+
+/// Matches `export class Quorum1768785488966 implements MigrationInterface`
+static CLASS_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"export\s+class\s+(\w+)\s+implements\s+MigrationInterface").unwrap()
+});
+
+/// Matches `name = 'Quorum1768785488966'`, which TypeORM records when set
+static NAME_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?m)^\s*(?:public\s+)?name\s*=\s*['"](\w+)['"]"#).unwrap());
+
+// ---------------------------------------------------------------------------
+// Migration files
+// ---------------------------------------------------------------------------
+
+/// A timestamped migration in `src/database/migrations`.
+#[derive(Debug)]
+pub struct MigrationFile {
+    /// The name TypeORM records in the `migrations` table
+    pub name: String,
+    pub timestamp: i64,
+    /// Path relative to the repository root
+    pub file: String,
+    pub content: String,
+}
+
+/// Reads every timestamped migration, oldest first.
+pub fn migration_files() -> Result<Vec<MigrationFile>> {
+    let src_dir = find_src_dir()?;
+    let root = src_dir.parent().unwrap_or(&src_dir).to_path_buf();
+    let pattern = src_dir.join("database/migrations/*.ts");
+
+    let mut files = Vec::new();
+    for entry in glob(&pattern.to_string_lossy()).context("failed to read glob pattern")? {
+        let path = entry?;
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let Some(cap) = FILE_NAME_RE.captures(&file_name) else {
+            continue;
+        };
+        let timestamp: i64 = cap[1].parse()?;
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+
+        // TypeORM records the `name` property, falling back to the class name
+        let name = NAME_RE
+            .captures(&content)
+            .or_else(|| CLASS_RE.captures(&content))
+            .map(|cap| cap[1].to_string())
+            .unwrap_or_else(|| format!("{}{}", cap[2].replace('-', ""), timestamp));
+
+        files.push(MigrationFile {
+            name,
+            timestamp,
+            file: relative_path(&path, &root),
+            content,
+        });
+    }
+    files.sort_by_key(|file| file.timestamp);
+    Ok(files)
+}
+
+fn relative_path(path: &Path, root: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+/// When a migration was generated, from the millisecond timestamp in its name.
+fn generated_at(timestamp: i64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp_millis(timestamp)
+}
+
+// ---------------------------------------------------------------------------
+// Status
+// ---------------------------------------------------------------------------
+
+pub async fn run_migrations_status(pool: &PgPool, format: OutputFormat) -> Result<()> {
+    let files = migration_files()?;
+    let report = collect_migration_status(pool, &files).await?;
+    render(&report, format)?;
+
+    let pending = report.count(MigrationStatus::Pending);
+    if pending > 0 {
+        bail!("the database is {} migration(s) behind the code", pending);
+    }
+    Ok(())
+}
+
+#[derive(Debug, FromRow)]
+struct MigrationRow {
+    id: i32,
+    timestamp: i64,
+    name: String,
+}
+
+async fn collect_migration_status(
+    pool: &PgPool,
+    files: &[MigrationFile],
+) -> Result<MigrationStatusReport> {
+    // A database TypeORM has never migrated has no `migrations` table yet
+    let table_exists: bool =
+        sqlx::query_scalar("SELECT to_regclass('public.migrations') IS NOT NULL")
+            .fetch_one(pool)
+            .await?;
+    let rows = if table_exists {
+        sqlx::query_as::<_, MigrationRow>(
+            r#"
+            SELECT id, "timestamp", name
+            FROM migrations
+            ORDER BY id
+            "#,
+        )
+        .fetch_all(pool)
+        .await?
+    } else {
+        Vec::new()
+    };
+
+    let mut applied: HashMap<&str, &MigrationRow> =
+        rows.iter().map(|row| (row.name.as_str(), row)).collect();
+    let mut migrations: Vec<MigrationEntry> = files
+        .iter()
+        .map(|file| {
+            let row = applied.remove(file.name.as_str());
+            MigrationEntry {
+                name: file.name.clone(),
+                timestamp: file.timestamp,
+                generated_at: generated_at(file.timestamp),
+                status: if row.is_some() {
+                    MigrationStatus::Applied
+                } else {
+                    MigrationStatus::Pending
+                },
+                run_order: row.map(|row| row.id),
+                file: Some(file.file.clone()),
+            }
+        })
+        .collect();
+
+    // Recorded migrations without a file, e.g. run from another branch
+    for row in rows
+        .iter()
+        .filter(|row| applied.contains_key(row.name.as_str()))
+    {
+        migrations.push(MigrationEntry {
+            name: row.name.clone(),
+            timestamp: row.timestamp,
+            generated_at: generated_at(row.timestamp),
+            status: MigrationStatus::Unknown,
+            run_order: Some(row.id),
+            file: None,
+        });
+    }
+    migrations.sort_by_key(|migration| migration.timestamp);
+
+    Ok(MigrationStatusReport {
+        table_exists,
+        files: files.len(),
+        recorded: rows.len(),
+        migrations,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Report
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MigrationStatusReport {
+    table_exists: bool,
+    files: usize,
+    recorded: usize,
+    migrations: Vec<MigrationEntry>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MigrationEntry {
+    name: String,
+    timestamp: i64,
+    /// When the migration was generated, from its timestamp
+    generated_at: Option<DateTime<Utc>>,
+    status: MigrationStatus,
+    /// The `migrations.id` TypeORM assigned when it ran, which gives the
+    /// order migrations were applied in
+    run_order: Option<i32>,
+    file: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum MigrationStatus {
+    Applied,
+    Pending,
+    Unknown,
+}
+
+impl MigrationStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            MigrationStatus::Applied => "applied",
+            MigrationStatus::Pending => "pending",
+            MigrationStatus::Unknown => "unknown",
+        }
+    }
+
+    fn color(self) -> AnsiColors {
+        match self {
+            MigrationStatus::Applied => AnsiColors::Green,
+            MigrationStatus::Pending => AnsiColors::Yellow,
+            MigrationStatus::Unknown => AnsiColors::Red,
+        }
+    }
+}
+
+impl MigrationStatusReport {
+    fn count(&self, status: MigrationStatus) -> usize {
+        self.migrations
+            .iter()
+            .filter(|migration| migration.status == status)
+            .count()
+    }
+}
+
+impl Report for MigrationStatusReport {
+    const KIND: &'static str = "migrations-status";
+    const SCHEMA_VERSION: u32 = 1;
+
+    fn render_text(&self) {
+        println!(
+            "\n{} {}",
+            "Migration Status".bold().underline(),
+            format!(
+                "({} files, {} recorded in the migrations table)",
+                self.files, self.recorded
+            )
+            .dimmed()
+        );
+        println!(
+            "{} {} applied, {} pending, {} unknown",
+            "•".cyan(),
+            self.count(MigrationStatus::Applied).to_string().bold(),
+            self.count(MigrationStatus::Pending).to_string().bold(),
+            self.count(MigrationStatus::Unknown).to_string().bold()
+        );
+        if !self.table_exists {
+            println!(
+                "{}",
+                "  The migrations table doesn't exist, so no migration has run".dimmed()
+            );
+        }
+
+        println!();
+        for migration in &self.migrations {
+            let status = format!("{:<8}", migration.status.as_str());
+            let run_order = migration
+                .run_order
+                .map(|id| format!("#{}", id))
+                .unwrap_or_default();
+            let generated = migration
+                .generated_at
+                .map(|at| format_timestamp(at, "%Y-%m-%d %H:%M"))
+                .unwrap_or_default();
+            let file = migration
+                .file
+                .clone()
+                .unwrap_or_else(|| "no file on disk".to_string());
+            println!(
+                "  {} {:>4}  {}  {:<32} {}",
+                status.color(migration.status.color()),
+                run_order,
+                generated.dimmed(),
+                migration.name,
+                file.dimmed()
+            );
+        }
+        println!(
+            "\n{}",
+            "  TypeORM records the order migrations ran in (#), not when; times are when each was generated"
+                .dimmed()
+        );
+    }
+
+    fn records(&self) -> Vec<Record> {
+        let mut records = vec![
+            Record::new("summary")
+                .field("tableExists", self.table_exists)
+                .field("files", self.files)
+                .field("recorded", self.recorded)
+                .field("applied", self.count(MigrationStatus::Applied))
+                .field("pending", self.count(MigrationStatus::Pending))
+                .field("unknown", self.count(MigrationStatus::Unknown)),
+        ];
+        for migration in &self.migrations {
+            records.push(
+                Record::new("migrations")
+                    .field("name", migration.name.as_str())
+                    .field("timestamp", migration.timestamp)
+                    .field(
+                        "generatedAt",
+                        migration.generated_at.map(|at| at.to_rfc3339()),
+                    )
+                    .field("status", migration.status.as_str())
+                    .field("runOrder", migration.run_order)
+                    .field("file", migration.file.clone()),
+            );
+        }
+        records
+    }
+}
@@ -0,0 +1,275 @@
+use std::io::{self, Write};
+
+use anyhow::{Result, bail};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use clap::ValueEnum;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+// ---------------------------------------------------------------------------
+// Output formats
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Colored, human-readable terminal output
+    #[default]
+    Text,
+    /// A single JSON document wrapped in a versioned envelope
+    Json,
+    /// One CSV row per record, with a `section` column
+    Csv,
+    /// One JSON object per line, one line per record
+    Ndjson,
+    /// A Markdown document (`schema` only)
+    Markdown,
+    /// A Mermaid entity-relationship diagram (`schema` only)
+    Mermaid,
+    /// A Graphviz DOT entity-relationship diagram (`schema` only)
+    Dot,
+}
+
+impl OutputFormat {
+    /// Formats that render a whole document rather than records, which only
+    /// `schema` supports.
+    pub fn is_document(self) -> bool {
+        matches!(
+            self,
+            OutputFormat::Markdown | OutputFormat::Mermaid | OutputFormat::Dot
+        )
+    }
+
+    /// The name as given to `--format`.
+    pub fn name(self) -> String {
+        self.to_possible_value()
+            .map(|value| value.get_name().to_string())
+            .unwrap_or_default()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Reports
+// ---------------------------------------------------------------------------
+
+/// A typed command result that can be handed to any of the renderers.
+///
+/// Machine-readable output carries `KIND` and `SCHEMA_VERSION` so automation
+/// can detect breaking changes. Adding fields or sections is backwards
+/// compatible; renaming, removing or changing the meaning of a field requires
+/// bumping `SCHEMA_VERSION`.
+pub trait Report: Serialize {
+    const KIND: &'static str;
+    const SCHEMA_VERSION: u32;
+
+    /// Human-readable output used by `--format text`
+    fn render_text(&self);
+
+    /// Flattened rows used by `--format csv` and `--format ndjson`
+    fn records(&self) -> Vec<Record>;
+
+    /// Documents used by `--format markdown`, `mermaid` and `dot`. `None`
+    /// when the report doesn't support the format.
+    fn render_document(&self, _format: OutputFormat) -> Option<String> {
+        None
+    }
+}
+
+/// A single flat row belonging to a named report section.
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub section: &'static str,
+    pub fields: Vec<(&'static str, Value)>,
+}
+
+impl Record {
+    pub fn new(section: &'static str) -> Self {
+        Self {
+            section,
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn field(mut self, name: &'static str, value: impl Into<Value>) -> Self {
+        self.fields.push((name, value.into()));
+        self
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Envelope<'a, T: Serialize> {
+    kind: &'static str,
+    schema_version: u32,
+    generated_at: DateTime<Utc>,
+    data: &'a T,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OwnedEnvelope {
+    kind: String,
+    schema_version: u32,
+    generated_at: DateTime<Utc>,
+    data: Value,
+}
+
+pub fn render<R: Report>(report: &R, format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Text => {
+            report.render_text();
+            Ok(())
+        }
+        OutputFormat::Json => render_json(report),
+        OutputFormat::Csv => render_csv(&report.records()),
+        OutputFormat::Ndjson => render_ndjson::<R>(&report.records()),
+        OutputFormat::Markdown | OutputFormat::Mermaid | OutputFormat::Dot => {
+            let Some(document) = report.render_document(format) else {
+                bail!("{} does not support --format {}", R::KIND, format.name());
+            };
+            let mut out = io::stdout().lock();
+            write!(out, "{}", document)?;
+            Ok(())
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Renderers
+// ---------------------------------------------------------------------------
+
+fn render_json<R: Report>(report: &R) -> Result<()> {
+    let mut out = io::stdout().lock();
+    writeln!(out, "{}", to_json(report)?)?;
+    Ok(())
+}
+
+/// The `--format json` document for a report, for commands that save it to a
+/// file.
+pub fn to_json<R: Report>(report: &R) -> Result<String> {
+    let envelope = Envelope {
+        kind: R::KIND,
+        schema_version: R::SCHEMA_VERSION,
+        generated_at: Utc::now(),
+        data: report,
+    };
+    Ok(serde_json::to_string_pretty(&envelope)?)
+}
+
+/// Reads a document written by `to_json` (or `--format json`) back into its
+/// report, along with when it was generated. Fails when the document holds a
+/// different kind of report or a newer schema version.
+pub fn from_json<R: Report + DeserializeOwned>(contents: &str) -> Result<(R, DateTime<Utc>)> {
+    let envelope: OwnedEnvelope = serde_json::from_str(contents)?;
+    if envelope.kind != R::KIND {
+        bail!("expected a {} document, found {}", R::KIND, envelope.kind);
+    }
+    if envelope.schema_version > R::SCHEMA_VERSION {
+        bail!(
+            "{} schema version {} is newer than the supported version {}",
+            R::KIND,
+            envelope.schema_version,
+            R::SCHEMA_VERSION
+        );
+    }
+    Ok((
+        serde_json::from_value(envelope.data)?,
+        envelope.generated_at,
+    ))
+}
+
+fn render_ndjson<R: Report>(records: &[Record]) -> Result<()> {
+    let mut out = io::stdout().lock();
+    for record in records {
+        let mut object = Map::new();
+        object.insert("kind".into(), R::KIND.into());
+        object.insert("schemaVersion".into(), R::SCHEMA_VERSION.into());
+        object.insert("section".into(), record.section.into());
+        for (name, value) in &record.fields {
+            object.insert((*name).into(), value.clone());
+        }
+        serde_json::to_writer(&mut out, &object)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn render_csv(records: &[Record]) -> Result<()> {
+    // Sections carry different fields, so the header is the union of every
+    // field name in first-seen order and missing cells are left empty.
+    let mut columns: Vec<&'static str> = Vec::new();
+    for record in records {
+        for (name, _) in &record.fields {
+            if !columns.contains(name) {
+                columns.push(name);
+            }
+        }
+    }
+
+    let mut out = io::stdout().lock();
+    let header: Vec<&str> = std::iter::once("section")
+        .chain(columns.iter().copied())
+        .collect();
+    writeln!(out, "{}", header.join(","))?;
+
+    for record in records {
+        let mut cells = vec![csv_escape(record.section)];
+        for column in &columns {
+            let cell = record
+                .fields
+                .iter()
+                .find(|(name, _)| name == column)
+                .map(|(_, value)| csv_value(value))
+                .unwrap_or_default();
+            cells.push(cell);
+        }
+        writeln!(out, "{}", cells.join(","))?;
+    }
+
+    Ok(())
+}
+
+fn csv_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => csv_escape(s),
+        other => csv_escape(&other.to_string()),
+    }
+}
+
+fn csv_escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Serde helpers
+// ---------------------------------------------------------------------------
+
+/// Serializes a `timestamp without time zone` column as an RFC 3339 UTC string.
+pub fn serialize_naive_utc<S: Serializer>(
+    value: &Option<NaiveDateTime>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    value
+        .map(|ts| DateTime::<Utc>::from_naive_utc_and_offset(ts, Utc))
+        .serialize(serializer)
+}
+
+/// Like `serialize_naive_utc`, for `NOT NULL` columns.
+pub fn serialize_naive_utc_required<S: Serializer>(
+    value: &NaiveDateTime,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    value.and_utc().serialize(serializer)
+}
+
+/// Converts a `timestamp without time zone` column into a JSON value.
+pub fn naive_utc_value(value: Option<NaiveDateTime>) -> Value {
+    value
+        .map(|ts| DateTime::<Utc>::from_naive_utc_and_offset(ts, Utc).to_rfc3339())
+        .into()
+}
@@ -1,266 +1,790 @@
-use anyhow::Result;
-use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
-use owo_colors::OwoColorize;
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use owo_colors::{AnsiColors, OwoColorize};
+use serde::Serialize;
 use sqlx::{FromRow, PgPool};
 use uuid::Uuid;
 
-use crate::utils::{color_stage, color_vote, format_number, humanize, pct};
+use crate::chart::{Distribution, print_bars, print_stacked_bars, sparkline};
+use crate::cli::PollStatsArgs;
+use crate::compare::{Comparison, MetricDelta, Unit, comparison_records, print_comparison};
+use crate::lifecycle::{LifecycleStats, collect_lifecycle, lifecycle_records, print_lifecycle};
+use crate::output::{OutputFormat, Record, Report, naive_utc_value, render, serialize_naive_utc};
+use crate::scope::{PollScope, ScopeNames, resolve_channel_name, resolve_server};
+use crate::timing::QueryTimer;
+use crate::turnout::{TurnoutStats, collect_turnout, print_turnout, turnout_records};
+use crate::utils::{color_stage, color_vote, format_channel, format_number, humanize, pct};
+use crate::watch::{
+    Changes, Snapshot, clear_screen, print_watch_header, take_snapshot, wait, watch_proposal,
+};
+use crate::window::{Bucket, TimeWindow, zero_fill};
+
+/// Upper bound on histogram rows for the votes-per-poll distribution.
+const HISTOGRAM_BINS: usize = 12;
 
 pub async fn run_poll_stats(
     pool: &PgPool,
-    days: i32,
-    channel_id: Option<Uuid>,
-    poll_id: Option<Uuid>,
-    top_polls: u32,
-    top_channels: u32,
+    window: TimeWindow,
+    args: &PollStatsArgs,
+    format: OutputFormat,
 ) -> Result<()> {
-    let since = Utc::now() - Duration::days(days as i64);
-    println!(
-        "\n{} {}",
-        "Poll Stats".bold().underline(),
-        format!("(last {} days, since {})", days, since.format("%Y-%m-%d")).dimmed()
-    );
+    let mut names = ScopeNames::default();
+    let mut server_id = None;
+    if let Some(id_or_slug) = &args.server_id {
+        let (id, name) = resolve_server(pool, id_or_slug).await?;
+        server_id = Some(id);
+        names.server_name = Some(name);
+    }
+    if let Some(channel_id) = args.channel_id {
+        names.channel_name = resolve_channel_name(pool, channel_id).await?;
+    }
 
-    // ── Poll type breakdown ──────────────────────────────────────────
+    let scope = PollScope {
+        server_id,
+        channel_id: args.channel_id,
+        poll_id: args.poll_id,
+        ..PollScope::new(&window)
+    };
+    if let Some(interval) = args.watch {
+        if format != OutputFormat::Text {
+            bail!("--watch redraws the terminal and only supports --format text");
+        }
+        let interval = Duration::from_secs(interval);
+        return match args.poll_id {
+            Some(poll_id) => watch_proposal(pool, poll_id, interval).await,
+            None => watch_poll_stats(pool, window, scope, &names, args, interval).await,
+        };
+    }
 
-    let poll_type_counts: Vec<PollTypeCount> = sqlx::query_as(
+    let timer = QueryTimer::new();
+    let report = collect_poll_stats(pool, window, scope, names, args, &timer).await?;
+    render(&report, format)?;
+    if args.timings {
+        timer.print();
+    }
+    Ok(())
+}
+
+/// Redraws the report until Ctrl-C is pressed, listing what changed since the
+/// previous refresh above it.
+async fn watch_poll_stats(
+    pool: &PgPool,
+    window: TimeWindow,
+    scope: PollScope,
+    names: &ScopeNames,
+    args: &PollStatsArgs,
+    interval: Duration,
+) -> Result<()> {
+    let mut previous: Option<Snapshot> = None;
+    loop {
+        let window = window.refreshed();
+        let scope = PollScope {
+            since: window.since,
+            until: window.until,
+            ..scope
+        };
+        let timer = QueryTimer::new();
+        let (report, snapshot) = tokio::try_join!(
+            collect_poll_stats(pool, window, scope, names.clone(), args, &timer),
+            timer.time("watch snapshot", take_snapshot(pool, &scope)),
+        )?;
+
+        clear_screen()?;
+        print_watch_header(interval);
+        if let Some(previous) = &previous {
+            Changes::between(previous, &snapshot).print();
+        }
+        report.render_text();
+        if args.timings {
+            timer.print();
+        }
+
+        previous = Some(snapshot);
+        if !wait(interval).await {
+            return Ok(());
+        }
+    }
+}
+
+async fn collect_poll_stats(
+    pool: &PgPool,
+    window: TimeWindow,
+    scope: PollScope,
+    names: ScopeNames,
+    args: &PollStatsArgs,
+    timer: &QueryTimer,
+) -> Result<PollStatsReport> {
+    let PollScope {
+        since,
+        until,
+        server_id,
+        channel_id,
+        poll_id,
+    } = scope;
+
+    // Every query below is independent, so they are built up front and run
+    // concurrently over the pool.
+
+    // ── Vote stats ───────────────────────────────────────────────────
+
+    let vote_mix = sqlx::query_as::<_, VoteTypeCount>(
         r#"
-        SELECT COALESCE("pollType", 'poll')::text AS poll_type,
+        SELECT v."voteType"::text AS vote_type,
                COUNT(*)::bigint AS count
-        FROM poll
-        WHERE "createdAt" >= NOW() - ($1::int * INTERVAL '1 day')
-          AND ($2::uuid IS NULL OR "channelId" = $2)
-          AND ($3::uuid IS NULL OR id = $3)
-        GROUP BY "pollType"
+        FROM vote v
+        JOIN poll p ON p.id = v."pollId"
+        WHERE v."createdAt" >= $1 AND v."createdAt" < $5
+          AND ($2::uuid IS NULL OR p."channelId" = $2)
+          AND ($3::uuid IS NULL OR p.id = $3)
+          AND ($4::uuid IS NULL OR p."channelId" IN (SELECT id FROM channel WHERE "serverId" = $4))
+        GROUP BY v."voteType"
         ORDER BY count DESC
         "#,
     )
-    .bind(days)
+    .bind(since)
     .bind(channel_id)
     .bind(poll_id)
-    .fetch_all(pool)
-    .await?;
-
-    let total_polls: i64 = poll_type_counts.iter().map(|r| r.count).sum();
-    println!(
-        "{} {} polls created",
-        "•".cyan(),
-        format_number(total_polls).bold()
-    );
+    .bind(server_id)
+    .bind(until)
+    .fetch_all(pool);
 
-    for PollTypeCount { poll_type, count } in &poll_type_counts {
-        println!(
-            "  {} {:<10} {:>6} ({:>5.1}%)",
-            "→".dimmed(),
-            poll_type.bold(),
-            count.to_string().bold(),
-            pct(*count, total_polls)
-        );
-    }
+    let votes_per_poll = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(v.id)::bigint
+        FROM poll p
+        LEFT JOIN vote v ON v."pollId" = p.id
+        WHERE p."createdAt" >= $1 AND p."createdAt" < $5
+          AND ($2::uuid IS NULL OR p."channelId" = $2)
+          AND ($3::uuid IS NULL OR p.id = $3)
+          AND ($4::uuid IS NULL OR p."channelId" IN (SELECT id FROM channel WHERE "serverId" = $4))
+        GROUP BY p.id
+        "#,
+    )
+    .bind(since)
+    .bind(channel_id)
+    .bind(poll_id)
+    .bind(server_id)
+    .bind(until)
+    .fetch_all(pool);
 
-    // ── Proposal stage distribution ──────────────────────────────────
+    // ── Creation trend ───────────────────────────────────────────────
 
-    let stage_counts: Vec<StageCount> = sqlx::query_as(
+    let bucket_counts = sqlx::query_as::<_, TrendBucket>(
         r#"
-        SELECT stage::text AS stage, COUNT(*)::bigint AS count
+        SELECT DATE_TRUNC($6::text, "createdAt" AT TIME ZONE 'UTC' AT TIME ZONE $7)::date AS start,
+               COUNT(*)::bigint AS count
         FROM poll
-        WHERE "pollType" = 'proposal'
-          AND "createdAt" >= NOW() - ($1::int * INTERVAL '1 day')
+        WHERE "createdAt" >= $1 AND "createdAt" < $5
           AND ($2::uuid IS NULL OR "channelId" = $2)
           AND ($3::uuid IS NULL OR id = $3)
-        GROUP BY stage
-        ORDER BY count DESC
+          AND ($4::uuid IS NULL OR "channelId" IN (SELECT id FROM channel WHERE "serverId" = $4))
+        GROUP BY start
         "#,
     )
-    .bind(days)
+    .bind(since)
     .bind(channel_id)
     .bind(poll_id)
-    .fetch_all(pool)
-    .await?;
-
-    let total_proposals: i64 = stage_counts.iter().map(|r| r.count).sum();
-    if !stage_counts.is_empty() {
-        println!("\n{}", "Proposal Stages".bold());
-        for StageCount { stage, count } in &stage_counts {
-            println!(
-                "  {} {:<10} {:>6} ({:>5.1}%)",
-                "→".dimmed(),
-                color_stage(stage),
-                count.to_string().bold(),
-                pct(*count, total_proposals)
-            );
-        }
-    }
+    .bind(server_id)
+    .bind(until)
+    .bind(args.bucket.as_str())
+    .bind(window.timezone.name())
+    .fetch_all(pool);
 
-    // ── Vote stats ───────────────────────────────────────────────────
-
-    let total_votes: i64 = sqlx::query_scalar(
+    let vote_bucket_counts = sqlx::query_as::<_, VoteTrendBucket>(
         r#"
-        SELECT COUNT(*)::bigint
+        SELECT DATE_TRUNC($6::text, v."createdAt" AT TIME ZONE 'UTC' AT TIME ZONE $7)::date AS start,
+               COUNT(*) FILTER (WHERE v."voteType" = 'agree')::bigint AS agree,
+               COUNT(*) FILTER (WHERE v."voteType" = 'disagree')::bigint AS disagree,
+               COUNT(*) FILTER (WHERE v."voteType" = 'abstain')::bigint AS abstain,
+               COUNT(*) FILTER (WHERE v."voteType" = 'block')::bigint AS block,
+               COUNT(*) FILTER (WHERE v."voteType" IS NULL)::bigint AS other
         FROM vote v
         JOIN poll p ON p.id = v."pollId"
-        WHERE v."createdAt" >= NOW() - ($1::int * INTERVAL '1 day')
+        WHERE v."createdAt" >= $1 AND v."createdAt" < $5
           AND ($2::uuid IS NULL OR p."channelId" = $2)
           AND ($3::uuid IS NULL OR p.id = $3)
+          AND ($4::uuid IS NULL OR p."channelId" IN (SELECT id FROM channel WHERE "serverId" = $4))
+        GROUP BY start
         "#,
     )
-    .bind(days)
+    .bind(since)
     .bind(channel_id)
     .bind(poll_id)
-    .fetch_one(pool)
-    .await?;
+    .bind(server_id)
+    .bind(until)
+    .bind(args.bucket.as_str())
+    .bind(window.timezone.name())
+    .fetch_all(pool);
+
+    // ── Most active polls ────────────────────────────────────────────
 
-    let unique_voters: i64 = sqlx::query_scalar(
+    let most_active_polls = async {
+        if poll_id.is_some() || args.top_polls == 0 {
+            return Ok(Vec::new());
+        }
+        let query = sqlx::query_as::<_, PollParticipation>(
+            r#"
+            SELECT p.id AS poll_id,
+                   p."channelId" AS channel_id,
+                   c.name AS channel_name,
+                   s.name AS server_name,
+                   COUNT(v.id)::bigint AS votes,
+                   MAX(v."createdAt") AS last_vote_at
+            FROM poll p
+            JOIN channel c ON c.id = p."channelId"
+            JOIN server s ON s.id = c."serverId"
+            LEFT JOIN vote v ON v."pollId" = p.id
+            WHERE p."createdAt" >= $1 AND p."createdAt" < $5
+              AND ($2::uuid IS NULL OR p."channelId" = $2)
+              AND ($3::uuid IS NULL OR c."serverId" = $3)
+            GROUP BY p.id, c.id, s.id
+            HAVING COUNT(v.id) > 0
+            ORDER BY votes DESC, last_vote_at DESC NULLS LAST
+            LIMIT $4::int
+            "#,
+        )
+        .bind(since)
+        .bind(channel_id)
+        .bind(server_id)
+        .bind(args.top_polls as i32)
+        .bind(until)
+        .fetch_all(pool);
+        timer.time("most active polls", query).await
+    };
+
+    // ── Top channels ─────────────────────────────────────────────────
+
+    let top_channel_counts = async {
+        if args.top_channels == 0 {
+            return Ok(Vec::new());
+        }
+        let query = sqlx::query_as::<_, ChannelCount>(
+            r#"
+            SELECT p."channelId" AS channel_id,
+                   c.name AS channel_name,
+                   s.name AS server_name,
+                   COUNT(*)::bigint AS count
+            FROM poll p
+            JOIN channel c ON c.id = p."channelId"
+            JOIN server s ON s.id = c."serverId"
+            WHERE p."createdAt" >= $1 AND p."createdAt" < $5
+              AND ($2::uuid IS NULL OR p."channelId" = $2)
+              AND ($3::uuid IS NULL OR c."serverId" = $3)
+            GROUP BY p."channelId", c.id, s.id
+            ORDER BY count DESC
+            LIMIT $4::int
+            "#,
+        )
+        .bind(since)
+        .bind(channel_id)
+        .bind(server_id)
+        .bind(args.top_channels as i32)
+        .bind(until)
+        .fetch_all(pool);
+        timer.time("top channels", query).await
+    };
+
+    let previous_scope = scope.previous();
+    let previous_metrics = async {
+        if !args.compare {
+            return Ok(None);
+        }
+        collect_window_metrics(pool, &previous_scope, timer, "previous")
+            .await
+            .map(Some)
+    };
+
+    let (
+        metrics,
+        previous_metrics,
+        lifecycle,
+        vote_mix,
+        votes_per_poll,
+        turnout,
+        bucket_counts,
+        vote_bucket_counts,
+        most_active_polls,
+        top_channel_counts,
+    ) = tokio::try_join!(
+        collect_window_metrics(pool, &scope, timer, "current"),
+        previous_metrics,
+        collect_lifecycle(pool, &scope, timer),
+        timer.time("vote mix", vote_mix),
+        timer.time("votes per poll", votes_per_poll),
+        collect_turnout(pool, &scope, args.min_turnout, timer),
+        timer.time("creation trend", bucket_counts),
+        timer.time("vote trend", vote_bucket_counts),
+        most_active_polls,
+        top_channel_counts,
+    )?;
+
+    let votes_per_poll = Distribution::new(votes_per_poll, HISTOGRAM_BINS);
+    let comparison =
+        previous_metrics.map(|previous| compare_windows(&previous_scope, &metrics, &previous));
+
+    let starts = args.bucket.starts(&window);
+    let trend = zero_fill(
+        &starts,
+        bucket_counts,
+        |bucket| bucket.start,
+        |start| TrendBucket { start, count: 0 },
+    );
+    let vote_trend = zero_fill(
+        &starts,
+        vote_bucket_counts,
+        |bucket| bucket.start,
+        |start| VoteTrendBucket {
+            start,
+            ..Default::default()
+        },
+    );
+
+    let WindowMetrics {
+        poll_types,
+        stages,
+        total_votes,
+        unique_voters,
+        avg_votes_per_poll,
+    } = metrics;
+
+    Ok(PollStatsReport {
+        window_days: window.days(),
+        window,
+        server_id,
+        channel_id,
+        poll_id,
+        names,
+        total_polls: poll_types.iter().map(|r| r.count).sum(),
+        poll_types,
+        total_proposals: stages.iter().map(|r| r.count).sum(),
+        stages,
+        lifecycle,
+        total_votes,
+        unique_voters,
+        vote_mix,
+        avg_votes_per_poll,
+        votes_per_poll,
+        turnout,
+        bucket: args.bucket,
+        trend,
+        vote_trend,
+        most_active_polls,
+        top_channels: top_channel_counts,
+        comparison,
+    })
+}
+
+/// Headline numbers that `--compare` computes for both windows.
+struct WindowMetrics {
+    poll_types: Vec<PollTypeCount>,
+    stages: Vec<StageCount>,
+    total_votes: i64,
+    unique_voters: i64,
+    avg_votes_per_poll: Option<f64>,
+}
+
+impl WindowMetrics {
+    fn total_polls(&self) -> i64 {
+        self.poll_types.iter().map(|r| r.count).sum()
+    }
+
+    fn total_proposals(&self) -> i64 {
+        self.stages.iter().map(|r| r.count).sum()
+    }
+}
+
+/// `label` tells the current and previous window apart in `--timings`.
+async fn collect_window_metrics(
+    pool: &PgPool,
+    scope: &PollScope,
+    timer: &QueryTimer,
+    label: &str,
+) -> Result<WindowMetrics> {
+    let PollScope {
+        since,
+        until,
+        server_id,
+        channel_id,
+        poll_id,
+    } = *scope;
+
+    // ── Poll type breakdown ──────────────────────────────────────────
+
+    let poll_types = sqlx::query_as::<_, PollTypeCount>(
         r#"
-        SELECT COUNT(DISTINCT v."userId")::bigint
-        FROM vote v
-        JOIN poll p ON p.id = v."pollId"
-        WHERE v."createdAt" >= NOW() - ($1::int * INTERVAL '1 day')
-          AND ($2::uuid IS NULL OR p."channelId" = $2)
-          AND ($3::uuid IS NULL OR p.id = $3)
+        SELECT COALESCE("pollType", 'poll')::text AS poll_type,
+               COUNT(*)::bigint AS count
+        FROM poll
+        WHERE "createdAt" >= $1 AND "createdAt" < $5
+          AND ($2::uuid IS NULL OR "channelId" = $2)
+          AND ($3::uuid IS NULL OR id = $3)
+          AND ($4::uuid IS NULL OR "channelId" IN (SELECT id FROM channel WHERE "serverId" = $4))
+        GROUP BY "pollType"
+        ORDER BY count DESC
         "#,
     )
-    .bind(days)
+    .bind(since)
     .bind(channel_id)
     .bind(poll_id)
-    .fetch_one(pool)
-    .await?;
-
-    println!(
-        "\n{}\n{} {} votes captured\n{} {} unique voters",
-        "Votes".bold(),
-        "•".cyan(),
-        format_number(total_votes).bold(),
-        "•".cyan(),
-        format_number(unique_voters).bold()
-    );
+    .bind(server_id)
+    .bind(until)
+    .fetch_all(pool);
 
-    let vote_mix: Vec<VoteTypeCount> = sqlx::query_as(
+    // ── Proposal stage distribution ──────────────────────────────────
+
+    let stages = sqlx::query_as::<_, StageCount>(
         r#"
-        SELECT v."voteType"::text AS vote_type,
-               COUNT(*)::bigint AS count
-        FROM vote v
-        JOIN poll p ON p.id = v."pollId"
-        WHERE v."createdAt" >= NOW() - ($1::int * INTERVAL '1 day')
-          AND ($2::uuid IS NULL OR p."channelId" = $2)
-          AND ($3::uuid IS NULL OR p.id = $3)
-        GROUP BY v."voteType"
+        SELECT stage::text AS stage, COUNT(*)::bigint AS count
+        FROM poll
+        WHERE "pollType" = 'proposal'
+          AND "createdAt" >= $1 AND "createdAt" < $5
+          AND ($2::uuid IS NULL OR "channelId" = $2)
+          AND ($3::uuid IS NULL OR id = $3)
+          AND ($4::uuid IS NULL OR "channelId" IN (SELECT id FROM channel WHERE "serverId" = $4))
+        GROUP BY stage
         ORDER BY count DESC
         "#,
     )
-    .bind(days)
+    .bind(since)
     .bind(channel_id)
     .bind(poll_id)
-    .fetch_all(pool)
-    .await?;
+    .bind(server_id)
+    .bind(until)
+    .fetch_all(pool);
 
-    if !vote_mix.is_empty() {
-        for VoteTypeCount { vote_type, count } in &vote_mix {
-            let label = vote_type.as_deref().unwrap_or("unknown");
-            println!(
-                "  {} {:<9} {:>6} ({:>5.1}%)",
-                "→".dimmed(),
-                color_vote(label),
-                count.to_string().bold(),
-                pct(*count, total_votes)
-            );
-        }
-    }
+    // ── Vote stats ───────────────────────────────────────────────────
 
-    let avg_votes: Option<f64> = sqlx::query_scalar(
+    let vote_totals = sqlx::query_as::<_, VoteTotals>(
         r#"
-        WITH vote_counts AS (
+        WITH scoped_votes AS (
+            SELECT v."userId"
+            FROM vote v
+            JOIN poll p ON p.id = v."pollId"
+            WHERE v."createdAt" >= $1 AND v."createdAt" < $5
+              AND ($2::uuid IS NULL OR p."channelId" = $2)
+              AND ($3::uuid IS NULL OR p.id = $3)
+              AND ($4::uuid IS NULL OR p."channelId" IN (SELECT id FROM channel WHERE "serverId" = $4))
+        ),
+        vote_counts AS (
             SELECT p.id, COUNT(v.id)::bigint AS votes
             FROM poll p
             LEFT JOIN vote v ON v."pollId" = p.id
-            WHERE p."createdAt" >= NOW() - ($1::int * INTERVAL '1 day')
+            WHERE p."createdAt" >= $1 AND p."createdAt" < $5
               AND ($2::uuid IS NULL OR p."channelId" = $2)
               AND ($3::uuid IS NULL OR p.id = $3)
+              AND ($4::uuid IS NULL OR p."channelId" IN (SELECT id FROM channel WHERE "serverId" = $4))
             GROUP BY p.id
         )
-        SELECT AVG(votes)::float FROM vote_counts
+        SELECT (SELECT COUNT(*) FROM scoped_votes)::bigint AS total_votes,
+               (SELECT COUNT(DISTINCT "userId") FROM scoped_votes)::bigint AS unique_voters,
+               (SELECT AVG(votes)::float FROM vote_counts) AS avg_votes_per_poll
         "#,
     )
-    .bind(days)
+    .bind(since)
     .bind(channel_id)
     .bind(poll_id)
-    .fetch_optional(pool)
-    .await?;
+    .bind(server_id)
+    .bind(until)
+    .fetch_one(pool);
+
+    let (poll_types, stages, vote_totals) = tokio::try_join!(
+        timer.time(format!("poll types ({label})"), poll_types),
+        timer.time(format!("stages ({label})"), stages),
+        timer.time(format!("vote totals ({label})"), vote_totals),
+    )?;
+
+    Ok(WindowMetrics {
+        poll_types,
+        stages,
+        total_votes: vote_totals.total_votes,
+        unique_voters: vote_totals.unique_voters,
+        avg_votes_per_poll: vote_totals.avg_votes_per_poll,
+    })
+}
+
+#[derive(FromRow)]
+struct VoteTotals {
+    total_votes: i64,
+    unique_voters: i64,
+    avg_votes_per_poll: Option<f64>,
+}
 
-    if let Some(avg) = avg_votes {
+fn compare_windows(
+    previous_scope: &PollScope,
+    current: &WindowMetrics,
+    previous: &WindowMetrics,
+) -> Comparison {
+    let mut metrics = vec![
+        MetricDelta::new(
+            "totalPolls",
+            "polls created",
+            Unit::Count,
+            current.total_polls() as f64,
+            previous.total_polls() as f64,
+        ),
+        MetricDelta::new(
+            "totalProposals",
+            "proposals created",
+            Unit::Count,
+            current.total_proposals() as f64,
+            previous.total_proposals() as f64,
+        ),
+        MetricDelta::new(
+            "totalVotes",
+            "votes",
+            Unit::Count,
+            current.total_votes as f64,
+            previous.total_votes as f64,
+        ),
+        MetricDelta::new(
+            "uniqueVoters",
+            "unique voters",
+            Unit::Count,
+            current.unique_voters as f64,
+            previous.unique_voters as f64,
+        ),
+        MetricDelta::new(
+            "avgVotesPerPoll",
+            "avg votes per poll",
+            Unit::Ratio,
+            current.avg_votes_per_poll.unwrap_or(0.0),
+            previous.avg_votes_per_poll.unwrap_or(0.0),
+        ),
+    ];
+
+    // Every stage seen in either window, in the order the stage enum lists them
+    let stage_share = |window: &WindowMetrics, stage: &str| {
+        let count = window
+            .stages
+            .iter()
+            .find(|r| r.stage == stage)
+            .map_or(0, |r| r.count);
+        pct(count, window.total_proposals())
+    };
+    for stage in ["voting", "ratified", "revision", "closed"] {
+        let (current_share, previous_share) =
+            (stage_share(current, stage), stage_share(previous, stage));
+        if current_share == 0.0 && previous_share == 0.0 {
+            continue;
+        }
+        metrics.push(MetricDelta::new(
+            format!("stageShare.{}", stage),
+            format!("{} share", stage),
+            Unit::Percent,
+            current_share,
+            previous_share,
+        ));
+    }
+
+    Comparison {
+        previous_since: previous_scope.since,
+        previous_until: previous_scope.until,
+        metrics,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Report
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PollStatsReport {
+    window_days: i64,
+    #[serde(flatten)]
+    window: TimeWindow,
+    server_id: Option<Uuid>,
+    channel_id: Option<Uuid>,
+    poll_id: Option<Uuid>,
+    #[serde(flatten)]
+    names: ScopeNames,
+    total_polls: i64,
+    poll_types: Vec<PollTypeCount>,
+    total_proposals: i64,
+    stages: Vec<StageCount>,
+    lifecycle: LifecycleStats,
+    total_votes: i64,
+    unique_voters: i64,
+    vote_mix: Vec<VoteTypeCount>,
+    avg_votes_per_poll: Option<f64>,
+    /// Votes on each poll created in the window, `None` when there are none
+    votes_per_poll: Option<Distribution>,
+    turnout: TurnoutStats,
+    bucket: Bucket,
+    /// Polls created per bucket, zero-filled across the window
+    trend: Vec<TrendBucket>,
+    /// Votes cast per bucket by `voteType`, zero-filled across the window
+    vote_trend: Vec<VoteTrendBucket>,
+    most_active_polls: Vec<PollParticipation>,
+    top_channels: Vec<ChannelCount>,
+    /// Present with `--compare`
+    comparison: Option<Comparison>,
+}
+
+impl Report for PollStatsReport {
+    const KIND: &'static str = "poll-stats";
+    const SCHEMA_VERSION: u32 = 1;
+
+    fn render_text(&self) {
+        let scope = self
+            .names
+            .describe()
+            .map(|scope| format!(", {}", scope))
+            .unwrap_or_default();
         println!(
-            "{} avg votes per poll",
-            format!("{:.1}", avg).green().bold()
+            "\n{} {}",
+            "Poll Stats".bold().underline(),
+            format!("({}{})", self.window.describe(), scope).dimmed()
         );
-    }
 
-    // ── Recent creation trend ────────────────────────────────────────
+        // ── Poll type breakdown ──────────────────────────────────────
 
-    let daily_counts: Vec<DailyCount> = sqlx::query_as(
-        r#"
-        SELECT DATE_TRUNC('day', "createdAt")::date AS day,
-               COUNT(*)::bigint AS count
-        FROM poll
-        WHERE "createdAt" >= NOW() - ($1::int * INTERVAL '1 day')
-          AND ($2::uuid IS NULL OR "channelId" = $2)
-          AND ($3::uuid IS NULL OR id = $3)
-        GROUP BY day
-        ORDER BY day DESC
-        LIMIT 14
-        "#,
-    )
-    .bind(days)
-    .bind(channel_id)
-    .bind(poll_id)
-    .fetch_all(pool)
-    .await?;
+        println!(
+            "{} {} polls created",
+            "•".cyan(),
+            format_number(self.total_polls).bold()
+        );
 
-    if !daily_counts.is_empty() {
-        println!("\n{}", "Recent Creation Trend".bold());
-        for DailyCount { day, count } in daily_counts.iter().rev() {
-            let bar = "▇".repeat((*count).clamp(0, 25) as usize);
+        for PollTypeCount { poll_type, count } in &self.poll_types {
             println!(
-                "{} {:>4} {}",
-                day.format("%b %d").to_string().dimmed(),
-                count,
-                bar.blue()
+                "  {} {:<10} {:>6} ({:>5.1}%)",
+                "→".dimmed(),
+                poll_type.bold(),
+                count.to_string().bold(),
+                pct(*count, self.total_polls)
             );
         }
-    }
 
-    // ── Most active polls ────────────────────────────────────────────
+        // ── Proposal stage distribution ──────────────────────────────
 
-    if poll_id.is_none() && top_polls > 0 {
-        let poll_participation: Vec<PollParticipation> = sqlx::query_as(
-            r#"
-            SELECT p.id AS poll_id,
-                   p."channelId" AS channel_id,
-                   COUNT(v.id)::bigint AS votes,
-                   MAX(v."createdAt") AS last_vote_at
-            FROM poll p
-            LEFT JOIN vote v ON v."pollId" = p.id
-            WHERE p."createdAt" >= NOW() - ($1::int * INTERVAL '1 day')
-              AND ($2::uuid IS NULL OR p."channelId" = $2)
-            GROUP BY p.id
-            HAVING COUNT(v.id) > 0
-            ORDER BY votes DESC, last_vote_at DESC NULLS LAST
-            LIMIT $3::int
-            "#,
-        )
-        .bind(days)
-        .bind(channel_id)
-        .bind(top_polls as i32)
-        .fetch_all(pool)
-        .await?;
+        if !self.stages.is_empty() {
+            println!("\n{}", "Proposal Stages".bold());
+            for StageCount { stage, count } in &self.stages {
+                println!(
+                    "  {} {} {:>6} ({:>5.1}%)",
+                    "→".dimmed(),
+                    color_stage(&format!("{:<10}", stage)),
+                    count.to_string().bold(),
+                    pct(*count, self.total_proposals)
+                );
+            }
+        }
+
+        print_lifecycle(&self.lifecycle);
+
+        // ── Vote stats ───────────────────────────────────────────────
+
+        println!(
+            "\n{}\n{} {} votes captured\n{} {} unique voters",
+            "Votes".bold(),
+            "•".cyan(),
+            format_number(self.total_votes).bold(),
+            "•".cyan(),
+            format_number(self.unique_voters).bold()
+        );
+
+        for VoteTypeCount { vote_type, count } in &self.vote_mix {
+            let label = vote_type.as_deref().unwrap_or("unknown");
+            println!(
+                "  {} {} {:>6} ({:>5.1}%)",
+                "→".dimmed(),
+                color_vote(&format!("{:<9}", label)),
+                count.to_string().bold(),
+                pct(*count, self.total_votes)
+            );
+        }
 
-        if !poll_participation.is_empty() {
+        // ── Votes per poll ───────────────────────────────────────────
+
+        if let Some(distribution) = &self.votes_per_poll {
+            println!(
+                "\n{} {}",
+                "Votes per Poll".bold(),
+                format!(
+                    "(avg {:.1}, median {:.1}, p75 {:.1}, p90 {:.1}, max {})",
+                    distribution.mean,
+                    distribution.median,
+                    distribution.p75,
+                    distribution.p90,
+                    distribution.max
+                )
+                .dimmed()
+            );
+            distribution.print_histogram(AnsiColors::Green);
+        }
+
+        print_turnout(&self.turnout);
+
+        // ── Creation trend ───────────────────────────────────────────
+
+        if !self.trend.is_empty() {
+            let counts: Vec<i64> = self.trend.iter().map(|bucket| bucket.count).collect();
+            println!(
+                "\n{} {} {}",
+                "Creation Trend".bold(),
+                format!("(by {})", self.bucket.as_str()).dimmed(),
+                sparkline(&counts).blue()
+            );
+            let rows: Vec<(String, i64)> = self
+                .trend
+                .iter()
+                .map(|bucket| (self.bucket.format(bucket.start), bucket.count))
+                .collect();
+            print_bars(&rows, AnsiColors::Blue);
+        }
+
+        // ── Vote trend ───────────────────────────────────────────────
+
+        if self.vote_trend.iter().any(|bucket| bucket.total() > 0) {
+            let totals: Vec<i64> = self.vote_trend.iter().map(VoteTrendBucket::total).collect();
+            println!(
+                "\n{} {} {}",
+                "Vote Trend".bold(),
+                format!("(by {}, split by voteType)", self.bucket.as_str()).dimmed(),
+                sparkline(&totals).green()
+            );
+            let rows: Vec<(String, Vec<i64>)> = self
+                .vote_trend
+                .iter()
+                .map(|bucket| {
+                    (
+                        self.bucket.format(bucket.start),
+                        vec![
+                            bucket.agree,
+                            bucket.disagree,
+                            bucket.abstain,
+                            bucket.block,
+                            bucket.other,
+                        ],
+                    )
+                })
+                .collect();
+            print_stacked_bars(
+                &[
+                    ("agree", AnsiColors::Green),
+                    ("disagree", AnsiColors::Red),
+                    ("abstain", AnsiColors::Cyan),
+                    ("block", AnsiColors::Magenta),
+                    ("unknown", AnsiColors::White),
+                ],
+                &rows,
+            );
+        }
+
+        // ── Most active polls ────────────────────────────────────────
+
+        if !self.most_active_polls.is_empty() {
             println!("\n{}", "Most Active Polls".bold());
             for PollParticipation {
                 poll_id,
-                channel_id,
+                channel_name,
+                server_name,
                 votes,
                 last_vote_at,
-            } in poll_participation
+                ..
+            } in &self.most_active_polls
             {
                 let freshness = last_vote_at
                     .map(|ts| {
@@ -269,87 +793,214 @@ pub async fn run_poll_stats(
                     })
                     .unwrap_or_else(|| "no votes yet".into());
                 println!(
-                    "{} poll {} (channel {}) — {} votes, {}",
+                    "{} poll {} in {} — {} votes, {}",
                     "•".cyan(),
                     poll_id,
-                    channel_id,
+                    format_channel(channel_name, server_name),
                     votes.to_string().bold(),
                     freshness.dimmed()
                 );
             }
         }
-    }
-
-    // ── Top channels ─────────────────────────────────────────────────
 
-    if top_channels > 0 {
-        let channel_counts: Vec<ChannelCount> = sqlx::query_as(
-            r#"
-            SELECT "channelId" AS channel_id, COUNT(*)::bigint AS count
-            FROM poll
-            WHERE "createdAt" >= NOW() - ($1::int * INTERVAL '1 day')
-              AND ($2::uuid IS NULL OR "channelId" = $2)
-            GROUP BY "channelId"
-            ORDER BY count DESC
-            LIMIT $3::int
-            "#,
-        )
-        .bind(days)
-        .bind(channel_id)
-        .bind(top_channels as i32)
-        .fetch_all(pool)
-        .await?;
+        // ── Top channels ─────────────────────────────────────────────
 
-        if !channel_counts.is_empty() {
+        if !self.top_channels.is_empty() {
             println!("\n{}", "Top Channels".bold());
-            for ChannelCount { channel_id, count } in channel_counts {
+            for ChannelCount {
+                channel_name,
+                server_name,
+                count,
+                ..
+            } in &self.top_channels
+            {
                 println!(
                     "{} {} ({} polls)",
                     "•".cyan(),
-                    channel_id,
+                    format_channel(channel_name, server_name).bold(),
                     count.to_string().bold()
                 );
             }
         }
+
+        if let Some(comparison) = &self.comparison {
+            print_comparison(comparison);
+        }
     }
 
-    Ok(())
+    fn records(&self) -> Vec<Record> {
+        let mut records = vec![
+            Record::new("summary")
+                .field("windowDays", self.window_days)
+                .field("since", self.window.since.to_rfc3339())
+                .field("until", self.window.until.to_rfc3339())
+                .field("bucket", self.bucket.as_str())
+                .field("timezone", self.window.timezone.name())
+                .field("serverId", self.server_id.map(|id| id.to_string()))
+                .field("serverName", self.names.server_name.clone())
+                .field("channelId", self.channel_id.map(|id| id.to_string()))
+                .field("channelName", self.names.channel_name.clone())
+                .field("pollId", self.poll_id.map(|id| id.to_string()))
+                .field("totalPolls", self.total_polls)
+                .field("totalProposals", self.total_proposals)
+                .field("totalVotes", self.total_votes)
+                .field("uniqueVoters", self.unique_voters)
+                .field("avgVotesPerPoll", self.avg_votes_per_poll),
+        ];
+
+        for PollTypeCount { poll_type, count } in &self.poll_types {
+            records.push(
+                Record::new("pollTypes")
+                    .field("pollType", poll_type.as_str())
+                    .field("count", *count),
+            );
+        }
+        for StageCount { stage, count } in &self.stages {
+            records.push(
+                Record::new("stages")
+                    .field("stage", stage.as_str())
+                    .field("count", *count),
+            );
+        }
+        records.extend(lifecycle_records(&self.lifecycle));
+        for VoteTypeCount { vote_type, count } in &self.vote_mix {
+            records.push(
+                Record::new("voteMix")
+                    .field("voteType", vote_type.clone())
+                    .field("count", *count),
+            );
+        }
+        if let Some(distribution) = &self.votes_per_poll {
+            records.push(
+                Record::new("votesPerPoll")
+                    .field("polls", distribution.count)
+                    .field("mean", distribution.mean)
+                    .field("min", distribution.min)
+                    .field("p25", distribution.p25)
+                    .field("median", distribution.median)
+                    .field("p75", distribution.p75)
+                    .field("p90", distribution.p90)
+                    .field("max", distribution.max),
+            );
+            for bin in &distribution.bins {
+                records.push(
+                    Record::new("votesPerPollHistogram")
+                        .field("lower", bin.lower)
+                        .field("upper", bin.upper)
+                        .field("count", bin.count),
+                );
+            }
+        }
+        records.extend(turnout_records(&self.turnout));
+        for TrendBucket { start, count } in &self.trend {
+            records.push(
+                Record::new("trend")
+                    .field("start", start.to_string())
+                    .field("count", *count),
+            );
+        }
+        for bucket in &self.vote_trend {
+            records.push(
+                Record::new("voteTrend")
+                    .field("start", bucket.start.to_string())
+                    .field("agree", bucket.agree)
+                    .field("disagree", bucket.disagree)
+                    .field("abstain", bucket.abstain)
+                    .field("block", bucket.block)
+                    .field("other", bucket.other),
+            );
+        }
+        for poll in &self.most_active_polls {
+            records.push(
+                Record::new("mostActivePolls")
+                    .field("pollId", poll.poll_id.to_string())
+                    .field("channelId", poll.channel_id.to_string())
+                    .field("channelName", poll.channel_name.as_str())
+                    .field("serverName", poll.server_name.as_str())
+                    .field("votes", poll.votes)
+                    .field("lastVoteAt", naive_utc_value(poll.last_vote_at)),
+            );
+        }
+        for channel in &self.top_channels {
+            records.push(
+                Record::new("topChannels")
+                    .field("channelId", channel.channel_id.to_string())
+                    .field("channelName", channel.channel_name.as_str())
+                    .field("serverName", channel.server_name.as_str())
+                    .field("count", channel.count),
+            );
+        }
+        if let Some(comparison) = &self.comparison {
+            records.extend(comparison_records(comparison));
+        }
+
+        records
+    }
 }
 
-#[derive(Debug, FromRow)]
+#[derive(Debug, FromRow, Serialize)]
+#[serde(rename_all = "camelCase")]
 struct PollTypeCount {
     poll_type: String,
     count: i64,
 }
 
-#[derive(Debug, FromRow)]
+#[derive(Debug, FromRow, Serialize)]
+#[serde(rename_all = "camelCase")]
 struct StageCount {
     stage: String,
     count: i64,
 }
 
-#[derive(Debug, FromRow)]
+#[derive(Debug, FromRow, Serialize)]
+#[serde(rename_all = "camelCase")]
 struct VoteTypeCount {
     vote_type: Option<String>,
     count: i64,
 }
 
-#[derive(Debug, FromRow)]
-struct DailyCount {
-    day: NaiveDate,
+#[derive(Debug, FromRow, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TrendBucket {
+    start: NaiveDate,
     count: i64,
 }
 
-#[derive(Debug, FromRow)]
+#[derive(Debug, Default, FromRow, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VoteTrendBucket {
+    start: NaiveDate,
+    agree: i64,
+    disagree: i64,
+    abstain: i64,
+    block: i64,
+    /// Votes without a `voteType`
+    other: i64,
+}
+
+impl VoteTrendBucket {
+    fn total(&self) -> i64 {
+        self.agree + self.disagree + self.abstain + self.block + self.other
+    }
+}
+
+#[derive(Debug, FromRow, Serialize)]
+#[serde(rename_all = "camelCase")]
 struct PollParticipation {
     poll_id: Uuid,
     channel_id: Uuid,
+    channel_name: String,
+    server_name: String,
     votes: i64,
+    #[serde(serialize_with = "serialize_naive_utc")]
     last_vote_at: Option<NaiveDateTime>,
 }
 
-#[derive(Debug, FromRow)]
+#[derive(Debug, FromRow, Serialize)]
+#[serde(rename_all = "camelCase")]
 struct ChannelCount {
     channel_id: Uuid,
+    channel_name: String,
+    server_name: String,
     count: i64,
 }
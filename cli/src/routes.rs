@@ -104,7 +104,7 @@ pub fn run_routes(path_filter: Option<String>, tree: bool) -> Result<()> {
 // Locate src/ directory
 // ---------------------------------------------------------------------------
 
-fn find_src_dir() -> Result<PathBuf> {
+pub fn find_src_dir() -> Result<PathBuf> {
     let cwd = std::env::current_dir()?;
 
     // Running from repo root: look for src/main.ts as a marker
@@ -349,10 +349,11 @@ fn resolve_path(raw: &str, constants: &HashMap<String, String>) -> String {
     let resolved = resolved.to_string();
 
     // If the entire path is a single identifier (variable reference), resolve it
-    if !resolved.starts_with('/') && !resolved.is_empty() {
-        if let Some(value) = constants.get(&resolved) {
-            return value.clone();
-        }
+    if !resolved.starts_with('/')
+        && !resolved.is_empty()
+        && let Some(value) = constants.get(&resolved)
+    {
+        return value.clone();
     }
 
     resolved
@@ -485,8 +486,7 @@ fn print_section_header(breadcrumb: &[String], color: bool) {
 fn mount_path_resource(mount_path: &str) -> String {
     mount_path
         .split('/')
-        .filter(|s| !s.is_empty() && !s.starts_with(':'))
-        .last()
+        .rfind(|s| !s.is_empty() && !s.starts_with(':'))
         .unwrap_or("root")
         .to_string()
 }
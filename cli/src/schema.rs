@@ -1,31 +1,71 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
 use std::io::IsTerminal;
 
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
 use owo_colors::OwoColorize;
+use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, PgPool};
 
+use crate::cli::SchemaArgs;
+use crate::drift::diff_schema;
+use crate::entities::check_entities;
+use crate::output::{OutputFormat, Record, Report, from_json, render, to_json};
 use crate::utils::{print_header, print_section_label};
 
-pub async fn run_schema(pool: &PgPool) -> Result<()> {
-    let color = std::io::stdout().is_terminal();
+pub async fn run_schema(pool: &PgPool, args: &SchemaArgs, format: OutputFormat) -> Result<()> {
+    let schema = collect_schema(pool).await?;
+
+    if let Some(path) = &args.snapshot {
+        fs::write(path, to_json(&schema)? + "\n")
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        eprintln!(
+            "{}",
+            format!("Wrote schema snapshot to {}", path.display()).dimmed()
+        );
+        return Ok(());
+    }
 
-    if color {
-        println!("\n{}", "Database Schema".bold().underline());
-    } else {
-        println!("\nDatabase Schema");
+    if let Some(path) = &args.diff {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let (snapshot, taken_at) = from_json::<Schema>(&contents)
+            .with_context(|| format!("{} is not a schema snapshot", path.display()))?;
+        let drift = diff_schema(&snapshot, &schema, path, taken_at);
+        render(&drift, format)?;
+        if !drift.changes.is_empty() {
+            bail!(
+                "live schema differs from {} in {} place(s)",
+                path.display(),
+                drift.changes.len()
+            );
+        }
+        return Ok(());
     }
 
-    print_enums(pool, color).await?;
-    print_tables(pool, color).await?;
+    if args.check_entities {
+        let check = check_entities(&schema)?;
+        render(&check, format)?;
+        if !check.mismatches.is_empty() {
+            bail!(
+                "entities differ from the database in {} place(s)",
+                check.mismatches.len()
+            );
+        }
+        return Ok(());
+    }
 
-    Ok(())
+    render(&schema, format)
 }
 
-async fn print_enums(pool: &PgPool, color: bool) -> Result<()> {
-    let enums: Vec<EnumInfo> = sqlx::query_as(
+/// Reads every enum, table, column, index and constraint in the `public`
+/// schema.
+pub async fn collect_schema(pool: &PgPool) -> Result<Schema> {
+    let enums_query = sqlx::query_as::<_, EnumInfo>(
         r#"
         SELECT t.typname AS name,
-               ARRAY_AGG(e.enumlabel ORDER BY e.enumsortorder) AS values
+               ARRAY_AGG(e.enumlabel::text ORDER BY e.enumsortorder) AS values
         FROM pg_type t
         JOIN pg_enum e ON t.oid = e.enumtypid
         JOIN pg_namespace n ON t.typnamespace = n.oid
@@ -34,30 +74,9 @@ async fn print_enums(pool: &PgPool, color: bool) -> Result<()> {
         ORDER BY t.typname
         "#,
     )
-    .fetch_all(pool)
-    .await?;
+    .fetch_all(pool);
 
-    if !enums.is_empty() {
-        print_header("Enums", color);
-        for EnumInfo { name, values } in enums {
-            if color {
-                println!(
-                    "  {} {} = {{ {} }}",
-                    "•".cyan(),
-                    name.yellow(),
-                    values.join(", ")
-                );
-            } else {
-                println!("  - {} = {{ {} }}", name, values.join(", "));
-            }
-        }
-    }
-
-    Ok(())
-}
-
-async fn print_tables(pool: &PgPool, color: bool) -> Result<()> {
-    let tables: Vec<TableInfo> = sqlx::query_as(
+    let tables_query = sqlx::query_as::<_, TableRow>(
         r#"
         SELECT table_name AS name
         FROM information_schema.tables
@@ -66,28 +85,12 @@ async fn print_tables(pool: &PgPool, color: bool) -> Result<()> {
         ORDER BY table_name
         "#,
     )
-    .fetch_all(pool)
-    .await?;
-
-    for TableInfo { name } in tables {
-        if color {
-            println!("\n{} {}", "Table:".bold(), name.green().bold());
-        } else {
-            println!("\nTable: {}", name);
-        }
-
-        print_columns(pool, &name, color).await?;
-        print_indexes(pool, &name, color).await?;
-        print_constraints(pool, &name, color).await?;
-    }
-
-    Ok(())
-}
+    .fetch_all(pool);
 
-async fn print_columns(pool: &PgPool, table_name: &str, color: bool) -> Result<()> {
-    let columns: Vec<ColumnInfo> = sqlx::query_as(
+    let columns_query = sqlx::query_as::<_, ColumnRow>(
         r#"
-        SELECT column_name AS name,
+        SELECT table_name,
+               column_name AS name,
                CASE
                    WHEN data_type = 'ARRAY' THEN
                        COALESCE(udt_name, data_type)
@@ -99,18 +102,387 @@ async fn print_columns(pool: &PgPool, table_name: &str, color: bool) -> Result<(
                        data_type || '(' || numeric_precision || ',' || COALESCE(numeric_scale, 0) || ')'
                    ELSE data_type
                END AS data_type,
-               is_nullable AS nullable,
+               is_nullable = 'YES' AS nullable,
                column_default AS default_value
         FROM information_schema.columns
         WHERE table_schema = 'public'
-          AND table_name = $1
-        ORDER BY ordinal_position
+        ORDER BY table_name, ordinal_position
+        "#,
+    )
+    .fetch_all(pool);
+
+    let indexes_query = sqlx::query_as::<_, IndexRow>(
+        r#"
+        SELECT tablename AS table_name,
+               indexname AS name,
+               indexdef AS definition
+        FROM pg_indexes
+        WHERE schemaname = 'public'
+        ORDER BY tablename, indexname
         "#,
     )
-    .bind(table_name)
-    .fetch_all(pool)
-    .await?;
+    .fetch_all(pool);
+
+    // Primary keys and unique constraints are listed with their indexes
+    let constraints_query = sqlx::query_as::<_, ConstraintRow>(
+        r#"
+        SELECT
+            t.relname::text AS table_name,
+            c.conname AS name,
+            CASE c.contype
+                WHEN 'p' THEN 'PRIMARY KEY'
+                WHEN 'f' THEN 'FOREIGN KEY'
+                WHEN 'u' THEN 'UNIQUE'
+                WHEN 'c' THEN 'CHECK'
+                WHEN 'x' THEN 'EXCLUDE'
+            END AS constraint_type,
+            pg_get_constraintdef(c.oid) AS definition,
+            ARRAY(
+                SELECT a.attname::text
+                FROM unnest(c.conkey) WITH ORDINALITY k(attnum, ord)
+                JOIN pg_attribute a ON a.attrelid = c.conrelid AND a.attnum = k.attnum
+                ORDER BY k.ord
+            ) AS columns,
+            ft.relname::text AS referenced_table,
+            ARRAY(
+                SELECT a.attname::text
+                FROM unnest(c.confkey) WITH ORDINALITY k(attnum, ord)
+                JOIN pg_attribute a ON a.attrelid = c.confrelid AND a.attnum = k.attnum
+                ORDER BY k.ord
+            ) AS referenced_columns
+        FROM pg_constraint c
+        JOIN pg_class t ON c.conrelid = t.oid
+        JOIN pg_namespace n ON t.relnamespace = n.oid
+        LEFT JOIN pg_class ft ON ft.oid = c.confrelid
+        WHERE n.nspname = 'public'
+          AND c.contype IN ('f', 'c', 'x')
+        ORDER BY t.relname, c.contype, c.conname
+        "#,
+    )
+    .fetch_all(pool);
+
+    let (enums, tables, columns, indexes, constraints) = tokio::try_join!(
+        enums_query,
+        tables_query,
+        columns_query,
+        indexes_query,
+        constraints_query,
+    )?;
+
+    let mut tables: Vec<TableInfo> = tables
+        .into_iter()
+        .map(|table| TableInfo {
+            name: table.name,
+            columns: Vec::new(),
+            indexes: Vec::new(),
+            constraints: Vec::new(),
+        })
+        .collect();
+    let index: HashMap<String, usize> = tables
+        .iter()
+        .enumerate()
+        .map(|(i, table)| (table.name.clone(), i))
+        .collect();
+
+    // Rows for views and other relations that aren't base tables are skipped
+    for column in columns {
+        if let Some(&i) = index.get(&column.table_name) {
+            tables[i].columns.push(column.info);
+        }
+    }
+    for row in indexes {
+        if let Some(&i) = index.get(&row.table_name) {
+            tables[i].indexes.push(row.info);
+        }
+    }
+    for row in constraints {
+        if let Some(&i) = index.get(&row.table_name) {
+            tables[i].constraints.push(row.info);
+        }
+    }
+
+    Ok(Schema { enums, tables })
+}
+
+// ---------------------------------------------------------------------------
+// Model
+// ---------------------------------------------------------------------------
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Schema {
+    pub enums: Vec<EnumInfo>,
+    pub tables: Vec<TableInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, FromRow, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnumInfo {
+    pub name: String,
+    pub values: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableInfo {
+    pub name: String,
+    pub columns: Vec<ColumnInfo>,
+    pub indexes: Vec<IndexInfo>,
+    /// Foreign key, check and exclusion constraints
+    pub constraints: Vec<ConstraintInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, FromRow, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColumnInfo {
+    pub name: String,
+    pub data_type: String,
+    pub nullable: bool,
+    pub default_value: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, FromRow, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexInfo {
+    pub name: String,
+    pub definition: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, FromRow, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConstraintInfo {
+    pub name: String,
+    pub constraint_type: String,
+    pub definition: String,
+    pub columns: Vec<String>,
+    /// The table a foreign key points at
+    pub referenced_table: Option<String>,
+    pub referenced_columns: Vec<String>,
+}
+
+impl IndexInfo {
+    /// `PK`, `UQ` or `IX`
+    pub fn marker(&self) -> &'static str {
+        if self.name.ends_with("_pkey") || self.name.contains("PK_") {
+            "PK"
+        } else if self.definition.to_lowercase().contains("unique") {
+            "UQ"
+        } else {
+            "IX"
+        }
+    }
+
+    pub fn columns(&self) -> String {
+        extract_index_columns(&self.definition)
+    }
+}
+
+impl ConstraintInfo {
+    fn marker(&self) -> &str {
+        match self.constraint_type.as_str() {
+            "FOREIGN KEY" => "FK",
+            "CHECK" => "CK",
+            "EXCLUDE" => "EX",
+            other => other,
+        }
+    }
+}
+
+impl TableInfo {
+    fn column(&self, name: &str) -> Option<&ColumnInfo> {
+        self.columns.iter().find(|column| column.name == name)
+    }
+
+    fn is_primary_key(&self, column: &str) -> bool {
+        self.indexes
+            .iter()
+            .any(|index| index.marker() == "PK" && index_column_names(index).contains(&column))
+    }
+
+    fn is_foreign_key(&self, column: &str) -> bool {
+        self.foreign_keys()
+            .any(|fk| fk.columns.iter().any(|name| name == column))
+    }
+
+    /// Whether a unique index covers exactly `columns`, which makes a foreign
+    /// key over them one-to-one.
+    fn is_unique(&self, columns: &[String]) -> bool {
+        self.indexes.iter().any(|index| {
+            index.marker() != "IX" && {
+                let mut names = index_column_names(index);
+                names.sort();
+                let mut expected: Vec<&str> = columns.iter().map(String::as_str).collect();
+                expected.sort();
+                names == expected
+            }
+        })
+    }
+
+    fn foreign_keys(&self) -> impl Iterator<Item = &ConstraintInfo> {
+        self.constraints
+            .iter()
+            .filter(|constraint| constraint.referenced_table.is_some())
+    }
+}
+
+fn index_column_names(index: &IndexInfo) -> Vec<&str> {
+    let definition = index.definition.as_str();
+    let (Some(start), Some(end)) = (definition.rfind('('), definition.rfind(')')) else {
+        return Vec::new();
+    };
+    definition[start + 1..end]
+        .split(',')
+        .map(|name| name.trim().trim_matches('"'))
+        .collect()
+}
+
+fn extract_index_columns(definition: &str) -> String {
+    if let Some(start) = definition.rfind('(')
+        && let Some(end) = definition.rfind(')')
+    {
+        return definition[start + 1..end].to_string();
+    }
+    "?".to_string()
+}
+
+// ---------------------------------------------------------------------------
+// Rows
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, FromRow)]
+struct TableRow {
+    name: String,
+}
+
+#[derive(Debug, FromRow)]
+struct ColumnRow {
+    table_name: String,
+    #[sqlx(flatten)]
+    info: ColumnInfo,
+}
+
+#[derive(Debug, FromRow)]
+struct IndexRow {
+    table_name: String,
+    #[sqlx(flatten)]
+    info: IndexInfo,
+}
+
+#[derive(Debug, FromRow)]
+struct ConstraintRow {
+    table_name: String,
+    #[sqlx(flatten)]
+    info: ConstraintInfo,
+}
+
+// ---------------------------------------------------------------------------
+// Report
+// ---------------------------------------------------------------------------
+
+impl Report for Schema {
+    const KIND: &'static str = "schema";
+    const SCHEMA_VERSION: u32 = 1;
+
+    fn render_text(&self) {
+        let color = std::io::stdout().is_terminal();
+
+        if color {
+            println!("\n{}", "Database Schema".bold().underline());
+        } else {
+            println!("\nDatabase Schema");
+        }
+
+        print_enums(&self.enums, color);
+        for table in &self.tables {
+            if color {
+                println!("\n{} {}", "Table:".bold(), table.name.green().bold());
+            } else {
+                println!("\nTable: {}", table.name);
+            }
+
+            print_columns(&table.columns, color);
+            print_indexes(&table.indexes, color);
+            print_constraints(&table.constraints, color);
+        }
+    }
+
+    fn records(&self) -> Vec<Record> {
+        let mut records = Vec::new();
+        for EnumInfo { name, values } in &self.enums {
+            records.push(
+                Record::new("enums")
+                    .field("name", name.as_str())
+                    .field("values", values.join(";")),
+            );
+        }
+        for table in &self.tables {
+            for column in &table.columns {
+                records.push(
+                    Record::new("columns")
+                        .field("table", table.name.as_str())
+                        .field("name", column.name.as_str())
+                        .field("dataType", column.data_type.as_str())
+                        .field("nullable", column.nullable)
+                        .field("default", column.default_value.clone()),
+                );
+            }
+            for index in &table.indexes {
+                records.push(
+                    Record::new("indexes")
+                        .field("table", table.name.as_str())
+                        .field("name", index.name.as_str())
+                        .field("kind", index.marker())
+                        .field("columns", index.columns())
+                        .field("definition", index.definition.as_str()),
+                );
+            }
+            for constraint in &table.constraints {
+                records.push(
+                    Record::new("constraints")
+                        .field("table", table.name.as_str())
+                        .field("name", constraint.name.as_str())
+                        .field("constraintType", constraint.constraint_type.as_str())
+                        .field("definition", constraint.definition.as_str())
+                        .field("referencedTable", constraint.referenced_table.clone()),
+                );
+            }
+        }
+        records
+    }
+
+    fn render_document(&self, format: OutputFormat) -> Option<String> {
+        match format {
+            OutputFormat::Markdown => Some(self.to_markdown()),
+            OutputFormat::Mermaid => Some(self.to_mermaid()),
+            OutputFormat::Dot => Some(self.to_dot()),
+            _ => None,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Text output
+// ---------------------------------------------------------------------------
+
+fn print_enums(enums: &[EnumInfo], color: bool) {
+    if !enums.is_empty() {
+        print_header("Enums", color);
+        for EnumInfo { name, values } in enums {
+            if color {
+                println!(
+                    "  {} {} = {{ {} }}",
+                    "•".cyan(),
+                    name.yellow(),
+                    values.join(", ")
+                );
+            } else {
+                println!("  - {} = {{ {} }}", name, values.join(", "));
+            }
+        }
+    }
+}
+
+fn print_columns(columns: &[ColumnInfo], color: bool) {
     print_section_label("Columns:", color);
 
     for ColumnInfo {
@@ -120,8 +492,9 @@ async fn print_columns(pool: &PgPool, table_name: &str, color: bool) -> Result<(
         default_value,
     } in columns
     {
-        let null_marker = if nullable == "YES" { "?" } else { "" };
+        let null_marker = if *nullable { "?" } else { "" };
         let default_str = default_value
+            .as_ref()
             .map(|d| format!(" = {}", d))
             .unwrap_or_default();
 
@@ -141,40 +514,15 @@ async fn print_columns(pool: &PgPool, table_name: &str, color: bool) -> Result<(
             );
         }
     }
-
-    Ok(())
 }
 
-async fn print_indexes(pool: &PgPool, table_name: &str, color: bool) -> Result<()> {
-    let indexes: Vec<IndexInfo> = sqlx::query_as(
-        r#"
-        SELECT indexname AS name,
-               indexdef AS definition
-        FROM pg_indexes
-        WHERE schemaname = 'public'
-          AND tablename = $1
-        ORDER BY indexname
-        "#,
-    )
-    .bind(table_name)
-    .fetch_all(pool)
-    .await?;
-
+fn print_indexes(indexes: &[IndexInfo], color: bool) {
     if !indexes.is_empty() {
         print_section_label("Indexes:", color);
 
-        for IndexInfo { name, definition } in indexes {
-            let is_unique = definition.to_lowercase().contains("unique");
-            let is_primary = name.ends_with("_pkey") || name.contains("PK_");
-            let marker = if is_primary {
-                "PK"
-            } else if is_unique {
-                "UQ"
-            } else {
-                "IX"
-            };
-
-            let cols = extract_index_columns(&definition);
+        for index in indexes {
+            let marker = index.marker();
+            let cols = index.columns();
 
             if color {
                 let colored_marker = match marker {
@@ -186,59 +534,22 @@ async fn print_indexes(pool: &PgPool, table_name: &str, color: bool) -> Result<(
                     "    {} [{}] {} ({})",
                     "→".dimmed(),
                     colored_marker,
-                    name,
+                    index.name,
                     cols
                 );
             } else {
-                println!("    - [{}] {} ({})", marker, name, cols);
+                println!("    - [{}] {} ({})", marker, index.name, cols);
             }
         }
     }
-
-    Ok(())
 }
 
-async fn print_constraints(pool: &PgPool, table_name: &str, color: bool) -> Result<()> {
-    let constraints: Vec<ConstraintInfo> = sqlx::query_as(
-        r#"
-        SELECT
-            c.conname AS name,
-            CASE c.contype
-                WHEN 'p' THEN 'PRIMARY KEY'
-                WHEN 'f' THEN 'FOREIGN KEY'
-                WHEN 'u' THEN 'UNIQUE'
-                WHEN 'c' THEN 'CHECK'
-                WHEN 'x' THEN 'EXCLUDE'
-            END AS constraint_type,
-            pg_get_constraintdef(c.oid) AS definition
-        FROM pg_constraint c
-        JOIN pg_class t ON c.conrelid = t.oid
-        JOIN pg_namespace n ON t.relnamespace = n.oid
-        WHERE n.nspname = 'public'
-          AND t.relname = $1
-          AND c.contype IN ('f', 'c', 'x')
-        ORDER BY c.contype, c.conname
-        "#,
-    )
-    .bind(table_name)
-    .fetch_all(pool)
-    .await?;
-
+fn print_constraints(constraints: &[ConstraintInfo], color: bool) {
     if !constraints.is_empty() {
         print_section_label("Constraints:", color);
 
-        for ConstraintInfo {
-            name,
-            constraint_type,
-            definition,
-        } in constraints
-        {
-            let marker = match constraint_type.as_str() {
-                "FOREIGN KEY" => "FK",
-                "CHECK" => "CK",
-                "EXCLUDE" => "EX",
-                _ => &constraint_type,
-            };
+        for constraint in constraints {
+            let marker = constraint.marker();
 
             if color {
                 let colored_marker = match marker {
@@ -251,55 +562,308 @@ async fn print_constraints(pool: &PgPool, table_name: &str, color: bool) -> Resu
                     "    {} [{}] {} {}",
                     "→".dimmed(),
                     colored_marker,
-                    name,
-                    definition.dimmed()
+                    constraint.name,
+                    constraint.definition.dimmed()
                 );
             } else {
-                println!("    - [{}] {} {}", marker, name, definition);
+                println!(
+                    "    - [{}] {} {}",
+                    marker, constraint.name, constraint.definition
+                );
             }
         }
     }
-
-    Ok(())
 }
 
-fn extract_index_columns(definition: &str) -> String {
-    if let Some(start) = definition.rfind('(') {
-        if let Some(end) = definition.rfind(')') {
-            return definition[start + 1..end].to_string();
+// ---------------------------------------------------------------------------
+// Documents
+// ---------------------------------------------------------------------------
+
+impl Schema {
+    /// A data dictionary with one section per table.
+    fn to_markdown(&self) -> String {
+        let mut md = String::from("# Database Schema\n");
+
+        if !self.enums.is_empty() {
+            md.push_str("\n## Enums\n\n| Enum | Values |\n| --- | --- |\n");
+            for EnumInfo { name, values } in &self.enums {
+                let values: Vec<String> = values
+                    .iter()
+                    .map(|value| format!("`{}`", markdown_cell(value)))
+                    .collect();
+                let _ = writeln!(md, "| `{}` | {} |", name, values.join(", "));
+            }
+        }
+
+        md.push_str("\n## Tables\n");
+        for table in &self.tables {
+            let _ = writeln!(md, "\n### `{}`\n", table.name);
+            md.push_str("| Column | Type | Nullable | Default | Key |\n");
+            md.push_str("| --- | --- | --- | --- | --- |\n");
+            for column in &table.columns {
+                let mut keys = Vec::new();
+                if table.is_primary_key(&column.name) {
+                    keys.push("PK".to_string());
+                }
+                for fk in table
+                    .foreign_keys()
+                    .filter(|fk| fk.columns.contains(&column.name))
+                {
+                    keys.push(format!(
+                        "FK → `{}.{}`",
+                        fk.referenced_table.as_deref().unwrap_or_default(),
+                        fk.referenced_columns.join(", ")
+                    ));
+                }
+                let _ = writeln!(
+                    md,
+                    "| `{}` | {} | {} | {} | {} |",
+                    column.name,
+                    markdown_cell(&column.data_type),
+                    if column.nullable { "yes" } else { "no" },
+                    column
+                        .default_value
+                        .as_deref()
+                        .map(|value| format!("`{}`", markdown_cell(value)))
+                        .unwrap_or_default(),
+                    keys.join(", ")
+                );
+            }
+
+            if !table.indexes.is_empty() {
+                md.push_str("\n**Indexes**\n\n");
+                for index in &table.indexes {
+                    let _ = writeln!(
+                        md,
+                        "- `{}` ({}): {}",
+                        index.name,
+                        index.marker(),
+                        index.columns()
+                    );
+                }
+            }
+            if !table.constraints.is_empty() {
+                md.push_str("\n**Constraints**\n\n");
+                for constraint in &table.constraints {
+                    let _ = writeln!(
+                        md,
+                        "- `{}` ({}): `{}`",
+                        constraint.name,
+                        constraint.marker(),
+                        constraint.definition
+                    );
+                }
+            }
         }
+        md
+    }
+
+    /// An `erDiagram` with every column and one relationship per foreign key.
+    fn to_mermaid(&self) -> String {
+        let mut mermaid = String::from("erDiagram\n");
+        for table in &self.tables {
+            let _ = writeln!(mermaid, "    {} {{", mermaid_name(&table.name));
+            for column in &table.columns {
+                let key = match (
+                    table.is_primary_key(&column.name),
+                    table.is_foreign_key(&column.name),
+                ) {
+                    (true, true) => " PK, FK",
+                    (true, false) => " PK",
+                    (false, true) => " FK",
+                    (false, false) => "",
+                };
+                let _ = writeln!(
+                    mermaid,
+                    "        {} {}{}",
+                    mermaid_type(&column.data_type),
+                    mermaid_name(&column.name),
+                    key
+                );
+            }
+            mermaid.push_str("    }\n");
+        }
+
+        for table in &self.tables {
+            for fk in table.foreign_keys() {
+                let Some(referenced) = &fk.referenced_table else {
+                    continue;
+                };
+                let (parent, child) = self.cardinality(table, fk);
+                let _ = writeln!(
+                    mermaid,
+                    "    {} {}--{} {} : \"{}\"",
+                    mermaid_name(referenced),
+                    parent,
+                    child,
+                    mermaid_name(&table.name),
+                    fk.columns.join(", ")
+                );
+            }
+        }
+        mermaid
+    }
+
+    /// Tables as HTML-like records with a port per column, so foreign key
+    /// edges connect the referencing and referenced columns.
+    fn to_dot(&self) -> String {
+        let mut dot = String::from(
+            "digraph schema {\n  rankdir=LR;\n  node [shape=plaintext, fontname=\"Helvetica\"];\n  edge [fontname=\"Helvetica\", fontsize=10];\n",
+        );
+        for table in &self.tables {
+            let _ = write!(
+                dot,
+                "  \"{}\" [label=<<table border=\"0\" cellborder=\"1\" cellspacing=\"0\">\
+                 <tr><td bgcolor=\"lightgrey\"><b>{}</b></td></tr>",
+                dot_string(&table.name),
+                html_escape(&table.name)
+            );
+            for column in &table.columns {
+                let name = if table.is_primary_key(&column.name) {
+                    format!("<u>{}</u>", html_escape(&column.name))
+                } else {
+                    html_escape(&column.name)
+                };
+                let _ = write!(
+                    dot,
+                    "<tr><td port=\"{}\" align=\"left\">{}: {}{}</td></tr>",
+                    html_escape(&column.name),
+                    name,
+                    html_escape(&column.data_type),
+                    if column.nullable { "?" } else { "" }
+                );
+            }
+            dot.push_str("</table>>];\n");
+        }
+
+        for table in &self.tables {
+            for fk in table.foreign_keys() {
+                let Some(referenced) = &fk.referenced_table else {
+                    continue;
+                };
+                let (Some(column), Some(referenced_column)) =
+                    (fk.columns.first(), fk.referenced_columns.first())
+                else {
+                    continue;
+                };
+                let (parent, child) = self.cardinality(table, fk);
+                let _ = writeln!(
+                    dot,
+                    "  \"{}\":\"{}\" -> \"{}\":\"{}\" [label=\"{}\", arrowhead={}, arrowtail={}, dir=both];",
+                    dot_string(&table.name),
+                    dot_string(column),
+                    dot_string(referenced),
+                    dot_string(referenced_column),
+                    dot_string(&fk.name),
+                    if parent == "||" { "tee" } else { "teeodot" },
+                    if child == "o{" { "crowodot" } else { "teeodot" }
+                );
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Mermaid crow's foot markers for the referenced (parent) and
+    /// referencing (child) side of a foreign key. A nullable key makes the
+    /// parent optional, and a unique key makes the relationship one-to-one.
+    fn cardinality(&self, table: &TableInfo, fk: &ConstraintInfo) -> (&'static str, &'static str) {
+        let nullable = fk
+            .columns
+            .iter()
+            .any(|name| table.column(name).is_some_and(|column| column.nullable));
+        let parent = if nullable { "|o" } else { "||" };
+        let child = if table.is_unique(&fk.columns) {
+            "o|"
+        } else {
+            "o{"
+        };
+        (parent, child)
     }
-    "?".to_string()
 }
 
-#[derive(Debug, FromRow)]
-struct EnumInfo {
-    name: String,
-    values: Vec<String>,
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
 }
 
-#[derive(Debug, FromRow)]
-struct TableInfo {
-    name: String,
+/// Mermaid entity and attribute names must be plain words.
+fn mermaid_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
 }
 
-#[derive(Debug, FromRow)]
-struct ColumnInfo {
-    name: String,
-    data_type: String,
-    nullable: String,
-    default_value: Option<String>,
+/// Mermaid attribute types can't contain spaces or commas.
+fn mermaid_type(data_type: &str) -> String {
+    data_type.replace([' ', ','], "_")
 }
 
-#[derive(Debug, FromRow)]
-struct IndexInfo {
-    name: String,
-    definition: String,
+/// Escapes a quoted DOT ID, port or label. DOT only treats `\"` as an escape
+/// in quoted strings, so a port written this way names the same column as
+/// the HTML-escaped `port` attribute in the node's label.
+fn dot_string(value: &str) -> String {
+    value.replace('"', "\\\"")
 }
 
-#[derive(Debug, FromRow)]
-struct ConstraintInfo {
-    name: String,
-    constraint_type: String,
-    definition: String,
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str) -> ColumnInfo {
+        ColumnInfo {
+            name: name.to_string(),
+            data_type: "uuid".to_string(),
+            nullable: false,
+            default_value: None,
+        }
+    }
+
+    #[test]
+    fn dot_escapes_ids_ports_and_labels_consistently() {
+        let schema = Schema {
+            enums: Vec::new(),
+            tables: vec![
+                TableInfo {
+                    name: r#"a"b"#.to_string(),
+                    columns: vec![column(r#"x&"y"#)],
+                    indexes: Vec::new(),
+                    constraints: vec![ConstraintInfo {
+                        name: r#"FK_"q""#.to_string(),
+                        constraint_type: "FOREIGN KEY".to_string(),
+                        definition: String::new(),
+                        columns: vec![r#"x&"y"#.to_string()],
+                        referenced_table: Some("c<d".to_string()),
+                        referenced_columns: vec!["id".to_string()],
+                    }],
+                },
+                TableInfo {
+                    name: "c<d".to_string(),
+                    columns: vec![column("id")],
+                    indexes: Vec::new(),
+                    constraints: Vec::new(),
+                },
+            ],
+        };
+
+        let dot = schema.to_dot();
+        assert!(dot.contains(r#"  "a\"b" [label=<<table"#));
+        assert!(dot.contains(r#"<b>a&quot;b</b>"#));
+        assert!(dot.contains(r#"<td port="x&amp;&quot;y" align="left">"#));
+        assert!(dot.contains(r#"  "c<d" [label=<<table"#));
+        assert!(dot.contains(r#"  "a\"b":"x&\"y" -> "c<d":"id" [label="FK_\"q\"", "#));
+    }
 }
@@ -0,0 +1,96 @@
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::window::TimeWindow;
+
+/// Which polls a statistics command looks at. Every query binds the fields
+/// in the same order: `$1` since, `$2` channel, `$3` poll, `$4` server,
+/// `$5` until.
+#[derive(Debug, Clone, Copy)]
+pub struct PollScope {
+    /// Inclusive start of the window
+    pub since: DateTime<Utc>,
+    /// Exclusive end of the window
+    pub until: DateTime<Utc>,
+    pub server_id: Option<Uuid>,
+    pub channel_id: Option<Uuid>,
+    pub poll_id: Option<Uuid>,
+}
+
+impl PollScope {
+    /// Every poll created within `window`, across every server.
+    pub fn new(window: &TimeWindow) -> Self {
+        Self {
+            since: window.since,
+            until: window.until,
+            server_id: None,
+            channel_id: None,
+            poll_id: None,
+        }
+    }
+
+    /// The window of the same length immediately before this one.
+    pub fn previous(&self) -> Self {
+        Self {
+            since: self.since - (self.until - self.since),
+            until: self.since,
+            ..*self
+        }
+    }
+}
+
+/// Human-readable names for the server and channel a command is scoped to.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopeNames {
+    pub server_name: Option<String>,
+    pub channel_name: Option<String>,
+}
+
+impl ScopeNames {
+    pub fn describe(&self) -> Option<String> {
+        match (&self.server_name, &self.channel_name) {
+            (Some(server), Some(channel)) => Some(format!("#{} in {}", channel, server)),
+            (Some(server), None) => Some(format!("server {}", server)),
+            (None, Some(channel)) => Some(format!("#{}", channel)),
+            (None, None) => None,
+        }
+    }
+}
+
+#[derive(Debug, FromRow)]
+struct ServerRef {
+    id: Uuid,
+    name: String,
+}
+
+/// Resolves a `--server-id` value, which may be a server UUID or its slug.
+pub async fn resolve_server(pool: &PgPool, id_or_slug: &str) -> Result<(Uuid, String)> {
+    let server: Option<ServerRef> = sqlx::query_as(
+        r#"
+        SELECT id, name
+        FROM server
+        WHERE id::text = $1 OR slug = $1
+        LIMIT 1
+        "#,
+    )
+    .bind(id_or_slug)
+    .fetch_optional(pool)
+    .await?;
+
+    match server {
+        Some(ServerRef { id, name }) => Ok((id, name)),
+        None => bail!("No server found with ID or slug '{}'", id_or_slug),
+    }
+}
+
+pub async fn resolve_channel_name(pool: &PgPool, channel_id: Uuid) -> Result<Option<String>> {
+    let name = sqlx::query_scalar(r#"SELECT name FROM channel WHERE id = $1"#)
+        .bind(channel_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(name)
+}
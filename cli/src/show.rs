@@ -0,0 +1,572 @@
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::decision::{ProposalRow, VoteTally, fetch_proposal, now_naive_utc};
+use crate::output::{OutputFormat, Record, Report, render};
+use crate::tally::print_tally;
+use crate::utils::{color_stage, color_vote, format_channel, format_timestamp};
+
+pub async fn run_poll_show(pool: &PgPool, poll_id: Uuid, format: OutputFormat) -> Result<()> {
+    let details_query = sqlx::query_as::<_, PollDetails>(
+        r#"
+        SELECT p."pollType"::text AS poll_type,
+               p."updatedAt" AS updated_at,
+               p."userId" AS author_id,
+               u.name AS author_name
+        FROM poll p
+        JOIN "user" u ON u.id = p."userId"
+        WHERE p.id = $1
+        "#,
+    )
+    .bind(poll_id)
+    .fetch_optional(pool);
+
+    let votes_query = sqlx::query_as::<_, VoteRow>(
+        r#"
+        SELECT v."userId" AS user_id,
+               u.name AS user_name,
+               v."voteType"::text AS vote_type,
+               v."createdAt" AS created_at,
+               v."updatedAt" AS updated_at
+        FROM vote v
+        JOIN "user" u ON u.id = v."userId"
+        WHERE v."pollId" = $1
+        ORDER BY v."createdAt"
+        "#,
+    )
+    .bind(poll_id)
+    .fetch_all(pool);
+
+    let action_query = sqlx::query_as::<_, ActionRow>(
+        r#"
+        SELECT pa."actionType"::text AS action_type,
+               r.id AS role_id,
+               r.name AS role_name,
+               r.color AS role_color,
+               r."prevName" AS prev_name,
+               r."prevColor" AS prev_color,
+               r."serverRoleId" AS server_role_id,
+               sr.name AS server_role_name
+        FROM poll_action pa
+        LEFT JOIN poll_action_role r ON r."pollActionId" = pa.id
+        LEFT JOIN server_role sr ON sr.id = r."serverRoleId"
+        WHERE pa."pollId" = $1
+        "#,
+    )
+    .bind(poll_id)
+    .fetch_optional(pool);
+
+    let (proposal, details, votes, action) = tokio::try_join!(
+        fetch_proposal(pool, poll_id),
+        async { Ok(details_query.await?) },
+        async { Ok(votes_query.await?) },
+        async { Ok(action_query.await?) },
+    )?;
+    let (Some(proposal), Some(details)) = (proposal, details) else {
+        return Err(anyhow!("No poll found with ID {}", poll_id));
+    };
+
+    let action = match action {
+        Some(action) => Some(collect_action(pool, action).await?),
+        None => None,
+    };
+
+    let votes: Vec<PollVote> = votes.into_iter().map(PollVote::from).collect();
+    let timeline = build_timeline(&proposal, &details, &votes);
+
+    let report = PollShowReport {
+        poll_id,
+        poll_type: details.poll_type,
+        stage: proposal.stage.clone(),
+        channel_id: proposal.channel_id,
+        channel_name: proposal.channel_name.clone(),
+        server_name: proposal.server_name.clone(),
+        author_id: details.author_id,
+        author_name: details.author_name,
+        created_at: proposal.created_at.and_utc(),
+        closing_at: proposal.closing_at.map(|closing_at| closing_at.and_utc()),
+        decision_making_model: proposal.decision_making_model.clone(),
+        member_count: proposal.member_count,
+        tally: proposal.tally(),
+        timeline,
+        votes,
+        action,
+        proposal,
+    };
+
+    render(&report, format)
+}
+
+async fn collect_action(pool: &PgPool, action: ActionRow) -> Result<PollActionSummary> {
+    let Some(role_id) = action.role_id else {
+        return Ok(PollActionSummary {
+            action_type: action.action_type,
+            role: None,
+        });
+    };
+
+    let permissions_query = sqlx::query_as::<_, PermissionChange>(
+        r#"
+        SELECT action::text AS action,
+               subject::text AS subject,
+               "changeType"::text AS change_type
+        FROM poll_action_permission
+        WHERE "pollActionRoleId" = $1
+        ORDER BY subject, action
+        "#,
+    )
+    .bind(role_id)
+    .fetch_all(pool);
+
+    let members_query = sqlx::query_as::<_, MemberChange>(
+        r#"
+        SELECT m."userId" AS user_id,
+               u.name AS user_name,
+               m."changeType"::text AS change_type
+        FROM poll_action_role_member m
+        JOIN "user" u ON u.id = m."userId"
+        WHERE m."pollActionRoleId" = $1
+        ORDER BY m."changeType", u.name
+        "#,
+    )
+    .bind(role_id)
+    .fetch_all(pool);
+
+    let (permissions, members) = tokio::try_join!(permissions_query, members_query)?;
+
+    Ok(PollActionSummary {
+        action_type: action.action_type,
+        role: Some(RoleChange {
+            name: action.role_name,
+            color: action.role_color,
+            prev_name: action.prev_name,
+            prev_color: action.prev_color,
+            server_role_id: action.server_role_id,
+            server_role_name: action.server_role_name,
+            permissions,
+            members,
+        }),
+    })
+}
+
+/// Orders everything known about the poll by time. Polls keep only their
+/// current stage, so the move out of `voting` is placed at `updatedAt`,
+/// capped at `closingAt` for polls closed by the lazy sync.
+fn build_timeline(
+    proposal: &ProposalRow,
+    details: &PollDetails,
+    votes: &[PollVote],
+) -> Vec<TimelineEvent> {
+    let mut timeline = vec![TimelineEvent {
+        at: proposal.created_at.and_utc(),
+        event: "created",
+        user_name: Some(details.author_name.clone()),
+        detail: format!("created by {}", details.author_name),
+        styled: format!("created by {}", details.author_name.bold()),
+    }];
+
+    for vote in votes {
+        let (detail, styled) = if vote.changed {
+            (
+                format!("{} voted (now {})", vote.user_name, vote.vote_type),
+                format!(
+                    "{} voted {}",
+                    vote.user_name,
+                    format!("(now {})", vote.vote_type).dimmed()
+                ),
+            )
+        } else {
+            (
+                format!("{} voted {}", vote.user_name, vote.vote_type),
+                format!("{} voted {}", vote.user_name, color_vote(&vote.vote_type)),
+            )
+        };
+        timeline.push(TimelineEvent {
+            at: vote.created_at,
+            event: "vote",
+            user_name: Some(vote.user_name.clone()),
+            detail,
+            styled,
+        });
+        if vote.changed {
+            timeline.push(TimelineEvent {
+                at: vote.updated_at,
+                event: "voteChanged",
+                user_name: Some(vote.user_name.clone()),
+                detail: format!(
+                    "{} changed their vote to {}",
+                    vote.user_name, vote.vote_type
+                ),
+                styled: format!(
+                    "{} changed their vote to {}",
+                    vote.user_name,
+                    color_vote(&vote.vote_type)
+                ),
+            });
+        }
+    }
+
+    let now = now_naive_utc();
+    if let Some(closing_at) = proposal.closing_at {
+        let detail = if closing_at > now {
+            "voting closes"
+        } else {
+            "voting closed"
+        };
+        timeline.push(TimelineEvent {
+            at: closing_at.and_utc(),
+            event: "closing",
+            user_name: None,
+            detail: detail.to_string(),
+            styled: detail.bold().to_string(),
+        });
+    }
+
+    if proposal.stage == "voting" {
+        timeline.push(TimelineEvent {
+            at: now.and_utc(),
+            event: "stage",
+            user_name: None,
+            detail: "still voting".to_string(),
+            styled: format!("still {}", color_stage(&proposal.stage)),
+        });
+    } else {
+        let decided_at = match proposal.closing_at {
+            Some(closing_at) => details.updated_at.min(closing_at),
+            None => details.updated_at,
+        };
+        timeline.push(TimelineEvent {
+            at: decided_at.and_utc(),
+            event: "stage",
+            user_name: None,
+            detail: format!("moved to {}", proposal.stage),
+            styled: format!("moved to {}", color_stage(&proposal.stage)),
+        });
+    }
+
+    // Stable, so events at the same instant keep the order they were added in
+    timeline.sort_by_key(|event| event.at);
+    timeline
+}
+
+// ---------------------------------------------------------------------------
+// Rows
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, FromRow)]
+struct PollDetails {
+    poll_type: String,
+    updated_at: NaiveDateTime,
+    author_id: Uuid,
+    author_name: String,
+}
+
+#[derive(Debug, FromRow)]
+struct VoteRow {
+    user_id: Uuid,
+    user_name: String,
+    vote_type: String,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, FromRow)]
+struct ActionRow {
+    action_type: String,
+    role_id: Option<Uuid>,
+    role_name: Option<String>,
+    role_color: Option<String>,
+    prev_name: Option<String>,
+    prev_color: Option<String>,
+    server_role_id: Option<Uuid>,
+    server_role_name: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Report
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PollShowReport {
+    poll_id: Uuid,
+    poll_type: String,
+    stage: String,
+    channel_id: Uuid,
+    channel_name: String,
+    server_name: String,
+    author_id: Uuid,
+    author_name: String,
+    created_at: DateTime<Utc>,
+    closing_at: Option<DateTime<Utc>>,
+    decision_making_model: String,
+    member_count: i64,
+    tally: VoteTally,
+    /// Creation, votes, vote changes, the closing deadline and the stage
+    /// change, oldest first
+    timeline: Vec<TimelineEvent>,
+    votes: Vec<PollVote>,
+    /// `None` when no `poll_action` is attached
+    action: Option<PollActionSummary>,
+    #[serde(skip)]
+    proposal: ProposalRow,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TimelineEvent {
+    at: DateTime<Utc>,
+    /// `created`, `vote`, `voteChanged`, `closing` or `stage`; a poll still
+    /// voting gets a `stage` event at the time of the report
+    event: &'static str,
+    user_name: Option<String>,
+    detail: String,
+    /// `detail` coloured for text output
+    #[serde(skip)]
+    styled: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PollVote {
+    user_id: Uuid,
+    user_name: String,
+    /// The current vote; earlier choices are not stored
+    vote_type: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    /// `updatedAt` is later than `createdAt`, so the vote was changed
+    changed: bool,
+}
+
+impl From<VoteRow> for PollVote {
+    fn from(row: VoteRow) -> Self {
+        Self {
+            user_id: row.user_id,
+            user_name: row.user_name,
+            vote_type: row.vote_type,
+            created_at: row.created_at.and_utc(),
+            updated_at: row.updated_at.and_utc(),
+            changed: row.updated_at > row.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PollActionSummary {
+    action_type: String,
+    /// `None` unless the action changes or creates a role
+    role: Option<RoleChange>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RoleChange {
+    name: Option<String>,
+    color: Option<String>,
+    prev_name: Option<String>,
+    prev_color: Option<String>,
+    server_role_id: Option<Uuid>,
+    /// Current name of the server role the action targets
+    server_role_name: Option<String>,
+    permissions: Vec<PermissionChange>,
+    members: Vec<MemberChange>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+struct PermissionChange {
+    action: String,
+    subject: String,
+    change_type: String,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+struct MemberChange {
+    user_id: Uuid,
+    user_name: String,
+    change_type: String,
+}
+
+impl PollActionSummary {
+    fn print(&self) {
+        println!(
+            "\n{} {}",
+            "Action".bold(),
+            format!("({})", self.action_type).dimmed()
+        );
+        let Some(role) = &self.role else {
+            println!("{}", "  no role changes".dimmed());
+            return;
+        };
+
+        let target = role
+            .server_role_name
+            .as_deref()
+            .or(role.prev_name.as_deref())
+            .unwrap_or("new role");
+        println!("  {} role {}", "→".dimmed(), target.bold());
+        if let Some(name) = &role.name {
+            match &role.prev_name {
+                Some(prev_name) if prev_name != name => {
+                    println!("  {} name {} → {}", "→".dimmed(), prev_name, name.bold())
+                }
+                _ => println!("  {} name {}", "→".dimmed(), name.bold()),
+            }
+        }
+        if let Some(color) = &role.color {
+            match &role.prev_color {
+                Some(prev_color) if prev_color != color => {
+                    println!("  {} color {} → {}", "→".dimmed(), prev_color, color.bold())
+                }
+                _ => println!("  {} color {}", "→".dimmed(), color.bold()),
+            }
+        }
+        for permission in &role.permissions {
+            let sign = if permission.change_type == "add" {
+                "+".green().bold().to_string()
+            } else {
+                "-".red().bold().to_string()
+            };
+            println!(
+                "  {} permission {}{} {}",
+                "→".dimmed(),
+                sign,
+                permission.action,
+                permission.subject
+            );
+        }
+        for member in &role.members {
+            let sign = if member.change_type == "add" {
+                "+".green().bold().to_string()
+            } else {
+                "-".red().bold().to_string()
+            };
+            println!("  {} member {}{}", "→".dimmed(), sign, member.user_name);
+        }
+    }
+}
+
+impl Report for PollShowReport {
+    const KIND: &'static str = "poll-show";
+    const SCHEMA_VERSION: u32 = 1;
+
+    fn render_text(&self) {
+        println!(
+            "\n{} {}",
+            format!("Poll {}", self.poll_id).bold().underline(),
+            format!(
+                "({} in {})",
+                self.poll_type,
+                format_channel(&self.channel_name, &self.server_name)
+            )
+            .dimmed()
+        );
+        println!(
+            "{} {}, created by {}, {}, {} members",
+            "•".cyan(),
+            color_stage(&self.stage),
+            self.author_name.bold(),
+            self.decision_making_model,
+            self.member_count
+        );
+
+        println!("\n{}", "Timeline".bold());
+        for event in &self.timeline {
+            let marker = match event.event {
+                "voteChanged" => "↻".yellow().to_string(),
+                "closing" => "◷".cyan().to_string(),
+                "stage" => "■".cyan().to_string(),
+                _ => "●".dimmed().to_string(),
+            };
+            println!(
+                "  {} {} {}",
+                format_timestamp(event.at, "%Y-%m-%d %H:%M").dimmed(),
+                marker,
+                event.styled
+            );
+        }
+
+        if let Some(action) = &self.action {
+            action.print();
+        }
+
+        print_tally(&self.proposal, None);
+    }
+
+    fn records(&self) -> Vec<Record> {
+        let summary = Record::new("summary")
+            .field("pollId", self.poll_id.to_string())
+            .field("pollType", self.poll_type.as_str())
+            .field("stage", self.stage.as_str())
+            .field("channelId", self.channel_id.to_string())
+            .field("channelName", self.channel_name.as_str())
+            .field("serverName", self.server_name.as_str())
+            .field("authorId", self.author_id.to_string())
+            .field("authorName", self.author_name.as_str())
+            .field("createdAt", self.created_at.to_rfc3339())
+            .field(
+                "closingAt",
+                self.closing_at.map(|closing_at| closing_at.to_rfc3339()),
+            )
+            .field("decisionMakingModel", self.decision_making_model.as_str())
+            .field("memberCount", self.member_count)
+            .field("agreements", self.tally.agreements)
+            .field("disagreements", self.tally.disagreements)
+            .field("abstains", self.tally.abstains)
+            .field("blocks", self.tally.blocks)
+            .field(
+                "actionType",
+                self.action
+                    .as_ref()
+                    .map(|action| action.action_type.clone()),
+            );
+
+        let timeline = self.timeline.iter().map(|event| {
+            Record::new("timeline")
+                .field("at", event.at.to_rfc3339())
+                .field("event", event.event)
+                .field("userName", event.user_name.clone())
+                .field("detail", event.detail.as_str())
+        });
+        let votes = self.votes.iter().map(|vote| {
+            Record::new("votes")
+                .field("userId", vote.user_id.to_string())
+                .field("userName", vote.user_name.as_str())
+                .field("voteType", vote.vote_type.as_str())
+                .field("createdAt", vote.created_at.to_rfc3339())
+                .field("updatedAt", vote.updated_at.to_rfc3339())
+                .field("changed", vote.changed)
+        });
+        let role = self.action.as_ref().and_then(|action| action.role.as_ref());
+        let permissions = role.into_iter().flat_map(|role| {
+            role.permissions.iter().map(|permission| {
+                Record::new("actionPermissions")
+                    .field("action", permission.action.as_str())
+                    .field("subject", permission.subject.as_str())
+                    .field("changeType", permission.change_type.as_str())
+            })
+        });
+        let members = role.into_iter().flat_map(|role| {
+            role.members.iter().map(|member| {
+                Record::new("actionMembers")
+                    .field("userId", member.user_id.to_string())
+                    .field("userName", member.user_name.as_str())
+                    .field("changeType", member.change_type.as_str())
+            })
+        });
+
+        std::iter::once(summary)
+            .chain(timeline)
+            .chain(votes)
+            .chain(permissions)
+            .chain(members)
+            .collect()
+    }
+}
@@ -0,0 +1,316 @@
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::cli::RuleOverrides;
+use crate::decision::{PollRules, VoteTally, evaluate, fetch_proposals, now_naive_utc};
+use crate::output::{OutputFormat, Record, Report, render};
+use crate::scope::{PollScope, ScopeNames, resolve_channel_name, resolve_server};
+use crate::utils::{color_stage, format_channel, format_number};
+use crate::window::TimeWindow;
+
+pub async fn run_poll_simulate(
+    pool: &PgPool,
+    window: TimeWindow,
+    server_id: Option<&str>,
+    channel_id: Option<Uuid>,
+    overrides: RuleOverrides,
+    format: OutputFormat,
+) -> Result<()> {
+    if describe_overrides(&overrides).is_empty() {
+        bail!("Provide at least one rule to change, e.g. --agreement-threshold 66");
+    }
+
+    let mut names = ScopeNames::default();
+    let server_id = match server_id {
+        Some(id_or_slug) => {
+            let (id, name) = resolve_server(pool, id_or_slug).await?;
+            names.server_name = Some(name);
+            Some(id)
+        }
+        None => None,
+    };
+    if let Some(channel_id) = channel_id {
+        names.channel_name = resolve_channel_name(pool, channel_id).await?;
+    }
+
+    let scope = PollScope {
+        server_id,
+        channel_id,
+        ..PollScope::new(&window)
+    };
+    let proposals = fetch_proposals(pool, &scope).await?;
+    let now = now_naive_utc();
+
+    let mut report = PollSimulationReport {
+        window_days: window.days(),
+        window,
+        server_id,
+        channel_id,
+        names,
+        overrides,
+        proposals_replayed: 0,
+        ratified_before: 0,
+        ratified_after: 0,
+        changes: Vec::new(),
+    };
+
+    for proposal in &proposals {
+        let Some(current_rules) = proposal.rules() else {
+            continue;
+        };
+        let simulated_rules = apply_overrides(current_rules, &overrides);
+        let tally = proposal.tally();
+
+        // Replay as if voting has concluded so the comparison isolates the
+        // rule change from how much time is left on the clock.
+        let before = evaluate(&current_rules, &tally, proposal.member_count, None, now);
+        let after = evaluate(&simulated_rules, &tally, proposal.member_count, None, now);
+
+        report.proposals_replayed += 1;
+        report.ratified_before += before.ratifiable as i64;
+        report.ratified_after += after.ratifiable as i64;
+
+        if before.ratifiable == after.ratifiable {
+            continue;
+        }
+
+        // Explain whichever side of the comparison fails
+        let reasons = if after.ratifiable {
+            before.reasons
+        } else {
+            after.reasons
+        };
+
+        report.changes.push(SimulatedChange {
+            poll_id: proposal.poll_id,
+            channel_id: proposal.channel_id,
+            channel_name: proposal.channel_name.clone(),
+            server_name: proposal.server_name.clone(),
+            created_at: proposal.created_at.and_utc(),
+            stored_stage: proposal.stage.clone(),
+            current_rules,
+            simulated_rules,
+            tally,
+            member_count: proposal.member_count,
+            ratified_before: before.ratifiable,
+            ratified_after: after.ratifiable,
+            reasons,
+        });
+    }
+
+    render(&report, format)
+}
+
+fn apply_overrides(rules: PollRules, overrides: &RuleOverrides) -> PollRules {
+    PollRules {
+        decision_making_model: overrides.model.unwrap_or(rules.decision_making_model),
+        agreement_threshold: overrides
+            .agreement_threshold
+            .unwrap_or(rules.agreement_threshold),
+        disagreements_limit: overrides
+            .disagreements_limit
+            .unwrap_or(rules.disagreements_limit),
+        abstains_limit: overrides.abstains_limit.unwrap_or(rules.abstains_limit),
+        quorum_enabled: overrides.quorum_enabled.unwrap_or(rules.quorum_enabled),
+        quorum_threshold: overrides.quorum_threshold.unwrap_or(rules.quorum_threshold),
+    }
+}
+
+fn describe_overrides(overrides: &RuleOverrides) -> Vec<String> {
+    let RuleOverrides {
+        model,
+        agreement_threshold,
+        disagreements_limit,
+        abstains_limit,
+        quorum_enabled,
+        quorum_threshold,
+    } = overrides;
+
+    let mut parts = Vec::new();
+    if let Some(model) = model {
+        parts.push(format!("model {}", model.as_str()));
+    }
+    if let Some(value) = agreement_threshold {
+        parts.push(format!("agreement {}%", value));
+    }
+    if let Some(value) = disagreements_limit {
+        parts.push(format!("disagreements limit {}", value));
+    }
+    if let Some(value) = abstains_limit {
+        parts.push(format!("abstains limit {}", value));
+    }
+    if let Some(value) = quorum_enabled {
+        parts.push(format!("quorum {}", if *value { "on" } else { "off" }));
+    }
+    if let Some(value) = quorum_threshold {
+        parts.push(format!("quorum {}%", value));
+    }
+    parts
+}
+
+fn outcome_label(ratified: bool) -> String {
+    if ratified {
+        color_stage("ratified")
+    } else {
+        "not ratified".red().bold().to_string()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Report
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PollSimulationReport {
+    window_days: i64,
+    #[serde(flatten)]
+    window: TimeWindow,
+    server_id: Option<Uuid>,
+    channel_id: Option<Uuid>,
+    #[serde(flatten)]
+    names: ScopeNames,
+    overrides: RuleOverrides,
+    proposals_replayed: i64,
+    ratified_before: i64,
+    ratified_after: i64,
+    changes: Vec<SimulatedChange>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SimulatedChange {
+    poll_id: Uuid,
+    channel_id: Uuid,
+    channel_name: String,
+    server_name: String,
+    created_at: DateTime<Utc>,
+    stored_stage: String,
+    current_rules: PollRules,
+    simulated_rules: PollRules,
+    tally: VoteTally,
+    member_count: i64,
+    ratified_before: bool,
+    ratified_after: bool,
+    reasons: Vec<String>,
+}
+
+impl Report for PollSimulationReport {
+    const KIND: &'static str = "poll-simulate";
+    const SCHEMA_VERSION: u32 = 1;
+
+    fn render_text(&self) {
+        let scope = self
+            .names
+            .describe()
+            .map(|scope| format!(", {}", scope))
+            .unwrap_or_default();
+        println!(
+            "\n{} {}",
+            "Poll Simulation".bold().underline(),
+            format!("(proposals from {}{})", self.window.describe(), scope).dimmed()
+        );
+        println!(
+            "{} applying {}",
+            "•".cyan(),
+            describe_overrides(&self.overrides).join(", ").bold()
+        );
+        println!(
+            "{} {} proposals replayed",
+            "•".cyan(),
+            format_number(self.proposals_replayed).bold()
+        );
+        println!(
+            "{} {} ratified under current rules, {} under simulated rules",
+            "•".cyan(),
+            format_number(self.ratified_before).bold(),
+            format_number(self.ratified_after).bold()
+        );
+
+        if self.changes.is_empty() {
+            println!("\n{}", "No outcomes would change.".green());
+            return;
+        }
+
+        println!("\n{}", "Changed Outcomes".bold());
+        for change in &self.changes {
+            let VoteTally {
+                agreements,
+                disagreements,
+                abstains,
+                blocks,
+            } = change.tally;
+            println!(
+                "{} poll {} in {} — {} → {}",
+                "•".cyan(),
+                change.poll_id,
+                format_channel(&change.channel_name, &change.server_name),
+                outcome_label(change.ratified_before),
+                outcome_label(change.ratified_after)
+            );
+            println!(
+                "  {}",
+                format!(
+                    "currently {}, {} members, {} agree / {} disagree / {} abstain / {} block",
+                    change.stored_stage,
+                    change.member_count,
+                    agreements,
+                    disagreements,
+                    abstains,
+                    blocks
+                )
+                .dimmed()
+            );
+            for reason in &change.reasons {
+                println!("  {} {}", "→".dimmed(), reason);
+            }
+        }
+    }
+
+    fn records(&self) -> Vec<Record> {
+        let summary = Record::new("summary")
+            .field("windowDays", self.window_days)
+            .field("since", self.window.since.to_rfc3339())
+            .field("until", self.window.until.to_rfc3339())
+            .field("serverId", self.server_id.map(|id| id.to_string()))
+            .field("serverName", self.names.server_name.clone())
+            .field("channelId", self.channel_id.map(|id| id.to_string()))
+            .field("channelName", self.names.channel_name.clone())
+            .field("overrides", describe_overrides(&self.overrides).join("; "))
+            .field("proposalsReplayed", self.proposals_replayed)
+            .field("ratifiedBefore", self.ratified_before)
+            .field("ratifiedAfter", self.ratified_after);
+
+        let changes = self.changes.iter().map(|change| {
+            Record::new("changes")
+                .field("pollId", change.poll_id.to_string())
+                .field("channelId", change.channel_id.to_string())
+                .field("channelName", change.channel_name.as_str())
+                .field("serverName", change.server_name.as_str())
+                .field("createdAt", change.created_at.to_rfc3339())
+                .field("storedStage", change.stored_stage.as_str())
+                .field(
+                    "currentModel",
+                    change.current_rules.decision_making_model.as_str(),
+                )
+                .field(
+                    "simulatedModel",
+                    change.simulated_rules.decision_making_model.as_str(),
+                )
+                .field("agreements", change.tally.agreements)
+                .field("disagreements", change.tally.disagreements)
+                .field("abstains", change.tally.abstains)
+                .field("blocks", change.tally.blocks)
+                .field("memberCount", change.member_count)
+                .field("ratifiedBefore", change.ratified_before)
+                .field("ratifiedAfter", change.ratified_after)
+                .field("reasons", change.reasons.join("; "))
+        });
+
+        std::iter::once(summary).chain(changes).collect()
+    }
+}
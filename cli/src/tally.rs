@@ -0,0 +1,150 @@
+use owo_colors::{AnsiColors, OwoColorize};
+
+use crate::chart::bar;
+use crate::decision::{
+    DecisionModel, ProposalRow, VoteTally, evaluate, now_naive_utc, quorum_status, required_count,
+};
+use crate::utils::{color_vote, pct};
+
+/// Width of the vote, quorum and agreement bars.
+const PROGRESS_WIDTH: usize = 30;
+
+/// Prints a proposal's votes, its progress towards the quorum and agreement
+/// thresholds, its limits and whether the votes pass. `previous` adds each
+/// vote type's change since an earlier tally.
+pub fn print_tally(row: &ProposalRow, previous: Option<&VoteTally>) {
+    let now = now_naive_utc();
+    let tally = row.tally();
+
+    // ── Votes ────────────────────────────────────────────────────────
+
+    let rows = [
+        ("agree", tally.agreements, previous.map(|t| t.agreements)),
+        (
+            "disagree",
+            tally.disagreements,
+            previous.map(|t| t.disagreements),
+        ),
+        ("abstain", tally.abstains, previous.map(|t| t.abstains)),
+        ("block", tally.blocks, previous.map(|t| t.blocks)),
+    ];
+    let max = rows.iter().map(|(_, count, _)| *count).max().unwrap_or(0);
+    println!("\n{}", "Votes".bold());
+    for (vote_type, count, before) in rows {
+        let delta = match before.map(|before| count - before) {
+            Some(delta) if delta != 0 => format!(" {:+}", delta),
+            _ => String::new(),
+        };
+        println!(
+            "  {} {}{} {:>4}{} {}",
+            "→".dimmed(),
+            color_vote(vote_type),
+            " ".repeat(9 - vote_type.len()),
+            count.to_string().bold(),
+            format!("{:<4}", delta).yellow().bold(),
+            bar(count, max, PROGRESS_WIDTH).dimmed()
+        );
+    }
+
+    let Some(rules) = row.rules() else {
+        println!(
+            "\n{}",
+            format!(
+                "Unknown decision model '{}', thresholds not shown",
+                row.decision_making_model
+            )
+            .dimmed()
+        );
+        return;
+    };
+
+    // ── Thresholds ───────────────────────────────────────────────────
+
+    println!(
+        "\n{} {}",
+        "Thresholds".bold(),
+        format!("({})", rules.decision_making_model.as_str()).dimmed()
+    );
+    match quorum_status(&rules, &tally, row.member_count) {
+        Some(quorum) => print_progress(
+            "quorum",
+            quorum.votes,
+            quorum.required,
+            format!(
+                "{}% of {} members",
+                rules.quorum_threshold, row.member_count
+            ),
+        ),
+        None => println!(
+            "  {} {:<9} {}",
+            "→".dimmed(),
+            "quorum",
+            "not required".dimmed()
+        ),
+    }
+    if rules.decision_making_model != DecisionModel::Consent {
+        let participants = tally.agreements + tally.disagreements;
+        print_progress(
+            "agreement",
+            tally.agreements,
+            required_count(participants, rules.agreement_threshold).max(1),
+            format!(
+                "{}% of {} agree/disagree votes, {:.1}% so far",
+                rules.agreement_threshold,
+                participants,
+                pct(tally.agreements, participants)
+            ),
+        );
+    }
+    if rules.decision_making_model != DecisionModel::MajorityVote {
+        println!(
+            "  {} {:<9} disagreements {}/{}, abstains {}/{}, blocks {}",
+            "→".dimmed(),
+            "limits",
+            tally.disagreements,
+            rules.disagreements_limit,
+            tally.abstains,
+            rules.abstains_limit,
+            tally.blocks
+        );
+    }
+
+    // Judge the votes as if voting closed now, so the outcome is what a
+    // close at this moment would produce. Decided polls are judged the same
+    // way, which shows whether their final votes support the stored stage
+    let evaluation = evaluate(&rules, &tally, row.member_count, None, now);
+    let (passing, failing) = if row.stage == "voting" {
+        ("Currently passing", "Currently failing")
+    } else {
+        ("Votes pass every rule", "Votes fail")
+    };
+    if evaluation.ratifiable {
+        println!("\n{}", passing.green().bold());
+    } else {
+        println!("\n{}", failing.red().bold());
+        for reason in &evaluation.reasons {
+            println!("  {} {}", "→".dimmed(), reason);
+        }
+    }
+}
+
+fn print_progress(label: &str, value: i64, required: i64, detail: String) {
+    let (color, status) = if value >= required {
+        (AnsiColors::Green, "met")
+    } else {
+        (AnsiColors::Yellow, "not met")
+    };
+    let filled = bar(value.min(required), required, PROGRESS_WIDTH);
+    let empty = "░".repeat(PROGRESS_WIDTH.saturating_sub(filled.chars().count()));
+    println!(
+        "  {} {:<9} {}{} {}/{} {} {}",
+        "→".dimmed(),
+        label,
+        filled.color(color),
+        empty.dimmed(),
+        value,
+        required,
+        status.color(color).bold(),
+        format!("({})", detail).dimmed()
+    );
+}
@@ -0,0 +1,97 @@
+use std::cmp::Reverse;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use owo_colors::OwoColorize;
+
+/// Records how long each query of a report takes, for `--timings`. Queries
+/// run concurrently share the pool, so a timing includes any wait for a free
+/// connection.
+pub struct QueryTimer {
+    started: Instant,
+    timings: Mutex<Vec<QueryTiming>>,
+}
+
+impl QueryTimer {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            timings: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub async fn time<T, E>(
+        &self,
+        name: impl Into<String>,
+        query: impl Future<Output = Result<T, E>>,
+    ) -> Result<T>
+    where
+        anyhow::Error: From<E>,
+    {
+        let start = Instant::now();
+        let result = query.await;
+        if let Ok(mut timings) = self.timings.lock() {
+            timings.push(QueryTiming {
+                name: name.into(),
+                finished: Instant::now(),
+                elapsed: start.elapsed(),
+            });
+        }
+        Ok(result?)
+    }
+
+    /// Prints timings to stderr, slowest first, so machine-readable output on
+    /// stdout is unaffected.
+    pub fn print(&self) {
+        let mut timings = self
+            .timings
+            .lock()
+            .map(|timings| timings.clone())
+            .unwrap_or_default();
+        timings.sort_by_key(|timing| Reverse(timing.elapsed));
+        let total: Duration = timings.iter().map(|timing| timing.elapsed).sum();
+        let wall_clock = timings
+            .iter()
+            .map(|timing| timing.finished - self.started)
+            .max()
+            .unwrap_or_default();
+
+        eprintln!(
+            "\n{} {}",
+            "Query Timings".bold(),
+            format!(
+                "({} queries, {} wall clock, {} summed)",
+                timings.len(),
+                format_millis(wall_clock),
+                format_millis(total)
+            )
+            .dimmed()
+        );
+        let name_width = timings
+            .iter()
+            .map(|timing| timing.name.len())
+            .max()
+            .unwrap_or(0);
+        for QueryTiming { name, elapsed, .. } in &timings {
+            eprintln!(
+                "  {} {:<name_width$} {:>9}",
+                "→".dimmed(),
+                name,
+                format_millis(*elapsed)
+            );
+        }
+    }
+}
+
+#[derive(Clone)]
+struct QueryTiming {
+    name: String,
+    finished: Instant,
+    elapsed: Duration,
+}
+
+fn format_millis(elapsed: Duration) -> String {
+    format!("{:.1}ms", elapsed.as_secs_f64() * 1000.0)
+}
@@ -0,0 +1,373 @@
+use anyhow::Result;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::output::Record;
+use crate::scope::PollScope;
+use crate::timing::QueryTimer;
+use crate::utils::{color_stage, format_channel, format_number, pct, percentile};
+
+/// Voters relative to eligible members. Eligible voters are the members of a
+/// poll's channel, the same denominator the server uses for quorum, and only
+/// votes from current channel members are counted so turnout never exceeds
+/// 100%.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TurnoutStats {
+    pub overall: OverallTurnout,
+    pub median_poll_turnout: Option<f64>,
+    pub by_channel: Vec<ChannelTurnout>,
+    pub by_poll: Vec<PollTurnout>,
+    /// Threshold passed via `--min-turnout`, in percent
+    pub min_turnout: Option<f64>,
+    /// Decided proposals with eligible members whose turnout fell below
+    /// `min_turnout`
+    pub low_turnout: Vec<PollTurnout>,
+}
+
+#[derive(Debug, FromRow, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverallTurnout {
+    pub voters: i64,
+    pub channel_members: i64,
+    pub server_members: i64,
+}
+
+#[derive(Debug, FromRow, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelTurnout {
+    pub channel_id: Uuid,
+    pub channel_name: String,
+    pub server_name: String,
+    pub polls: i64,
+    pub voters: i64,
+    pub channel_members: i64,
+    pub server_members: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PollTurnout {
+    pub poll_id: Uuid,
+    pub channel_id: Uuid,
+    pub channel_name: String,
+    pub server_name: String,
+    pub poll_type: String,
+    pub stage: String,
+    pub created_at: DateTime<Utc>,
+    pub voters: i64,
+    pub eligible: i64,
+    pub turnout: f64,
+}
+
+pub async fn collect_turnout(
+    pool: &PgPool,
+    scope: &PollScope,
+    min_turnout: Option<f64>,
+    timer: &QueryTimer,
+) -> Result<TurnoutStats> {
+    let overall = sqlx::query_as::<_, OverallTurnout>(
+        r#"
+        WITH scoped_polls AS (
+            SELECT id, "channelId"
+            FROM poll
+            WHERE "createdAt" >= $1 AND "createdAt" < $5
+              AND ($2::uuid IS NULL OR "channelId" = $2)
+              AND ($3::uuid IS NULL OR id = $3)
+              AND ($4::uuid IS NULL OR "channelId" IN (SELECT id FROM channel WHERE "serverId" = $4))
+        )
+        SELECT (
+                   SELECT COUNT(DISTINCT v."userId")
+                   FROM scoped_polls sp
+                   JOIN vote v ON v."pollId" = sp.id
+                   JOIN channel_member cm
+                     ON cm."channelId" = sp."channelId" AND cm."userId" = v."userId"
+               )::bigint AS voters,
+               (
+                   SELECT COUNT(DISTINCT cm."userId")
+                   FROM channel_member cm
+                   WHERE cm."channelId" IN (SELECT "channelId" FROM scoped_polls)
+               )::bigint AS channel_members,
+               (
+                   SELECT COUNT(DISTINCT sm."userId")
+                   FROM server_member sm
+                   JOIN channel c ON c."serverId" = sm."serverId"
+                   WHERE c.id IN (SELECT "channelId" FROM scoped_polls)
+               )::bigint AS server_members
+        "#,
+    )
+    .bind(scope.since)
+    .bind(scope.channel_id)
+    .bind(scope.poll_id)
+    .bind(scope.server_id)
+    .bind(scope.until)
+    .fetch_one(pool);
+
+    let by_channel = sqlx::query_as::<_, ChannelTurnout>(
+        r#"
+        WITH scoped_polls AS (
+            SELECT id, "channelId"
+            FROM poll
+            WHERE "createdAt" >= $1 AND "createdAt" < $5
+              AND ($2::uuid IS NULL OR "channelId" = $2)
+              AND ($3::uuid IS NULL OR id = $3)
+              AND ($4::uuid IS NULL OR "channelId" IN (SELECT id FROM channel WHERE "serverId" = $4))
+        ),
+        channel_voters AS (
+            SELECT sp."channelId" AS channel_id,
+                   COUNT(DISTINCT sp.id) AS polls,
+                   COUNT(DISTINCT cm."userId") AS voters
+            FROM scoped_polls sp
+            LEFT JOIN vote v ON v."pollId" = sp.id
+            LEFT JOIN channel_member cm
+              ON cm."channelId" = sp."channelId" AND cm."userId" = v."userId"
+            GROUP BY sp."channelId"
+        )
+        SELECT c.id AS channel_id,
+               c.name AS channel_name,
+               s.name AS server_name,
+               cv.polls::bigint AS polls,
+               cv.voters::bigint AS voters,
+               (
+                   SELECT COUNT(*)
+                   FROM channel_member cm
+                   WHERE cm."channelId" = c.id
+               )::bigint AS channel_members,
+               (
+                   SELECT COUNT(*)
+                   FROM server_member sm
+                   WHERE sm."serverId" = c."serverId"
+               )::bigint AS server_members
+        FROM channel_voters cv
+        JOIN channel c ON c.id = cv.channel_id
+        JOIN server s ON s.id = c."serverId"
+        ORDER BY cv.polls DESC
+        "#,
+    )
+    .bind(scope.since)
+    .bind(scope.channel_id)
+    .bind(scope.poll_id)
+    .bind(scope.server_id)
+    .bind(scope.until)
+    .fetch_all(pool);
+
+    let poll_rows = sqlx::query_as::<_, PollTurnoutRow>(
+        r#"
+        SELECT p.id AS poll_id,
+               p."channelId" AS channel_id,
+               c.name AS channel_name,
+               s.name AS server_name,
+               p."pollType"::text AS poll_type,
+               p.stage::text AS stage,
+               p."createdAt" AS created_at,
+               COUNT(DISTINCT cm."userId")::bigint AS voters,
+               (
+                   SELECT COUNT(*)
+                   FROM channel_member m
+                   WHERE m."channelId" = p."channelId"
+               )::bigint AS eligible
+        FROM poll p
+        JOIN channel c ON c.id = p."channelId"
+        JOIN server s ON s.id = c."serverId"
+        LEFT JOIN vote v ON v."pollId" = p.id
+        LEFT JOIN channel_member cm
+          ON cm."channelId" = p."channelId" AND cm."userId" = v."userId"
+        WHERE p."createdAt" >= $1 AND p."createdAt" < $5
+          AND ($2::uuid IS NULL OR p."channelId" = $2)
+          AND ($3::uuid IS NULL OR p.id = $3)
+          AND ($4::uuid IS NULL OR c."serverId" = $4)
+        GROUP BY p.id, c.id, s.id
+        ORDER BY p."createdAt" DESC
+        "#,
+    )
+    .bind(scope.since)
+    .bind(scope.channel_id)
+    .bind(scope.poll_id)
+    .bind(scope.server_id)
+    .bind(scope.until)
+    .fetch_all(pool);
+
+    let (overall, by_channel, poll_rows) = tokio::try_join!(
+        timer.time("turnout overall", overall),
+        timer.time("turnout by channel", by_channel),
+        timer.time("turnout by poll", poll_rows),
+    )?;
+
+    let by_poll: Vec<PollTurnout> = poll_rows
+        .into_iter()
+        .map(|row| PollTurnout {
+            turnout: pct(row.voters, row.eligible),
+            poll_id: row.poll_id,
+            channel_id: row.channel_id,
+            channel_name: row.channel_name,
+            server_name: row.server_name,
+            poll_type: row.poll_type,
+            stage: row.stage,
+            created_at: row.created_at.and_utc(),
+            voters: row.voters,
+            eligible: row.eligible,
+        })
+        .collect();
+
+    let mut turnouts: Vec<f64> = by_poll
+        .iter()
+        .filter(|poll| poll.eligible > 0)
+        .map(|poll| poll.turnout)
+        .collect();
+    turnouts.sort_by(f64::total_cmp);
+
+    let mut low_turnout: Vec<PollTurnout> = match min_turnout {
+        Some(min) => by_poll
+            .iter()
+            .filter(|poll| {
+                poll.poll_type == "proposal"
+                    && poll.stage != "voting"
+                    && poll.eligible > 0
+                    && poll.turnout < min
+            })
+            .cloned()
+            .collect(),
+        None => Vec::new(),
+    };
+    low_turnout.sort_by(|a, b| a.turnout.total_cmp(&b.turnout));
+
+    Ok(TurnoutStats {
+        overall,
+        median_poll_turnout: percentile(&turnouts, 50.0),
+        by_channel,
+        by_poll,
+        min_turnout,
+        low_turnout,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Rendering
+// ---------------------------------------------------------------------------
+
+pub fn print_turnout(stats: &TurnoutStats) {
+    let OverallTurnout {
+        voters,
+        channel_members,
+        server_members,
+    } = stats.overall;
+
+    if channel_members > 0 {
+        println!(
+            "{} {} of {} eligible channel members voted ({:.1}%), {:.1}% of {} server members",
+            "•".cyan(),
+            format_number(voters).bold(),
+            format_number(channel_members).bold(),
+            pct(voters, channel_members),
+            pct(voters, server_members),
+            format_number(server_members)
+        );
+    }
+    if let Some(median) = stats.median_poll_turnout {
+        println!(
+            "{} median turnout per poll",
+            format!("{:.1}%", median).green().bold()
+        );
+    }
+
+    if stats.by_channel.len() > 1 {
+        println!("\n{}", "Turnout by Channel".bold());
+        for channel in &stats.by_channel {
+            println!(
+                "{} {} — {}/{} members ({:.1}%), {:.1}% of server, {} polls",
+                "•".cyan(),
+                format_channel(&channel.channel_name, &channel.server_name),
+                channel.voters.to_string().bold(),
+                channel.channel_members,
+                pct(channel.voters, channel.channel_members),
+                pct(channel.voters, channel.server_members),
+                channel.polls
+            );
+        }
+    }
+
+    if let Some(min) = stats.min_turnout {
+        println!(
+            "\n{} {}",
+            "Low Turnout Decisions".bold(),
+            format!("(below {:.1}%)", min).dimmed()
+        );
+        if stats.low_turnout.is_empty() {
+            println!("{}", "  none".dimmed());
+        }
+        for poll in &stats.low_turnout {
+            println!(
+                "{} poll {} in {} — {} with {}/{} members ({:.1}%)",
+                "•".cyan(),
+                poll.poll_id,
+                format_channel(&poll.channel_name, &poll.server_name),
+                color_stage(&poll.stage),
+                poll.voters.to_string().bold(),
+                poll.eligible,
+                poll.turnout
+            );
+        }
+    }
+}
+
+pub fn turnout_records(stats: &TurnoutStats) -> Vec<Record> {
+    let mut records = vec![
+        Record::new("turnout")
+            .field("voters", stats.overall.voters)
+            .field("channelMembers", stats.overall.channel_members)
+            .field("serverMembers", stats.overall.server_members)
+            .field("medianPollTurnout", stats.median_poll_turnout),
+    ];
+
+    for channel in &stats.by_channel {
+        records.push(
+            Record::new("turnoutByChannel")
+                .field("channelId", channel.channel_id.to_string())
+                .field("channelName", channel.channel_name.as_str())
+                .field("serverName", channel.server_name.as_str())
+                .field("polls", channel.polls)
+                .field("voters", channel.voters)
+                .field("channelMembers", channel.channel_members)
+                .field("serverMembers", channel.server_members),
+        );
+    }
+
+    let polls = stats
+        .by_poll
+        .iter()
+        .map(|poll| ("turnoutByPoll", poll))
+        .chain(stats.low_turnout.iter().map(|poll| ("lowTurnout", poll)));
+    for (section, poll) in polls {
+        records.push(
+            Record::new(section)
+                .field("pollId", poll.poll_id.to_string())
+                .field("channelId", poll.channel_id.to_string())
+                .field("channelName", poll.channel_name.as_str())
+                .field("serverName", poll.server_name.as_str())
+                .field("pollType", poll.poll_type.as_str())
+                .field("stage", poll.stage.as_str())
+                .field("createdAt", poll.created_at.to_rfc3339())
+                .field("voters", poll.voters)
+                .field("eligible", poll.eligible)
+                .field("turnout", poll.turnout),
+        );
+    }
+
+    records
+}
+
+#[derive(Debug, FromRow)]
+struct PollTurnoutRow {
+    poll_id: Uuid,
+    channel_id: Uuid,
+    channel_name: String,
+    server_name: String,
+    poll_type: String,
+    stage: String,
+    created_at: NaiveDateTime,
+    voters: i64,
+    eligible: i64,
+}
@@ -0,0 +1,414 @@
+use anyhow::{Result, bail};
+use chrono::NaiveDateTime;
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::output::{
+    OutputFormat, Record, Report, naive_utc_value, render, serialize_naive_utc,
+    serialize_naive_utc_required,
+};
+use crate::utils::{
+    color_stage, color_vote, format_channel, format_number, format_timestamp, humanize, pct,
+};
+use crate::window::TimeWindow;
+
+pub async fn run_user_stats(
+    pool: &PgPool,
+    window: TimeWindow,
+    user_id: Option<Uuid>,
+    name: Option<&str>,
+    format: OutputFormat,
+) -> Result<()> {
+    let user = resolve_user(pool, user_id, name).await?;
+    let report = collect_user_stats(pool, window, user).await?;
+    render(&report, format)
+}
+
+async fn resolve_user(
+    pool: &PgPool,
+    user_id: Option<Uuid>,
+    name: Option<&str>,
+) -> Result<UserProfile> {
+    let user: Option<UserProfile> = sqlx::query_as(
+        r#"
+        SELECT id, name, "displayName" AS display_name, anonymous, "createdAt" AS created_at
+        FROM "user"
+        WHERE id = $1 OR name = $2
+        LIMIT 1
+        "#,
+    )
+    .bind(user_id)
+    .bind(name)
+    .fetch_optional(pool)
+    .await?;
+
+    match (user, user_id) {
+        (Some(user), _) => Ok(user),
+        (None, Some(user_id)) => bail!("No user found with ID {}", user_id),
+        (None, None) => bail!("No user found with name '{}'", name.unwrap_or_default()),
+    }
+}
+
+async fn collect_user_stats(
+    pool: &PgPool,
+    window: TimeWindow,
+    user: UserProfile,
+) -> Result<UserStatsReport> {
+    // ── Proposals authored ───────────────────────────────────────────
+
+    let authored: Vec<StageCount> = sqlx::query_as(
+        r#"
+        SELECT stage::text AS stage, COUNT(*)::bigint AS count
+        FROM poll
+        WHERE "userId" = $1
+          AND "pollType" = 'proposal'
+          AND "createdAt" >= $2 AND "createdAt" < $3
+        GROUP BY stage
+        ORDER BY count DESC
+        "#,
+    )
+    .bind(user.id)
+    .bind(window.since)
+    .bind(window.until)
+    .fetch_all(pool)
+    .await?;
+
+    let proposals_authored = authored.iter().map(|stage| stage.count).sum();
+
+    // ── Votes cast ───────────────────────────────────────────────────
+
+    let vote_mix: Vec<VoteTypeCount> = sqlx::query_as(
+        r#"
+        SELECT "voteType"::text AS vote_type, COUNT(*)::bigint AS count
+        FROM vote
+        WHERE "userId" = $1
+          AND "createdAt" >= $2 AND "createdAt" < $3
+        GROUP BY "voteType"
+        ORDER BY count DESC
+        "#,
+    )
+    .bind(user.id)
+    .bind(window.since)
+    .bind(window.until)
+    .fetch_all(pool)
+    .await?;
+
+    let total_votes = vote_mix.iter().map(|vote| vote.count).sum();
+    let blocks = vote_mix
+        .iter()
+        .filter(|vote| vote.vote_type == "block")
+        .map(|vote| vote.count)
+        .sum();
+
+    // ── Participation in the user's channels ─────────────────────────
+
+    let channels: Vec<ChannelParticipation> = sqlx::query_as(
+        r#"
+        SELECT c.id AS channel_id,
+               c.name AS channel_name,
+               s.name AS server_name,
+               COUNT(p.id)::bigint AS polls,
+               COUNT(v.id)::bigint AS voted
+        FROM channel_member cm
+        JOIN channel c ON c.id = cm."channelId"
+        JOIN server s ON s.id = c."serverId"
+        LEFT JOIN poll p
+          ON p."channelId" = c.id
+         AND p."createdAt" >= $2 AND p."createdAt" < $3
+        LEFT JOIN vote v ON v."pollId" = p.id AND v."userId" = cm."userId"
+        WHERE cm."userId" = $1
+        GROUP BY c.id, s.id
+        ORDER BY polls DESC, c.name
+        "#,
+    )
+    .bind(user.id)
+    .bind(window.since)
+    .bind(window.until)
+    .fetch_all(pool)
+    .await?;
+
+    let polls_in_channels = channels.iter().map(|channel| channel.polls).sum();
+    let polls_voted = channels.iter().map(|channel| channel.voted).sum();
+
+    // ── Server activity ──────────────────────────────────────────────
+
+    let servers: Vec<ServerActivity> = sqlx::query_as(
+        r#"
+        SELECT s.id AS server_id,
+               s.name AS server_name,
+               sm."createdAt" AS joined_at,
+               sm."lastActiveAt" AS last_active_at
+        FROM server_member sm
+        JOIN server s ON s.id = sm."serverId"
+        WHERE sm."userId" = $1
+        ORDER BY sm."lastActiveAt" DESC NULLS LAST
+        "#,
+    )
+    .bind(user.id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(UserStatsReport {
+        window_days: window.days(),
+        window,
+        user,
+        proposals_authored,
+        authored,
+        total_votes,
+        blocks,
+        vote_mix,
+        polls_in_channels,
+        polls_voted,
+        participation_rate: pct(polls_voted, polls_in_channels),
+        channels,
+        servers,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Report
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UserStatsReport {
+    window_days: i64,
+    #[serde(flatten)]
+    window: TimeWindow,
+    user: UserProfile,
+    proposals_authored: i64,
+    /// Proposals authored in the window, by current stage
+    authored: Vec<StageCount>,
+    total_votes: i64,
+    blocks: i64,
+    vote_mix: Vec<VoteTypeCount>,
+    /// Polls created in the window across the channels the user belongs to
+    polls_in_channels: i64,
+    polls_voted: i64,
+    participation_rate: f64,
+    channels: Vec<ChannelParticipation>,
+    servers: Vec<ServerActivity>,
+}
+
+impl Report for UserStatsReport {
+    const KIND: &'static str = "user-stats";
+    const SCHEMA_VERSION: u32 = 1;
+
+    fn render_text(&self) {
+        println!(
+            "\n{} {}",
+            "User Stats".bold().underline(),
+            format!("({})", self.window.describe()).dimmed()
+        );
+
+        let UserProfile {
+            id,
+            name,
+            display_name,
+            anonymous,
+            created_at,
+        } = &self.user;
+        let display_name = display_name
+            .as_deref()
+            .map(|display_name| format!(" ({})", display_name))
+            .unwrap_or_default();
+        println!(
+            "{} {}{} {}",
+            "•".cyan(),
+            name.bold(),
+            display_name,
+            id.to_string().dimmed()
+        );
+        println!(
+            "  {} joined {}{}",
+            "→".dimmed(),
+            humanize(created_at.and_utc()),
+            if *anonymous { ", anonymous" } else { "" }
+        );
+
+        // ── Proposals authored ───────────────────────────────────────
+
+        println!(
+            "\n{}\n{} {} proposals authored",
+            "Proposals".bold(),
+            "•".cyan(),
+            format_number(self.proposals_authored).bold()
+        );
+        for StageCount { stage, count } in &self.authored {
+            println!(
+                "  {} {} {:>6} ({:>5.1}%)",
+                "→".dimmed(),
+                color_stage(&format!("{:<10}", stage)),
+                count.to_string().bold(),
+                pct(*count, self.proposals_authored)
+            );
+        }
+
+        // ── Votes cast ───────────────────────────────────────────────
+
+        println!(
+            "\n{}\n{} {} votes cast\n{} {} blocks issued",
+            "Votes".bold(),
+            "•".cyan(),
+            format_number(self.total_votes).bold(),
+            "•".cyan(),
+            format_number(self.blocks).bold()
+        );
+        for VoteTypeCount { vote_type, count } in &self.vote_mix {
+            println!(
+                "  {} {} {:>6} ({:>5.1}%)",
+                "→".dimmed(),
+                color_vote(&format!("{:<9}", vote_type)),
+                count.to_string().bold(),
+                pct(*count, self.total_votes)
+            );
+        }
+
+        // ── Participation ────────────────────────────────────────────
+
+        println!(
+            "\n{}\n{} voted on {} of {} polls in their channels",
+            "Participation".bold(),
+            format!("{:.1}%", self.participation_rate).green().bold(),
+            format_number(self.polls_voted).bold(),
+            format_number(self.polls_in_channels).bold()
+        );
+        for channel in &self.channels {
+            println!(
+                "  {} {} — {}/{} polls ({:.1}%)",
+                "→".dimmed(),
+                format_channel(&channel.channel_name, &channel.server_name),
+                channel.voted.to_string().bold(),
+                channel.polls,
+                pct(channel.voted, channel.polls)
+            );
+        }
+
+        // ── Server activity ──────────────────────────────────────────
+
+        if !self.servers.is_empty() {
+            println!("\n{}", "Servers".bold());
+            for server in &self.servers {
+                let last_active = server
+                    .last_active_at
+                    .map(|ts| format!("last active {}", humanize(ts.and_utc())))
+                    .unwrap_or_else(|| "never active".to_string());
+                println!(
+                    "{} {} — {} {}",
+                    "•".cyan(),
+                    server.server_name.bold(),
+                    last_active,
+                    format!(
+                        "(joined {})",
+                        format_timestamp(server.joined_at.and_utc(), "%Y-%m-%d")
+                    )
+                    .dimmed()
+                );
+            }
+        }
+    }
+
+    fn records(&self) -> Vec<Record> {
+        let mut records = vec![
+            Record::new("summary")
+                .field("windowDays", self.window_days)
+                .field("since", self.window.since.to_rfc3339())
+                .field("until", self.window.until.to_rfc3339())
+                .field("userId", self.user.id.to_string())
+                .field("name", self.user.name.as_str())
+                .field("displayName", self.user.display_name.clone())
+                .field("anonymous", self.user.anonymous)
+                .field("createdAt", self.user.created_at.and_utc().to_rfc3339())
+                .field("proposalsAuthored", self.proposals_authored)
+                .field("totalVotes", self.total_votes)
+                .field("blocks", self.blocks)
+                .field("pollsInChannels", self.polls_in_channels)
+                .field("pollsVoted", self.polls_voted)
+                .field("participationRate", self.participation_rate),
+        ];
+
+        for StageCount { stage, count } in &self.authored {
+            records.push(
+                Record::new("authored")
+                    .field("stage", stage.as_str())
+                    .field("count", *count),
+            );
+        }
+        for VoteTypeCount { vote_type, count } in &self.vote_mix {
+            records.push(
+                Record::new("voteMix")
+                    .field("voteType", vote_type.as_str())
+                    .field("count", *count),
+            );
+        }
+        for channel in &self.channels {
+            records.push(
+                Record::new("channels")
+                    .field("channelId", channel.channel_id.to_string())
+                    .field("channelName", channel.channel_name.as_str())
+                    .field("serverName", channel.server_name.as_str())
+                    .field("polls", channel.polls)
+                    .field("voted", channel.voted),
+            );
+        }
+        for server in &self.servers {
+            records.push(
+                Record::new("servers")
+                    .field("serverId", server.server_id.to_string())
+                    .field("serverName", server.server_name.as_str())
+                    .field("joinedAt", server.joined_at.and_utc().to_rfc3339())
+                    .field("lastActiveAt", naive_utc_value(server.last_active_at)),
+            );
+        }
+
+        records
+    }
+}
+
+#[derive(Debug, FromRow, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UserProfile {
+    id: Uuid,
+    name: String,
+    display_name: Option<String>,
+    anonymous: bool,
+    #[serde(serialize_with = "serialize_naive_utc_required")]
+    created_at: NaiveDateTime,
+}
+
+#[derive(Debug, FromRow, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StageCount {
+    stage: String,
+    count: i64,
+}
+
+#[derive(Debug, FromRow, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VoteTypeCount {
+    vote_type: String,
+    count: i64,
+}
+
+#[derive(Debug, FromRow, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChannelParticipation {
+    channel_id: Uuid,
+    channel_name: String,
+    server_name: String,
+    polls: i64,
+    voted: i64,
+}
+
+#[derive(Debug, FromRow, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ServerActivity {
+    server_id: Uuid,
+    server_name: String,
+    #[serde(serialize_with = "serialize_naive_utc_required")]
+    joined_at: NaiveDateTime,
+    #[serde(serialize_with = "serialize_naive_utc")]
+    last_active_at: Option<NaiveDateTime>,
+}
@@ -1,8 +1,14 @@
+use std::sync::OnceLock;
+
+use anyhow::{Result, anyhow};
 use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use owo_colors::OwoColorize;
 
 const MAX_DAYS: i64 = 365 * 5;
 
+static TIMEZONE: OnceLock<Tz> = OnceLock::new();
+
 pub fn normalize_window(days: i64) -> i32 {
     days.clamp(1, MAX_DAYS) as i32
 }
@@ -18,23 +24,27 @@ pub fn format_number(value: i64) -> String {
     format!("{sign}{}", digits.into_iter().collect::<String>())
 }
 
+/// Colors a stage by name. Pad before coloring, e.g.
+/// `color_stage(&format!("{:<10}", stage))`, since escape codes count towards
+/// the width of `{:<10}`.
 pub fn color_stage(stage: &str) -> String {
-    match stage {
+    match stage.trim() {
         "voting" => stage.yellow().bold().to_string(),
         "ratified" => stage.green().bold().to_string(),
         "revision" => stage.blue().bold().to_string(),
         "closed" => stage.magenta().bold().to_string(),
-        other => other.to_string(),
+        _ => stage.to_string(),
     }
 }
 
+/// Colors a vote type by name; pad before coloring as with `color_stage`.
 pub fn color_vote(vote: &str) -> String {
-    match vote {
+    match vote.trim() {
         "agree" => vote.green().bold().to_string(),
         "disagree" => vote.red().bold().to_string(),
         "abstain" => vote.cyan().bold().to_string(),
         "block" => vote.magenta().bold().to_string(),
-        other => other.to_string(),
+        _ => vote.to_string(),
     }
 }
 
@@ -73,3 +83,61 @@ pub fn humanize(ts: DateTime<Utc>) -> String {
         format!("{}d ago", delta.num_days())
     }
 }
+
+/// Parses `--tz`/`TZ` as an IANA zone name. Also accepts the `:Area/City`
+/// and zoneinfo path forms the `TZ` variable allows.
+pub fn parse_timezone(value: &str) -> Result<Tz> {
+    let name = value.trim_start_matches(':');
+    let name = name.rsplit_once("zoneinfo/").map_or(name, |(_, zone)| zone);
+    name.parse().map_err(|_| {
+        anyhow!(
+            "Unknown time zone '{}', expected an IANA name such as Europe/Berlin",
+            value
+        )
+    })
+}
+
+/// Sets the zone used for bucketing and printed timestamps. Called once from
+/// `main` before any report runs.
+pub fn set_timezone(tz: Tz) {
+    let _ = TIMEZONE.set(tz);
+}
+
+/// The reporting time zone, UTC unless `--tz` or `TZ` is set.
+pub fn timezone() -> Tz {
+    TIMEZONE.get().copied().unwrap_or(Tz::UTC)
+}
+
+/// Formats a timestamp in the reporting time zone.
+pub fn format_timestamp(ts: DateTime<Utc>, fmt: &str) -> String {
+    ts.with_timezone(&timezone()).format(fmt).to_string()
+}
+
+/// Linearly interpolated percentile (`p` in 0..=100) of an ascending slice.
+pub fn percentile(sorted: &[f64], p: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (p / 100.0).clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    Some(sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64))
+}
+
+pub fn format_duration(seconds: f64) -> String {
+    let total = seconds.max(0.0).round() as i64;
+    let (days, hours, minutes) = (total / 86_400, total % 86_400 / 3_600, total % 3_600 / 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}s", total)
+    }
+}
+
+pub fn format_channel(channel_name: &str, server_name: &str) -> String {
+    format!("#{} ({})", channel_name, server_name)
+}
@@ -0,0 +1,296 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Utc};
+use owo_colors::OwoColorize;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::decision::{ProposalRow, VoteTally, fetch_proposal, now_naive_utc};
+use crate::scope::PollScope;
+use crate::tally::print_tally;
+use crate::utils::{color_stage, format_channel, format_duration, format_timestamp};
+
+/// Rows listed per kind of change before the rest are summarised.
+const MAX_CHANGE_ROWS: usize = 10;
+
+/// Moves the cursor home and clears the screen so each refresh redraws in
+/// place.
+pub fn clear_screen() -> Result<()> {
+    print!("\x1b[H\x1b[2J");
+    io::stdout().flush()?;
+    Ok(())
+}
+
+/// Waits for the next refresh. Returns `false` when Ctrl-C was pressed
+/// instead, so the caller can stop cleanly.
+pub async fn wait(interval: Duration) -> bool {
+    tokio::select! {
+        _ = tokio::time::sleep(interval) => true,
+        _ = tokio::signal::ctrl_c() => false,
+    }
+}
+
+pub fn print_watch_header(interval: Duration) {
+    println!(
+        "{}",
+        format!(
+            "Watching every {}s, last refresh {}. Press Ctrl-C to stop.",
+            interval.as_secs(),
+            format_timestamp(Utc::now(), "%H:%M:%S")
+        )
+        .dimmed()
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Snapshots
+// ---------------------------------------------------------------------------
+
+/// The state of one poll at a refresh, used to spot what changed.
+#[derive(Debug, Clone, FromRow)]
+pub struct PollState {
+    pub poll_id: Uuid,
+    pub poll_type: String,
+    pub stage: String,
+    pub channel_name: String,
+    pub server_name: String,
+    pub votes: i64,
+}
+
+pub struct Snapshot {
+    pub taken_at: DateTime<Utc>,
+    pub polls: HashMap<Uuid, PollState>,
+}
+
+impl Snapshot {
+    fn new(polls: impl IntoIterator<Item = PollState>) -> Self {
+        Self {
+            taken_at: Utc::now(),
+            polls: polls.into_iter().map(|poll| (poll.poll_id, poll)).collect(),
+        }
+    }
+
+    pub fn from_proposal(row: &ProposalRow) -> Self {
+        Self::new([PollState {
+            poll_id: row.poll_id,
+            poll_type: "proposal".to_string(),
+            stage: row.stage.clone(),
+            channel_name: row.channel_name.clone(),
+            server_name: row.server_name.clone(),
+            votes: row.tally().total(),
+        }])
+    }
+}
+
+pub async fn take_snapshot(pool: &PgPool, scope: &PollScope) -> Result<Snapshot> {
+    let polls: Vec<PollState> = sqlx::query_as(
+        r#"
+        SELECT p.id AS poll_id,
+               COALESCE(p."pollType", 'poll')::text AS poll_type,
+               p.stage::text AS stage,
+               c.name AS channel_name,
+               s.name AS server_name,
+               COUNT(v.id)::bigint AS votes
+        FROM poll p
+        JOIN channel c ON c.id = p."channelId"
+        JOIN server s ON s.id = c."serverId"
+        LEFT JOIN vote v ON v."pollId" = p.id
+        WHERE p."createdAt" >= $1 AND p."createdAt" < $5
+          AND ($2::uuid IS NULL OR p."channelId" = $2)
+          AND ($3::uuid IS NULL OR p.id = $3)
+          AND ($4::uuid IS NULL OR c."serverId" = $4)
+        GROUP BY p.id, c.id, s.id
+        "#,
+    )
+    .bind(scope.since)
+    .bind(scope.channel_id)
+    .bind(scope.poll_id)
+    .bind(scope.server_id)
+    .bind(scope.until)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(Snapshot::new(polls))
+}
+
+/// What happened between two refreshes.
+pub struct Changes {
+    since: DateTime<Utc>,
+    new_polls: Vec<PollState>,
+    /// Current state alongside the stage it left
+    transitions: Vec<(PollState, String)>,
+    /// Polls whose vote count changed, with the difference
+    vote_changes: Vec<(PollState, i64)>,
+}
+
+impl Changes {
+    pub fn between(previous: &Snapshot, current: &Snapshot) -> Self {
+        let mut changes = Self {
+            since: previous.taken_at,
+            new_polls: Vec::new(),
+            transitions: Vec::new(),
+            vote_changes: Vec::new(),
+        };
+        for poll in current.polls.values() {
+            let Some(before) = previous.polls.get(&poll.poll_id) else {
+                changes.new_polls.push(poll.clone());
+                if poll.votes > 0 {
+                    changes.vote_changes.push((poll.clone(), poll.votes));
+                }
+                continue;
+            };
+            if before.stage != poll.stage {
+                changes
+                    .transitions
+                    .push((poll.clone(), before.stage.clone()));
+            }
+            if before.votes != poll.votes {
+                changes
+                    .vote_changes
+                    .push((poll.clone(), poll.votes - before.votes));
+            }
+        }
+        changes
+            .vote_changes
+            .sort_by_key(|(poll, delta)| (-delta, poll.poll_id));
+        changes.new_polls.sort_by_key(|poll| poll.poll_id);
+        changes.transitions.sort_by_key(|(poll, _)| poll.poll_id);
+        changes
+    }
+
+    fn is_empty(&self) -> bool {
+        self.new_polls.is_empty() && self.transitions.is_empty() && self.vote_changes.is_empty()
+    }
+
+    pub fn print(&self) {
+        let since = format_timestamp(self.since, "%H:%M:%S");
+        if self.is_empty() {
+            println!("{}", format!("No changes since {}", since).dimmed());
+            return;
+        }
+
+        let new_votes: i64 = self.vote_changes.iter().map(|(_, delta)| delta).sum();
+        println!(
+            "{} {}",
+            "Changes".bold().yellow(),
+            format!("(since {})", since).dimmed()
+        );
+
+        if !self.vote_changes.is_empty() {
+            println!(
+                "{} {:+} votes on {} polls",
+                "•".cyan(),
+                new_votes.bold().green(),
+                self.vote_changes.len()
+            );
+            for (poll, delta) in self.vote_changes.iter().take(MAX_CHANGE_ROWS) {
+                println!(
+                    "  {} {} {} in {}",
+                    "→".dimmed(),
+                    format!("{:+}", delta).green().bold(),
+                    poll.poll_id,
+                    format_channel(&poll.channel_name, &poll.server_name)
+                );
+            }
+            print_overflow(self.vote_changes.len());
+        }
+
+        for (poll, from) in self.transitions.iter().take(MAX_CHANGE_ROWS) {
+            println!(
+                "{} {} in {} moved {} → {}",
+                "•".cyan(),
+                poll.poll_id,
+                format_channel(&poll.channel_name, &poll.server_name),
+                color_stage(from),
+                color_stage(&poll.stage)
+            );
+        }
+        print_overflow(self.transitions.len());
+
+        for poll in self.new_polls.iter().take(MAX_CHANGE_ROWS) {
+            println!(
+                "{} new {} {} in {}",
+                "•".cyan(),
+                poll.poll_type.bold().yellow(),
+                poll.poll_id,
+                format_channel(&poll.channel_name, &poll.server_name)
+            );
+        }
+        print_overflow(self.new_polls.len());
+    }
+}
+
+fn print_overflow(count: usize) {
+    if count > MAX_CHANGE_ROWS {
+        println!(
+            "  {}",
+            format!("…and {} more", count - MAX_CHANGE_ROWS).dimmed()
+        );
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Live proposal tally
+// ---------------------------------------------------------------------------
+
+/// Redraws one proposal's tally against its quorum and agreement thresholds
+/// until Ctrl-C is pressed.
+pub async fn watch_proposal(pool: &PgPool, poll_id: Uuid, interval: Duration) -> Result<()> {
+    let mut previous: Option<(Snapshot, VoteTally)> = None;
+    loop {
+        let row = fetch_proposal(pool, poll_id)
+            .await?
+            .ok_or_else(|| anyhow!("No proposal found with ID {}", poll_id))?;
+        let snapshot = Snapshot::from_proposal(&row);
+
+        clear_screen()?;
+        print_watch_header(interval);
+        if let Some((previous_snapshot, _)) = &previous {
+            Changes::between(previous_snapshot, &snapshot).print();
+        }
+        print_live_tally(&row, previous.as_ref().map(|(_, tally)| tally));
+
+        previous = Some((snapshot, row.tally()));
+        if !wait(interval).await {
+            return Ok(());
+        }
+    }
+}
+
+fn print_live_tally(row: &ProposalRow, previous: Option<&VoteTally>) {
+    let now = now_naive_utc();
+    let timing = match row.closing_at {
+        Some(closing_at) if closing_at > now => format!(
+            "closes in {} ({})",
+            format_duration((closing_at - now).num_seconds() as f64),
+            format_timestamp(closing_at.and_utc(), "%Y-%m-%d %H:%M %Z")
+        ),
+        Some(closing_at) => format!(
+            "closed {} ago",
+            format_duration((now - closing_at).num_seconds() as f64)
+        ),
+        None => "no closing time".to_string(),
+    };
+
+    println!(
+        "\n{} {}",
+        "Live Tally".bold().underline(),
+        format!(
+            "(poll {} in {})",
+            row.poll_id,
+            format_channel(&row.channel_name, &row.server_name)
+        )
+        .dimmed()
+    );
+    println!(
+        "{} {}, {} members, {}",
+        "•".cyan(),
+        color_stage(&row.stage),
+        row.member_count,
+        timing.dimmed()
+    );
+    print_tally(row, previous);
+}
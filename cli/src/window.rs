@@ -0,0 +1,398 @@
+use std::collections::HashMap;
+
+use anyhow::{Result, bail};
+use chrono::{
+    DateTime, Datelike, Duration, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc,
+};
+use chrono_tz::Tz;
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::utils::timezone;
+
+/// The time range a report covers, either the last `--days` days or an
+/// explicit `--since`/`--until` range.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimeWindow {
+    /// Inclusive start
+    pub since: DateTime<Utc>,
+    /// Exclusive end
+    pub until: DateTime<Utc>,
+    /// Zone that dates and buckets are interpreted in
+    pub timezone: Tz,
+    /// Set when the window is the last N days up to now
+    #[serde(skip)]
+    pub last_days: Option<i32>,
+    /// Set when no `--until` was given, so the window ends at the current time
+    #[serde(skip)]
+    pub until_now: bool,
+}
+
+/// A `--since`/`--until` value before the reporting time zone is known.
+#[derive(Debug, Clone, Copy)]
+pub enum Boundary {
+    /// A calendar day in the reporting time zone
+    Date(NaiveDate),
+    Instant(DateTime<Utc>),
+}
+
+impl TimeWindow {
+    /// `--since` defaults to `days` before `--until`, which defaults to now.
+    /// Dates are local midnights in the reporting time zone, and a date passed
+    /// as `--until` includes that whole day.
+    pub fn resolve(days: i32, since: Option<Boundary>, until: Option<Boundary>) -> Result<Self> {
+        let tz = timezone();
+        let last_days = (since.is_none() && until.is_none()).then_some(days);
+        let until_now = until.is_none();
+        let until = match until {
+            Some(Boundary::Date(date)) => local_midnight(tz, date + Duration::days(1)),
+            Some(Boundary::Instant(ts)) => ts,
+            None => Utc::now(),
+        };
+        let since = match since {
+            Some(Boundary::Date(date)) => local_midnight(tz, date),
+            Some(Boundary::Instant(ts)) => ts,
+            None => until - Duration::days(days as i64),
+        };
+        if since >= until {
+            bail!(
+                "--since ({}) must be before --until ({})",
+                since.with_timezone(&tz).to_rfc3339(),
+                until.with_timezone(&tz).to_rfc3339()
+            );
+        }
+        Ok(Self {
+            since,
+            until,
+            timezone: tz,
+            last_days,
+            until_now,
+        })
+    }
+
+    /// The window as of now, for `--watch`. A window ending now moves forward
+    /// (keeping its length when given as `--days`); explicit ranges stay put.
+    pub fn refreshed(&self) -> Self {
+        if !self.until_now {
+            return *self;
+        }
+        let until = Utc::now();
+        let since = match self.last_days {
+            Some(days) => until - Duration::days(days as i64),
+            None => self.since,
+        };
+        Self {
+            since,
+            until,
+            ..*self
+        }
+    }
+
+    /// Length of the window in days, rounded up.
+    pub fn days(&self) -> i64 {
+        ((self.until - self.since).num_seconds() + 86_399) / 86_400
+    }
+
+    pub fn describe(&self) -> String {
+        let since = self.since.with_timezone(&self.timezone);
+        let until = self.until.with_timezone(&self.timezone);
+        if let Some(days) = self.last_days {
+            return format!("the last {} days since {}", days, since.format("%Y-%m-%d"));
+        }
+        if since.time() == NaiveTime::MIN && until.time() == NaiveTime::MIN {
+            // Whole days, so show the inclusive last day
+            format!(
+                "{} to {}",
+                since.format("%Y-%m-%d"),
+                (until - Duration::days(1)).format("%Y-%m-%d")
+            )
+        } else {
+            format!(
+                "{} to {}",
+                since.format("%Y-%m-%d %H:%M"),
+                until.format("%Y-%m-%d %H:%M %Z")
+            )
+        }
+    }
+}
+
+/// The first instant of `date` in `tz`. Where a DST change skips midnight
+/// (e.g. America/Santiago), the day starts at the first local hour that
+/// exists.
+fn local_midnight(tz: Tz, date: NaiveDate) -> DateTime<Utc> {
+    let midnight: NaiveDateTime = date.and_time(NaiveTime::MIN);
+    (0..=24)
+        .find_map(|hours| {
+            tz.from_local_datetime(&(midnight + Duration::hours(hours)))
+                .earliest()
+        })
+        .expect("no time zone skips more than a day")
+        .with_timezone(&Utc)
+}
+
+/// Parses `--since`/`--until`: a date (YYYY-MM-DD) or an RFC 3339 timestamp.
+pub fn parse_boundary(value: &str) -> Result<Boundary, String> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(Boundary::Date(date));
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|ts| Boundary::Instant(ts.with_timezone(&Utc)))
+        .map_err(|_| format!("'{value}' is not a date (YYYY-MM-DD) or RFC 3339 timestamp"))
+}
+
+// ---------------------------------------------------------------------------
+// Buckets
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Bucket {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl Bucket {
+    /// The `DATE_TRUNC` field name, which also truncates weeks to Monday.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Day => "day",
+            Self::Week => "week",
+            Self::Month => "month",
+        }
+    }
+
+    pub fn truncate(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => date,
+            Self::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            Self::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    fn next(self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => start + Duration::days(1),
+            Self::Week => start + Duration::days(7),
+            Self::Month => start + Months::new(1),
+        }
+    }
+
+    /// Start of every bucket overlapping the window, in order, as local dates
+    /// in the window's time zone.
+    pub fn starts(self, window: &TimeWindow) -> Vec<NaiveDate> {
+        let local_date = |ts: DateTime<Utc>| ts.with_timezone(&window.timezone).date_naive();
+        let first = self.truncate(local_date(window.since));
+        let last = self.truncate(local_date(window.until - Duration::nanoseconds(1)));
+        let mut starts = Vec::new();
+        let mut start = first;
+        while start <= last {
+            starts.push(start);
+            start = self.next(start);
+        }
+        starts
+    }
+
+    pub fn format(self, start: NaiveDate) -> String {
+        match self {
+            Self::Day | Self::Week => start.format("%b %d").to_string(),
+            Self::Month => start.format("%b %Y").to_string(),
+        }
+    }
+}
+
+/// One row per bucket start, taking the row that starts there and `empty` for
+/// buckets without one, so quiet periods show up instead of vanishing.
+pub fn zero_fill<T>(
+    starts: &[NaiveDate],
+    rows: impl IntoIterator<Item = T>,
+    start_of: impl Fn(&T) -> NaiveDate,
+    empty: impl Fn(NaiveDate) -> T,
+) -> Vec<T> {
+    let mut rows: HashMap<NaiveDate, T> =
+        rows.into_iter().map(|row| (start_of(&row), row)).collect();
+    starts
+        .iter()
+        .map(|&start| rows.remove(&start).unwrap_or_else(|| empty(start)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn window(since: &str, until: &str) -> TimeWindow {
+        TimeWindow {
+            since: utc(since),
+            until: utc(until),
+            timezone: Tz::UTC,
+            last_days: None,
+            until_now: false,
+        }
+    }
+
+    #[test]
+    fn parses_dates_and_timestamps() {
+        assert!(matches!(
+            parse_boundary("2026-03-01"),
+            Ok(Boundary::Date(d)) if d == date("2026-03-01")
+        ));
+        assert!(matches!(
+            parse_boundary("2026-03-01T09:30:00+02:00"),
+            Ok(Boundary::Instant(ts)) if ts == utc("2026-03-01T07:30:00Z")
+        ));
+        for value in ["2026-13-01", "March 1", "2026-03-01 09:30"] {
+            assert!(parse_boundary(value).is_err(), "{value}");
+        }
+    }
+
+    #[test]
+    fn resolves_windows() {
+        let cases = [
+            // (since, until, resolved since, resolved until)
+            (
+                Some("2026-03-01"),
+                Some("2026-03-31"),
+                "2026-03-01T00:00:00Z",
+                "2026-04-01T00:00:00Z",
+            ),
+            // A single day covers that whole day
+            (
+                Some("2026-03-01"),
+                Some("2026-03-01"),
+                "2026-03-01T00:00:00Z",
+                "2026-03-02T00:00:00Z",
+            ),
+            // --until without --since goes back --days from the end of that day
+            (
+                None,
+                Some("2026-03-10"),
+                "2026-03-04T00:00:00Z",
+                "2026-03-11T00:00:00Z",
+            ),
+            (
+                Some("2026-03-01T12:00:00Z"),
+                Some("2026-03-02T06:00:00Z"),
+                "2026-03-01T12:00:00Z",
+                "2026-03-02T06:00:00Z",
+            ),
+        ];
+        for (since, until, expected_since, expected_until) in cases {
+            let boundary = |value: Option<&str>| value.map(|value| parse_boundary(value).unwrap());
+            let window = TimeWindow::resolve(7, boundary(since), boundary(until)).unwrap();
+            assert_eq!(window.since, utc(expected_since), "{since:?}..{until:?}");
+            assert_eq!(window.until, utc(expected_until), "{since:?}..{until:?}");
+            assert_eq!(window.last_days, None);
+            assert!(!window.until_now);
+        }
+    }
+
+    #[test]
+    fn since_must_be_before_until() {
+        for (since, until) in [
+            ("2026-03-10", "2026-03-01"),
+            ("2026-03-01T12:00:00Z", "2026-03-01T12:00:00Z"),
+            ("2026-03-02T00:00:00Z", "2026-03-01"),
+        ] {
+            let result = TimeWindow::resolve(
+                7,
+                Some(parse_boundary(since).unwrap()),
+                Some(parse_boundary(until).unwrap()),
+            );
+            assert!(result.is_err(), "{since}..{until}");
+        }
+    }
+
+    #[test]
+    fn truncates_to_bucket_starts() {
+        let cases = [
+            (Bucket::Day, "2026-03-04", "2026-03-04"),
+            // Weeks start on Monday, like `DATE_TRUNC('week', ...)`
+            (Bucket::Week, "2026-03-02", "2026-03-02"),
+            (Bucket::Week, "2026-03-04", "2026-03-02"),
+            (Bucket::Week, "2026-03-08", "2026-03-02"),
+            (Bucket::Week, "2026-01-01", "2025-12-29"),
+            (Bucket::Month, "2026-03-31", "2026-03-01"),
+        ];
+        for (bucket, value, start) in cases {
+            assert_eq!(
+                bucket.truncate(date(value)),
+                date(start),
+                "{bucket:?} {value}"
+            );
+        }
+    }
+
+    #[test]
+    fn lists_every_bucket_overlapping_the_window() {
+        let cases = [
+            (
+                Bucket::Day,
+                window("2026-03-01T00:00:00Z", "2026-03-04T00:00:00Z"),
+                vec!["2026-03-01", "2026-03-02", "2026-03-03"],
+            ),
+            (
+                Bucket::Week,
+                window("2026-03-04T00:00:00Z", "2026-03-18T12:00:00Z"),
+                vec!["2026-03-02", "2026-03-09", "2026-03-16"],
+            ),
+            (
+                Bucket::Month,
+                window("2025-11-15T00:00:00Z", "2026-02-10T00:00:00Z"),
+                vec!["2025-11-01", "2025-12-01", "2026-01-01", "2026-02-01"],
+            ),
+        ];
+        for (bucket, window, starts) in cases {
+            let starts: Vec<NaiveDate> = starts.into_iter().map(date).collect();
+            assert_eq!(bucket.starts(&window), starts, "{bucket:?}");
+        }
+    }
+
+    #[test]
+    fn empty_buckets_are_zero_filled() {
+        let starts = Bucket::Day.starts(&window("2026-03-01T00:00:00Z", "2026-03-05T00:00:00Z"));
+        let rows = vec![(date("2026-03-02"), 4), (date("2026-03-04"), 1)];
+        let filled = zero_fill(&starts, rows, |row| row.0, |start| (start, 0));
+        assert_eq!(
+            filled,
+            vec![
+                (date("2026-03-01"), 0),
+                (date("2026-03-02"), 4),
+                (date("2026-03-03"), 0),
+                (date("2026-03-04"), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn local_midnight_follows_dst_changes() {
+        let new_york: Tz = "America/New_York".parse().unwrap();
+        let santiago: Tz = "America/Santiago".parse().unwrap();
+        let cases = [
+            // Spring forward happens at 02:00, so midnight is still EST
+            (new_york, "2026-03-08", "2026-03-08T05:00:00Z"),
+            (new_york, "2026-03-09", "2026-03-09T04:00:00Z"),
+            // Fall back repeats 01:00, midnight is still EDT
+            (new_york, "2026-11-01", "2026-11-01T04:00:00Z"),
+            (new_york, "2026-11-02", "2026-11-02T05:00:00Z"),
+            // Midnight doesn't exist, so the day starts at 01:00 -03
+            (santiago, "2026-09-06", "2026-09-06T04:00:00Z"),
+            (santiago, "2026-09-05", "2026-09-05T04:00:00Z"),
+        ];
+        for (tz, day, start) in cases {
+            assert_eq!(local_midnight(tz, date(day)), utc(start), "{tz} {day}");
+        }
+    }
+}